@@ -0,0 +1,148 @@
+use std::{borrow::Borrow, fmt::Display, ops::Deref};
+
+use chrono::{DateTime, Utc};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::id_gen::{self, IdGenMethod};
+
+/// 文字列をラップした ID 型を定義するマクロ。<br />
+/// JSON 上ではただの文字列として扱われる。
+macro_rules! define_id {
+    ($($(#[$meta:meta])* $name:ident),* $(,)?) => {
+        $(
+            $(#[$meta])*
+            #[derive(Clone, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+            #[serde(transparent)]
+            pub struct $name(String);
+
+            impl $name {
+                pub fn new(id: impl Into<String>) -> Self {
+                    Self(id.into())
+                }
+
+                pub fn as_str(&self) -> &str {
+                    &self.0
+                }
+
+                pub fn into_string(self) -> String {
+                    self.0
+                }
+
+                /// ID に含まれる作成日時。生成方式を判別できない場合は `None` を返す。
+                pub fn created_at(&self) -> Option<DateTime<Utc>> {
+                    id_gen::parse_date(&self.0)
+                }
+
+                /// 指定した日時に生成されうる最小の ID
+                pub fn min_at(method: IdGenMethod, date: DateTime<Utc>) -> Self {
+                    Self(method.min_id(date))
+                }
+            }
+
+            impl crate::schema::Schema for $name {}
+
+            impl Display for $name {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    f.write_str(&self.0)
+                }
+            }
+
+            impl Deref for $name {
+                type Target = str;
+
+                fn deref(&self) -> &str {
+                    &self.0
+                }
+            }
+
+            impl AsRef<str> for $name {
+                fn as_ref(&self) -> &str {
+                    &self.0
+                }
+            }
+
+            impl Borrow<str> for $name {
+                fn borrow(&self) -> &str {
+                    &self.0
+                }
+            }
+
+            impl From<String> for $name {
+                fn from(value: String) -> Self {
+                    Self(value)
+                }
+            }
+
+            impl From<&String> for $name {
+                fn from(value: &String) -> Self {
+                    Self(value.clone())
+                }
+            }
+
+            impl From<&str> for $name {
+                fn from(value: &str) -> Self {
+                    Self(value.to_string())
+                }
+            }
+
+            impl From<&$name> for $name {
+                fn from(value: &$name) -> Self {
+                    value.clone()
+                }
+            }
+
+            impl From<$name> for String {
+                fn from(value: $name) -> Self {
+                    value.0
+                }
+            }
+
+            impl From<&$name> for String {
+                fn from(value: &$name) -> Self {
+                    value.0.clone()
+                }
+            }
+
+            impl PartialEq<str> for $name {
+                fn eq(&self, other: &str) -> bool {
+                    self.0 == other
+                }
+            }
+
+            impl PartialEq<&str> for $name {
+                fn eq(&self, other: &&str) -> bool {
+                    self.0 == *other
+                }
+            }
+
+            impl PartialEq<String> for $name {
+                fn eq(&self, other: &String) -> bool {
+                    &self.0 == other
+                }
+            }
+        )*
+    };
+}
+
+define_id! {
+    /// ノートの ID
+    NoteId,
+    /// ユーザーの ID
+    UserId,
+    /// チャンネルの ID
+    ChannelId,
+    /// ドライブのファイルの ID
+    DriveFileId,
+    /// ドライブのフォルダーの ID
+    DriveFolderId,
+    /// 通知の ID
+    NotificationId,
+    /// ロールの ID
+    RoleId,
+    /// ページの ID
+    PageId,
+    /// ノートへのリアクションの ID
+    NoteReactionId,
+    /// お気に入りの ID。お気に入りに登録したノートの ID とは異なる。
+    NoteFavoriteId,
+}
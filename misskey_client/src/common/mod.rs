@@ -1,48 +1,109 @@
 //! リクエストとレスポンスの両方に使用する構造体・列挙型
 
 mod charts;
+mod ids;
 mod notes;
 mod notifications;
-
-use std::{fmt::Display, str::FromStr};
+mod reactions;
+mod version;
 
 pub use charts::ChartSpan;
+pub use ids::{ChannelId, DriveFileId, DriveFolderId, NoteFavoriteId, NoteId, NoteReactionId, NotificationId, PageId, RoleId, UserId};
 pub use notes::{NoteVisibility, ReactionAcceptance};
 pub use notifications::NotificationType;
-use serde_derive::{Deserialize, Serialize};
+pub use reactions::Reaction;
+pub use version::ServerVersion;
+
+/// 文字列で表現される列挙型を定義するマクロ。<br />
+/// `Display`、`FromStr`、シリアライズとデシリアライズはすべて同じ対応表を使用する。
+/// 対応表にない値は `Other` として保持されるため、サーバーの更新で値が追加されてもデシリアライズに失敗しない。
+macro_rules! string_enum {
+    ($(#[$meta:meta])* $vis:vis enum $name:ident { $($(#[$variant_meta:meta])* $variant:ident = $value:literal),* $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, Eq, Hash, PartialEq)]
+        $vis enum $name {
+            $($(#[$variant_meta])* $variant,)*
+            /// このクレートが対応していない値
+            Other(String),
+        }
+
+        impl $name {
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(Self::$variant => $value,)*
+                    Self::Other(a) => a,
+                }
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = std::convert::Infallible;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Ok(match s {
+                    $($value => Self::$variant,)*
+                    _ => Self::Other(s.to_string()),
+                })
+            }
+        }
 
-use crate::errors::InvalidEnumString;
+        impl serde::Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: serde::Serializer {
+                serializer.serialize_str(self.as_str())
+            }
+        }
 
-#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub enum StatusVisibility {
-    Followers,
-    Private,
-    Public,
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: serde::Deserializer<'de> {
+                let value = <std::borrow::Cow<'de, str> as serde::Deserialize>::deserialize(deserializer)?;
+                let value = value.parse().unwrap_or_else(|a| match a {});
+                if let Self::Other(a) = &value {
+                    $crate::schema::record_unknown_value(stringify!($name), a);
+                }
+                Ok(value)
+            }
+        }
+
+        impl $crate::schema::Schema for $name {}
+    };
 }
 
-impl FromStr for StatusVisibility {
-    type Err = InvalidEnumString;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        use StatusVisibility::*;
-        Ok(match s {
-            "followers" => Followers,
-            "private" => Private,
-            "public" => Public,
-            _ => return Err(InvalidEnumString)
-        })
+pub(crate) use string_enum;
+
+string_enum! {
+    pub enum StatusVisibility {
+        Followers = "followers",
+        Private = "private",
+        Public = "public",
     }
 }
 
-impl Display for StatusVisibility {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        use StatusVisibility::*;
-        f.write_str(match self {
-            Followers => "followers",
-            Private => "private",
-            Public => "public",
-        })
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::responses::notifications::RoleTarget;
+
+    use super::{NoteVisibility, NotificationType, ReactionAcceptance, StatusVisibility};
+
+    #[test]
+    fn string_enums_round_trip() {
+        for (value, expected) in [("specified", NoteVisibility::Specified), ("circle", NoteVisibility::Other("circle".to_string()))] {
+            assert_eq!(value.parse::<NoteVisibility>(), Ok(expected.clone()));
+            assert_eq!(expected.to_string(), value);
+            assert_eq!(serde_json::to_value(&expected).unwrap(), json!(value));
+            assert_eq!(serde_json::from_value::<NoteVisibility>(json!(value)).unwrap(), expected);
+        }
+        assert_eq!(serde_json::from_value::<NotificationType>(json!("pollEnded")).unwrap(), NotificationType::PollEnded);
+        assert_eq!(serde_json::from_value::<NotificationType>(json!("groupInvited")).unwrap(), NotificationType::Other("groupInvited".to_string()));
+        assert_eq!(serde_json::from_value::<StatusVisibility>(json!("private")).unwrap(), StatusVisibility::Private);
+        assert_eq!(serde_json::from_value::<RoleTarget>(json!("everyone")).unwrap(), RoleTarget::Other("everyone".to_string()));
+        assert_eq!(serde_json::to_value(ReactionAcceptance::LikeOnlyForRemote).unwrap(), json!("likeOnlyForRemote"));
     }
 }
-
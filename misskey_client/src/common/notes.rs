@@ -1,61 +1,39 @@
-use std::{fmt::Display, str::FromStr};
-
-use serde_derive::{Deserialize, Serialize};
-
-use crate::errors::InvalidEnumString;
-
-#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub enum NoteVisibility {
-    Public,
-    Home,
-    Followers,
-    Specified,
-}
-
-impl Default for NoteVisibility {
-    fn default() -> Self {
-        NoteVisibility::Public
+use super::{string_enum, Reaction};
+
+string_enum! {
+    #[derive(Default)]
+    pub enum NoteVisibility {
+        #[default]
+        Public = "public",
+        Home = "home",
+        Followers = "followers",
+        Specified = "specified",
     }
 }
 
-impl Display for NoteVisibility {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        use NoteVisibility::*;
-        f.write_str(match self {
-            Public => "public",
-            Home => "home",
-            Followers => "followers",
-            Specified => "specified",
-        })
+string_enum! {
+    pub enum ReactionAcceptance {
+        /// いいねのみを受け付けます。
+        LikeOnly = "likeOnly",
+        /// リモートユーザーからはいいねのみを受け付けます。
+        LikeOnlyForRemote = "likeOnlyForRemote",
+        /// センシティブフラグの立っていないリアクションのみを受け付けます。
+        NonSensitiveOnly = "nonSensitiveOnly",
+        /// ローカルユーザーからはセンシティブフラグの立っていないリアクションのみを受け付けます。<br />
+        /// リモートユーザーからはいいねのみを受け付けます。
+        NonSensitiveOnlyForLocalLikeOnlyForRemote = "nonSensitiveOnlyForLocalLikeOnlyForRemote",
     }
 }
 
-impl FromStr for NoteVisibility {
-    type Err = InvalidEnumString;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        use NoteVisibility::*;
-        Ok(match s {
-            "public" => Public,
-            "home" => Home,
-            "followers" => Followers,
-            "specified" => Specified,
-            _ => return Err(InvalidEnumString),
-        })
+impl ReactionAcceptance {
+    /// リアクションがそのまま受け付けられるか否か。`remote_user` にはリアクションするユーザーがリモートか否かを指定する。<br />
+    /// Misskey は受け付けないリアクションをいいねに置き換える。
+    /// 絵文字がセンシティブか否かはリアクションからは判別できないため、`NonSensitiveOnly` では常に `true` を返す。
+    pub fn accepts(&self, reaction: &Reaction, remote_user: bool) -> bool {
+        match self {
+            Self::LikeOnly => reaction.is_like(),
+            Self::LikeOnlyForRemote | Self::NonSensitiveOnlyForLocalLikeOnlyForRemote => !remote_user || reaction.is_like(),
+            Self::NonSensitiveOnly | Self::Other(_) => true,
+        }
     }
 }
-
-#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub enum ReactionAcceptance {
-    /// いいねのみを受け付けます。
-    LikeOnly,
-    /// リモートユーザーからはいいねのみを受け付けます。
-    LikeOnlyForRemote,
-    /// センシティブフラグの立っていないリアクションのみを受け付けます。
-    NonSensitiveOnly,
-    /// ローカルユーザーからはセンシティブフラグの立っていないリアクションのみを受け付けます。<br />
-    /// リモートユーザーからはいいねのみを受け付けます。
-    NonSensitiveOnlyForLocalLikeOnlyForRemote,
-}
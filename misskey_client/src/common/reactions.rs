@@ -0,0 +1,177 @@
+use std::{convert::Infallible, fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// ノートへのリアクション。<br />
+/// ローカルのカスタム絵文字は `:name:` と `:name@.:` のどちらからも同じ値になり、Misskey の `reactions` のキーと同じ `:name@.:` の形式で表示される。<br />
+/// 文字列からの変換は失敗せず、解釈できない文字列は `Other` になる。
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Reaction {
+    /// Unicode の絵文字
+    Unicode(String),
+    /// サーバーのカスタム絵文字
+    Local(String),
+    /// 他のサーバーのカスタム絵文字
+    Remote {
+        name: String,
+        host: String,
+    },
+    /// このクレートが解釈できない形式のリアクション
+    Other(String),
+}
+
+impl Reaction {
+    /// `reaction_acceptance` がいいねのみの場合に置き換えられるリアクション
+    pub const LIKE: &'static str = "❤";
+
+    /// いいね (`❤`)
+    pub fn like() -> Self {
+        Self::Unicode(Self::LIKE.to_string())
+    }
+
+    pub fn local(name: impl Into<String>) -> Self {
+        Self::Local(name.into())
+    }
+
+    /// カスタム絵文字の名前。Unicode の絵文字の場合は `None`
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Self::Local(name) | Self::Remote { name, .. } => Some(name),
+            Self::Unicode(_) | Self::Other(_) => None,
+        }
+    }
+
+    /// リモートのカスタム絵文字のホスト
+    pub fn host(&self) -> Option<&str> {
+        match self {
+            Self::Remote { host, .. } => Some(host),
+            _ => None,
+        }
+    }
+
+    pub fn is_custom(&self) -> bool {
+        matches!(self, Self::Local(_) | Self::Remote { .. })
+    }
+
+    /// いいね (`❤` または異体字セレクタ付きの `❤️`) か否か
+    pub fn is_like(&self) -> bool {
+        matches!(self, Self::Unicode(a) if a.trim_end_matches('\u{fe0f}') == Self::LIKE)
+    }
+}
+
+impl Display for Reaction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unicode(a) | Self::Other(a) => f.write_str(a),
+            Self::Local(name) => write!(f, ":{}@.:", name),
+            Self::Remote { name, host } => write!(f, ":{}@{}:", name, host),
+        }
+    }
+}
+
+impl Reaction {
+    /// `:name:`、`:name@.:` または `:name@host:` の形式のカスタム絵文字を解釈する。ホストにはポート番号を含められる。
+    fn parse_custom(s: &str) -> Option<Self> {
+        let inner = s.strip_prefix(':')?.strip_suffix(':')?;
+        let (name, host) = match inner.split_once('@') {
+            Some((name, host)) => (name, Some(host)),
+            None => (inner, None),
+        };
+        if name.is_empty() || !name.chars().all(|a| a.is_ascii_alphanumeric() || matches!(a, '_' | '+' | '-')) {
+            return None;
+        }
+        match host {
+            None | Some(".") => Some(Self::Local(name.to_string())),
+            Some(host) if !host.is_empty() && !host.contains(['@', '/']) => Some(Self::Remote { name: name.to_string(), host: host.to_string() }),
+            Some(_) => None,
+        }
+    }
+}
+
+impl FromStr for Reaction {
+    type Err = Infallible;
+
+    /// `:` で始まるものをカスタム絵文字、それ以外の空白を含まない文字列を Unicode の絵文字として解釈する。
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(reaction) = Self::parse_custom(s) {
+            return Ok(reaction);
+        }
+        match s.is_empty() || s.starts_with(':') || s.contains(char::is_whitespace) {
+            true => Ok(Self::Other(s.to_string())),
+            false => Ok(Self::Unicode(s.to_string())),
+        }
+    }
+}
+
+impl From<&str> for Reaction {
+    fn from(value: &str) -> Self {
+        value.parse().unwrap_or_else(|e| match e {})
+    }
+}
+
+impl From<String> for Reaction {
+    fn from(value: String) -> Self {
+        value.parse().unwrap_or_else(|e| match e {})
+    }
+}
+
+impl Serialize for Reaction {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        serializer.collect_str(self)
+    }
+}
+
+impl crate::schema::Schema for Reaction {}
+
+impl<'de> Deserialize<'de> for Reaction {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        let value = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        let value = value.parse().unwrap_or_else(|e| match e {});
+        if let Self::Other(a) = &value {
+            crate::schema::record_unknown_value("Reaction", a);
+        }
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde_json::json;
+
+    use crate::common::ReactionAcceptance;
+
+    use super::Reaction;
+
+    #[test]
+    fn reaction() {
+        let reaction = |a: &str| a.parse::<Reaction>().unwrap();
+        assert_eq!(reaction("👍"), Reaction::Unicode("👍".to_string()));
+        assert_eq!(reaction(":blobcat:"), Reaction::local("blobcat"));
+        assert_eq!(reaction(":blobcat@.:"), reaction(":blobcat:"));
+        assert_eq!(reaction(":blob_cat+1@misskey.io:"), Reaction::Remote { name: "blob_cat+1".to_string(), host: "misskey.io".to_string() });
+        assert_eq!(reaction(":blobcat:").to_string(), ":blobcat@.:");
+        assert_eq!(reaction(":blobcat@misskey.io:").host(), Some("misskey.io"));
+        assert_eq!(reaction(":blobcat@misskey.test:8080:").host(), Some("misskey.test:8080"));
+        assert!(reaction("❤️").is_like() && Reaction::like().is_like() && !reaction(":heart:").is_like());
+        for other in ["", ":", "::", ":bad name:", ":blobcat@:", ":ブロブ:", "a b"] {
+            assert_eq!(reaction(other), Reaction::Other(other.to_string()));
+            assert_eq!(Reaction::from(other), Reaction::Other(other.to_string()));
+            assert_eq!(reaction(other).to_string(), other);
+            assert!(!reaction(other).is_custom() && reaction(other).name().is_none());
+        }
+
+        let counts = serde_json::from_value::<BTreeMap<Reaction, usize>>(json!({ "👍": 2, ":blobcat@.:": 1, ":blobcat@misskey.io:": 3 })).unwrap();
+        assert_eq!(counts[&Reaction::local("blobcat")], 1);
+        assert_eq!(serde_json::to_value(&counts).unwrap(), json!({ "👍": 2, ":blobcat@.:": 1, ":blobcat@misskey.io:": 3 }));
+        // 解釈できないキーがあってもデシリアライズに失敗しない
+        let counts = serde_json::from_value::<BTreeMap<Reaction, usize>>(json!({ ":ブロブ@misskey.io:": 1 })).unwrap();
+        assert_eq!(counts[&Reaction::Other(":ブロブ@misskey.io:".to_string())], 1);
+
+        assert!(ReactionAcceptance::LikeOnlyForRemote.accepts(&reaction("👍"), false));
+        assert!(!ReactionAcceptance::LikeOnlyForRemote.accepts(&reaction("👍"), true));
+        assert!(!ReactionAcceptance::LikeOnly.accepts(&reaction(":blobcat:"), false));
+        assert!(ReactionAcceptance::NonSensitiveOnly.accepts(&reaction(":blobcat:"), true));
+    }
+}
@@ -0,0 +1,154 @@
+use std::{cmp::Ordering, fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::errors::InvalidVersionString;
+
+/// `2025.4.0` や `13.14.2` のようなサーバーのバージョン。<br />
+/// `-alpha.1`、`-beta.2`、`-rc.1` の接尾辞はプレリリースとして正式版より前に並ぶ。
+/// フォークが付ける `-sharkey` のようなその他の接尾辞と `+` 以降のビルド情報は無視する。
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ServerVersion {
+    major: u32,
+    minor: u32,
+    patch: u32,
+    pre: Option<String>,
+}
+
+impl ServerVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self { major, minor, patch, pre: None }
+    }
+
+    pub fn major(&self) -> u32 {
+        self.major
+    }
+
+    pub fn minor(&self) -> u32 {
+        self.minor
+    }
+
+    pub fn patch(&self) -> u32 {
+        self.patch
+    }
+
+    /// `beta.1` のようなプレリリースの識別子
+    pub fn pre(&self) -> Option<&str> {
+        self.pre.as_deref()
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        self.pre.is_some()
+    }
+}
+
+/// プレリリースの識別子を `.` で区切り、数値は数値として比較する。
+fn compare_pre(a: &str, b: &str) -> Ordering {
+    let mut a = a.split('.');
+    let mut b = b.split('.');
+    loop {
+        let ordering = match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+                (Ok(a), Ok(b)) => a.cmp(&b),
+                (Ok(_), Err(_)) => Ordering::Less,
+                (Err(_), Ok(_)) => Ordering::Greater,
+                (Err(_), Err(_)) => a.cmp(b),
+            },
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+impl Ord for ServerVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch).cmp(&(other.major, other.minor, other.patch)).then_with(|| match (&self.pre, &other.pre) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(_), None) => Ordering::Less,
+            (Some(a), Some(b)) => compare_pre(a, b).then_with(|| a.cmp(b)),
+        })
+    }
+}
+
+impl PartialOrd for ServerVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for ServerVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(pre) = &self.pre {
+            write!(f, "-{}", pre)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ServerVersion {
+    type Err = InvalidVersionString;
+
+    /// 先頭の `v` は省略できる。`minor` と `patch` を省略した場合は `0` として扱う。
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let s = s.strip_prefix('v').unwrap_or(s);
+        let s = s.split_once('+').map_or(s, |a| a.0);
+        let (numbers, suffix) = s.split_once('-').map_or((s, None), |a| (a.0, Some(a.1)));
+        let mut parts = numbers.split('.');
+        let mut next = |required: bool| match parts.next() {
+            Some(a) if !a.is_empty() && a.bytes().all(|a| a.is_ascii_digit()) => a.parse().map_err(|_| InvalidVersionString),
+            None if !required => Ok(0),
+            _ => Err(InvalidVersionString),
+        };
+        let (major, minor, patch) = (next(true)?, next(false)?, next(false)?);
+        if parts.next().is_some() {
+            return Err(InvalidVersionString);
+        }
+        let pre = suffix.filter(|a| ["alpha", "beta", "rc"].iter().any(|b| a.to_ascii_lowercase().starts_with(b))).map(str::to_string);
+        Ok(Self { major, minor, patch, pre })
+    }
+}
+
+impl Serialize for ServerVersion {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        serializer.collect_str(self)
+    }
+}
+
+impl crate::schema::Schema for ServerVersion {}
+
+impl<'de> Deserialize<'de> for ServerVersion {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        let value = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        value.parse().map_err(|_| serde::de::Error::invalid_value(serde::de::Unexpected::Str(&value), &"a version such as 2025.4.0"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::ServerVersion;
+
+    #[test]
+    fn server_version() {
+        let version = |a: &str| a.parse::<ServerVersion>().unwrap();
+        assert_eq!(version("2025.4.0"), ServerVersion::new(2025, 4, 0));
+        assert_eq!(version("v13.14"), ServerVersion::new(13, 14, 0));
+        assert_eq!(version("2024.11.1-sharkey+build.3"), ServerVersion::new(2024, 11, 1));
+        assert_eq!(version("2025.4.0-beta.2").pre(), Some("beta.2"));
+        assert!(version("2025.4.0-beta.2") < version("2025.4.0-beta.10"));
+        assert!(version("2025.4.0-beta.10") < version("2025.4.0-rc.1"));
+        assert!(version("2025.4.0-rc.1") < version("2025.4.0"));
+        assert!(version("2024.11.2") < version("2025.1.0"));
+        assert!("2025.x".parse::<ServerVersion>().is_err());
+        assert!("2025.4.0.1".parse::<ServerVersion>().is_err());
+        assert_eq!(serde_json::to_value(version("2025.4.0-alpha.1")).unwrap(), json!("2025.4.0-alpha.1"));
+    }
+}
@@ -8,10 +8,32 @@ mod sync;
 mod r#async;
 
 impl<T> MisskeyHttpClient<T> {
+    /// リクエストに必要な認証情報と権限があるかを送信前に確認する。
+    fn check_permissions<R>(&self, request: &R) -> MisskeyConnectionResult<()> where R: MisskeyClientRequest {
+        if self.access_token.is_none() {
+            if request.requires_credential() {
+                return Err(MisskeyConnectionError::CredentialRequired { endpoint: request.endpoint().to_string() });
+            }
+            return Ok(());
+        }
+        if let Some(granted) = &self.permissions {
+            let missing = request.permissions().iter().filter(|a| !granted.contains(a)).copied().collect::<Vec<_>>();
+            if !missing.is_empty() {
+                return Err(MisskeyConnectionError::PermissionDenied { endpoint: request.endpoint().to_string(), missing });
+            }
+        }
+        Ok(())
+    }
+
     fn gen_request<R>(&mut self, request: &R) -> MisskeyConnectionResult<Vec<u8>> where R: MisskeyClientRequest {
-        let data = request.body(self.access_token.as_deref()).to_string();
-        let length = data.as_bytes().len();
-        let mut req = Request::post(format!("/api{}", request.endpoint().to_string()))
+        self.check_permissions(request)?;
+        self.check_support(request)?;
+        request.validate().map_err(|reason| MisskeyConnectionError::InvalidRequest { endpoint: request.endpoint().to_string(), reason })?;
+        let data = request.body(self.access_token.as_deref())?.to_string();
+        let length = data.len();
+        let mut req = Request::builder()
+            .method(request.method())
+            .uri(request.path())
             .version(Version::HTTP_11)
             .header(header::ACCEPT_CHARSET, "UTF-8")
             .header(header::ACCEPT_ENCODING, "identity")
@@ -23,7 +45,7 @@ impl<T> MisskeyHttpClient<T> {
         }
         let (parts, body) = req.body(data.into_bytes())?.into_parts();
         Ok(format!("{} {} {:?}\r\n", parts.method, parts.uri, parts.version).bytes()
-        .chain(parts.headers.into_iter().filter_map(|a| a.0.map(|b| (b, a.1))).map(|a| a.0.as_str().bytes().chain(*b": ").chain(a.1.as_bytes().into_iter().map(|a| *a)).chain([b'\r', b'\n']).collect::<Vec<_>>()).flatten())
+        .chain(parts.headers.into_iter().filter_map(|a| a.0.map(|b| (b, a.1))).flat_map(|a| a.0.as_str().bytes().chain(*b": ").chain(a.1.as_bytes().iter().copied()).chain([b'\r', b'\n']).collect::<Vec<_>>()))
         .chain(*b"\r\n")
         .chain(body)
         .collect())
@@ -65,15 +87,27 @@ impl<T> MisskeyHttpClient<T> {
     fn gen_result<R>(&self, request: &R, response: http::response::Builder, body: Vec<u8>) -> MisskeyConnectionResult<Response<Option<R::Response>>> where R: MisskeyClientRequest {
         let body = String::from_utf8(body)?;
         let (parts, _) = response.body(())?.into_parts();
+        // レスポンスの型が `serde_json::Value` などの場合、エラーの本文も解釈できてしまうため、先にステータスを確認する。
+        if !parts.status.is_success() {
+            return Err(match serde_json::from_str::<ServerErrorResponse>(&body) {
+                Ok(a) => Self::server_error(request, parts, a),
+                Err(_) => MisskeyConnectionError::UnexpectedStatus { status: parts.status, endpoint: request.endpoint().to_string(), raw_string: body },
+            });
+        }
         match serde_json::from_str::<R::Response>(&body) {
             Ok(result) => Ok(Response::from_parts(parts, Some(result))),
             Err(_e) if _e.is_eof() && request.can_be_empty() && parts.status == StatusCode::NO_CONTENT => Ok(Response::from_parts(parts, None)),
             Err(_e) => {
                 match serde_json::from_str::<ServerErrorResponse>(&body) {
-                    Ok(a) => Err(a.into()),
+                    Ok(a) => Err(Self::server_error(request, parts, a)),
                     Err(e) => Err(MisskeyConnectionError::SerdeError { parent_error: _e, error: e, raw_string: body }),
                 }
             },
         }
     }
+
+    fn server_error<R>(request: &R, parts: http::response::Parts, response: ServerErrorResponse) -> MisskeyConnectionError where R: MisskeyClientRequest {
+        let request_id = parts.headers.get("x-request-id").and_then(|a| a.to_str().ok()).map(str::to_string);
+        response.error.with_context(parts.status, request.endpoint().to_string(), request_id).into()
+    }
 }
\ No newline at end of file
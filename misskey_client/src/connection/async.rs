@@ -1,21 +1,22 @@
 use http::Response;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
-use crate::{errors::MisskeyConnectionResult, miauth::{MiAuth, MiAuthServerResponse, MiAuthStatus}, MisskeyClientRequest, MisskeyHttpClient};
+use futures_util::{stream, Stream};
+use serde::Deserialize;
+
+use crate::{errors::MisskeyConnectionResult, miauth::{MiAuth, MiAuthServerResponse, MiAuthStatus}, pagination::{PaginatedRequest, Paginator}, requests::{meta::{GetEndpoints, GetMeta}, nodeinfo::{GetNodeInfo, GetNodeInfoLinks}}, server::{self, ServerInfo}, MisskeyClientRequest, MisskeyHttpClient};
 
 impl<T> MisskeyHttpClient<T> where T: AsyncReadExt + AsyncWriteExt + Unpin {
     pub async fn request<R>(&mut self, request: &R) -> MisskeyConnectionResult<Response<Option<R::Response>>> where R: MisskeyClientRequest {
         let request_bin = self.gen_request(request)?;
-        self.stream.write(&request_bin).await?;
+        self.stream.write_all(&request_bin).await?;
         self.stream.flush().await?;
 
         let mut buff = [0; 1];
         let mut result = Vec::new();
         while !result.ends_with(b"\r\n\r\n") {
             let size = self.stream.read(&mut buff).await?;
-            for i in 0..size {
-                result.push(buff[i]);
-            }
+            result.extend_from_slice(&buff[..size]);
         }
 
         let (response, length) = self.gen_header(String::from_utf8_lossy(&result).as_ref())?;
@@ -30,15 +31,49 @@ impl<T> MisskeyHttpClient<T> where T: AsyncReadExt + AsyncWriteExt + Unpin {
 
         self.gen_result(request, response, body)
     }
+
+    /// `/api/meta`、`/api/endpoints` と NodeInfo からサーバーの情報を取得し、以降のリクエストの確認に使用する。<br />
+    /// `/api/meta` 以外の取得に失敗した場合は、その情報を欠いたまま続行する。
+    pub async fn discover(&mut self) -> MisskeyConnectionResult<&ServerInfo> {
+        self.server_info = None;
+        let meta = self.request(&GetMeta::new().detail(true)).await?.into_body().expect("`/meta` never returns an empty response");
+        let endpoints = server::optional(self.request(&GetEndpoints).await)?;
+        let nodeinfo = match server::optional(self.request(&GetNodeInfoLinks).await)?.as_ref().and_then(|a| a.latest()) {
+            Some(link) => server::optional(self.request(&GetNodeInfo::from_link(link)).await)?,
+            None => None,
+        };
+        Ok(self.server_info.insert(ServerInfo::new(meta, endpoints, nodeinfo)))
+    }
 }
 
 impl<T> MiAuth<T> where T: AsyncReadExt + AsyncWriteExt + Unpin {
     pub async fn check(mut self) -> MisskeyConnectionResult<MiAuthStatus<T>> {
         let response = self.client.request(&self.info).await?;
         match response.into_body() {
-            Some(MiAuthServerResponse { ok: true, token: Some(token), user: Some(user) }) => Ok(MiAuthStatus::Succeed(self.client.login(token), user)),
+            Some(MiAuthServerResponse { ok: true, token: Some(token), user: Some(user) }) => Ok(MiAuthStatus::Succeed(self.client.login_with_permissions(token, self.permission), user)),
             Some(MiAuthServerResponse { ok: false, token: None, user: None }) => Ok(MiAuthStatus::Pending(self)),
             _ => Ok(MiAuthStatus::Pending(self)), // TODO 形式に沿わない応答についての検討
         }
     }
 }
+
+impl<'a, T, R> Paginator<'a, T, R> where T: AsyncReadExt + AsyncWriteExt + Unpin, R: PaginatedRequest + 'a, for<'de> R::Item: Deserialize<'de> {
+    /// 要素を 1 件ずつ返す `Stream` に変換する。
+    pub fn into_stream(self) -> impl Stream<Item = MisskeyConnectionResult<R::Item>> + 'a {
+        stream::unfold(self, |mut this| async move {
+            loop {
+                if let Some(item) = this.pop() {
+                    return Some((Ok(item), this));
+                }
+                let request = this.next_request()?;
+                match this.client.request(&request).await {
+                    Ok(response) => this.accept_page(response.into_body().unwrap_or_default()),
+                    Err(e) => {
+                        this.finished = true;
+                        return Some((Err(e), this));
+                    },
+                }
+            }
+        })
+    }
+}
@@ -1,21 +1,21 @@
 use http::Response;
 use std::io::{Read, Write};
 
-use crate::{errors::MisskeyConnectionResult, miauth::{MiAuth, MiAuthServerResponse, MiAuthStatus}, MisskeyClientRequest, MisskeyHttpClient};
+use serde::Deserialize;
+
+use crate::{errors::MisskeyConnectionResult, miauth::{MiAuth, MiAuthServerResponse, MiAuthStatus}, pagination::{PaginatedRequest, Paginator}, requests::{meta::{GetEndpoints, GetMeta}, nodeinfo::{GetNodeInfo, GetNodeInfoLinks}}, server::{self, ServerInfo}, MisskeyClientRequest, MisskeyHttpClient};
 
 impl<T> MisskeyHttpClient<T> where T: Read + Write {
     pub fn request<R>(&mut self, request: &R) -> MisskeyConnectionResult<Response<Option<R::Response>>> where R: MisskeyClientRequest {
         let request_bin = self.gen_request(request)?;
-        self.stream.write(&request_bin)?;
+        self.stream.write_all(&request_bin)?;
         self.stream.flush()?;
 
         let mut buff = [0; 1];
         let mut result = Vec::new();
         while !result.ends_with(b"\r\n\r\n") {
             let size = self.stream.read(&mut buff)?;
-            for i in 0..size {
-                result.push(buff[i]);
-            }
+            result.extend_from_slice(&buff[..size]);
         }
 
         let (response, length) = self.gen_header(String::from_utf8_lossy(&result).as_ref())?;
@@ -30,16 +30,49 @@ impl<T> MisskeyHttpClient<T> where T: Read + Write {
 
         self.gen_result(request, response, body)
     }
+
+    /// `/api/meta`、`/api/endpoints` と NodeInfo からサーバーの情報を取得し、以降のリクエストの確認に使用する。<br />
+    /// `/api/meta` 以外の取得に失敗した場合は、その情報を欠いたまま続行する。
+    pub fn discover(&mut self) -> MisskeyConnectionResult<&ServerInfo> {
+        self.server_info = None;
+        let meta = self.request(&GetMeta::new().detail(true))?.into_body().expect("`/meta` never returns an empty response");
+        let endpoints = server::optional(self.request(&GetEndpoints))?;
+        let nodeinfo = match server::optional(self.request(&GetNodeInfoLinks))?.as_ref().and_then(|a| a.latest()) {
+            Some(link) => server::optional(self.request(&GetNodeInfo::from_link(link)))?,
+            None => None,
+        };
+        Ok(self.server_info.insert(ServerInfo::new(meta, endpoints, nodeinfo)))
+    }
 }
 
 impl<T> MiAuth<T> where T: Read + Write {
     pub fn check(mut self) -> MisskeyConnectionResult<MiAuthStatus<T>> {
         let response = self.client.request(&self.info)?;
         match response.into_body() {
-            Some(MiAuthServerResponse { ok: true, token: Some(token), user: Some(user) }) => Ok(MiAuthStatus::Succeed(self.client.login(token), user)),
+            Some(MiAuthServerResponse { ok: true, token: Some(token), user: Some(user) }) => Ok(MiAuthStatus::Succeed(self.client.login_with_permissions(token, self.permission), user)),
             Some(MiAuthServerResponse { ok: false, token: None, user: None }) => Ok(MiAuthStatus::Pending(self)),
             // None => Err(),
             _ => Ok(MiAuthStatus::Pending(self)), // TODO 形式に沿わない応答についての検討
         }
     }
 }
+
+impl<T, R> Iterator for Paginator<'_, T, R> where T: Read + Write, R: PaginatedRequest, for<'de> R::Item: Deserialize<'de> {
+    type Item = MisskeyConnectionResult<R::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.pop() {
+                return Some(Ok(item));
+            }
+            let request = self.next_request()?;
+            match self.client.request(&request) {
+                Ok(response) => self.accept_page(response.into_body().unwrap_or_default()),
+                Err(e) => {
+                    self.finished = true;
+                    return Some(Err(e));
+                },
+            }
+        }
+    }
+}
@@ -1,10 +1,11 @@
-use std::{error::Error, fmt::{Debug, Display}, io, string::FromUtf8Error};
+use std::{convert::Infallible, error::Error, fmt::{Debug, Display}, io, str::FromStr, string::FromUtf8Error};
 
 use derive_getters::Getters;
-use http::uri::InvalidUri;
+use itertools::Itertools;
+use http::{uri::InvalidUri, StatusCode};
 use serde_derive::Deserialize;
 
-use crate::ServerErrorResponse;
+use crate::{common::ServerVersion, miauth::Permission, MisskeyClientRequest, ServerErrorResponse};
 
 pub type MisskeyConnectionResult<T> = Result<T, MisskeyConnectionError>;
 
@@ -31,15 +32,186 @@ pub enum MisskeyConnectionError {
         /// 受け取った文字列
         raw_string: String
     },
+    /// リクエストの本文をシリアル化できなかったとき。
+    SerializeError(serde_json::Error),
+    /// Misskey のエラーとして解釈できない本文で、成功以外のステータスが返ったとき。
+    UnexpectedStatus {
+        status: StatusCode,
+        endpoint: String,
+        /// 受け取った文字列
+        raw_string: String,
+    },
     /// Misskey サーバーからエラーの応答があったとき。
-    ServerResponseError(ServerError),
+    ServerResponseError(Box<ServerError>),
+
+    /// 認証情報が必要なリクエストを、ログインせずに送信しようとしたとき。
+    CredentialRequired {
+        endpoint: String,
+    },
+    /// 許可されていない権限が必要なリクエストを送信しようとしたとき。
+    PermissionDenied {
+        endpoint: String,
+        /// 不足している権限
+        missing: Vec<Permission>,
+    },
+    /// 取得済みのサーバーの情報から、リクエストがサーバーで使用できないと判断したとき。
+    Unsupported {
+        endpoint: String,
+        reason: UnsupportedReason,
+    },
+    /// サーバーが拒否するパラメーターを、送信前に検出したとき。
+    InvalidRequest {
+        endpoint: String,
+        reason: InvalidRequestReason,
+    },
+}
+
+/// リクエストがサーバーで使用できない理由
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum UnsupportedReason {
+    /// サーバーのバージョンがリクエストの要求より古い。
+    Version {
+        required: ServerVersion,
+        actual: ServerVersion,
+    },
+    /// サーバーがエンドポイントを公開していない。
+    Endpoint,
+}
+
+impl Display for UnsupportedReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnsupportedReason::Version { required, actual } => write!(f, "requires Misskey {} or later, but the server is compatible with {}", required, actual),
+            UnsupportedReason::Endpoint => f.write_str("the server does not provide the endpoint"),
+        }
+    }
 }
 
-impl Error for MisskeyConnectionError {}
+/// リクエストのパラメーターが不正である理由。`param` は `poll.choices` のようなパラメーターの位置
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum InvalidRequestReason {
+    /// いずれかが必要なパラメーターがどれも指定されていない。
+    MissingAnyOf(&'static [&'static str]),
+    /// 空の文字列
+    Empty {
+        param: &'static str,
+    },
+    /// 文字列が長すぎる。長さは Misskey と同じく UTF-16 のコード単位で数える。
+    TooLong {
+        param: &'static str,
+        max: usize,
+        actual: usize,
+    },
+    /// 配列の要素数が範囲外
+    ItemCount {
+        param: &'static str,
+        min: usize,
+        max: usize,
+        actual: usize,
+    },
+    /// 配列の要素が重複している。
+    DuplicatedItems {
+        param: &'static str,
+    },
+    /// 未来でなければならない日時が過去になっている。
+    NotInFuture {
+        param: &'static str,
+    },
+    /// 本文が JSON のオブジェクトにならない。
+    NotAnObject,
+    /// 数値が範囲外
+    OutOfRange {
+        param: &'static str,
+        max: usize,
+        actual: usize,
+    },
+    /// ノートに投票がない。
+    NoPoll,
+    /// 投票が締め切られている。
+    PollExpired,
+    /// 単一選択の投票に投票済みか、複数選択の投票で同じ選択肢に投票済み
+    AlreadyVoted,
+}
+
+impl InvalidRequestReason {
+    pub(crate) fn check_not_empty(param: &'static str, value: &str) -> Result<(), Self> {
+        match value.is_empty() {
+            true => Err(Self::Empty { param }),
+            false => Ok(()),
+        }
+    }
+
+    pub(crate) fn check_length(param: &'static str, value: &str, max: usize) -> Result<(), Self> {
+        let actual = value.encode_utf16().count();
+        match actual > max {
+            true => Err(Self::TooLong { param, max, actual }),
+            false => Ok(()),
+        }
+    }
+
+    pub(crate) fn check_items<T>(param: &'static str, items: &[T], min: usize, max: usize) -> Result<(), Self> where T: PartialEq {
+        if !(min..=max).contains(&items.len()) {
+            return Err(Self::ItemCount { param, min, max, actual: items.len() });
+        }
+        match items.iter().enumerate().any(|(i, a)| items[..i].contains(a)) {
+            true => Err(Self::DuplicatedItems { param }),
+            false => Ok(()),
+        }
+    }
+}
+
+impl Display for InvalidRequestReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvalidRequestReason::MissingAnyOf(params) => write!(f, "one of {} is required", params.iter().join(", ")),
+            InvalidRequestReason::Empty { param } => write!(f, "{} must not be empty", param),
+            InvalidRequestReason::TooLong { param, max, actual } => write!(f, "{} must not be longer than {} characters, but is {}", param, max, actual),
+            InvalidRequestReason::ItemCount { param, min, max, actual } => write!(f, "{} must have {} to {} items, but has {}", param, min, max, actual),
+            InvalidRequestReason::DuplicatedItems { param } => write!(f, "{} must not have duplicated items", param),
+            InvalidRequestReason::NotInFuture { param } => write!(f, "{} must be in the future", param),
+            InvalidRequestReason::NotAnObject => f.write_str("request body must be a JSON object"),
+            InvalidRequestReason::OutOfRange { param, max, actual } => write!(f, "{} must not be greater than {}, but is {}", param, max, actual),
+            InvalidRequestReason::NoPoll => f.write_str("note has no poll"),
+            InvalidRequestReason::PollExpired => f.write_str("poll has already expired"),
+            InvalidRequestReason::AlreadyVoted => f.write_str("already voted on the poll"),
+        }
+    }
+}
+
+impl Error for MisskeyConnectionError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        use MisskeyConnectionError::*;
+        match self {
+            IoError(e) => Some(e),
+            HttpError(e) => Some(e),
+            InvalidUriError(e) => Some(e),
+            InvalidUriPartsError(e) => Some(e),
+            NotUtf8Error(e) => Some(e),
+            SerdeError { parent_error, .. } => Some(parent_error),
+            SerializeError(e) => Some(e),
+            ServerResponseError(e) => Some(e.as_ref()),
+            UnexpectedStatus { .. } | CredentialRequired { .. } | PermissionDenied { .. } | Unsupported { .. } | InvalidRequest { .. } => None,
+        }
+    }
+}
 
 impl Display for MisskeyConnectionError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        use MisskeyConnectionError::*;
+        match self {
+            IoError(_) => f.write_str("failed to communicate with the server"),
+            HttpError(_) => f.write_str("failed to build the HTTP message"),
+            InvalidUriError(_) | InvalidUriPartsError(_) => f.write_str("invalid URI"),
+            NotUtf8Error(_) => f.write_str("response body is not valid UTF-8"),
+            SerdeError { raw_string, .. } => write!(f, "failed to parse the response body: {:?}", raw_string),
+            SerializeError(_) => f.write_str("failed to serialize the request body"),
+            UnexpectedStatus { status, endpoint, raw_string } => write!(f, "{} returned {} with an unexpected body: {:?}", endpoint, status, raw_string),
+            ServerResponseError(e) => write!(f, "server returned an error: {}", e),
+            CredentialRequired { endpoint } => write!(f, "{} requires a credential, but the client is not logged in", endpoint),
+            PermissionDenied { endpoint, missing } => write!(f, "{} requires permissions that are not granted: {}", endpoint, missing.iter().join(", ")),
+            Unsupported { endpoint, reason } => write!(f, "{} is not supported on this server: {}", endpoint, reason),
+            InvalidRequest { endpoint, reason } => write!(f, "invalid request to {}: {}", endpoint, reason),
+        }
     }
 }
 
@@ -75,7 +247,7 @@ impl From<ServerErrorResponse> for MisskeyConnectionError {
 
 impl From<ServerError> for MisskeyConnectionError {
     fn from(value: ServerError) -> Self {
-        Self::ServerResponseError(value)
+        Self::ServerResponseError(Box::new(value))
     }
 }
 
@@ -101,28 +273,278 @@ impl Display for InvalidEnumString {
 
 impl Error for InvalidEnumString {}
 
+/// `YYYY-MM-DD` 形式でない日付
+pub struct InvalidDateString;
+
+impl Debug for InvalidDateString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("invalid date string")
+    }
+}
+
+impl Display for InvalidDateString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(self, f)
+    }
+}
+
+impl Error for InvalidDateString {}
+
+/// `2025.4.0` のような形式でないバージョン
+pub struct InvalidVersionString;
+
+impl Debug for InvalidVersionString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("invalid version string")
+    }
+}
+
+impl Display for InvalidVersionString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(self, f)
+    }
+}
+
+impl Error for InvalidVersionString {}
+
 #[derive(Debug, Getters, Deserialize)]
 pub struct ServerError {
     message: String,
     code: String,
     id: String,
     kind: String,
+    /// エラーの詳細。`INVALID_PARAM` では不正なパラメーターとその理由が格納される。
+    #[serde(default)]
+    info: Option<serde_json::Value>,
+    /// 応答の HTTP ステータスコード
+    #[serde(skip)]
+    status: Option<StatusCode>,
+    /// リクエスト先のエンドポイント
+    #[serde(skip)]
+    endpoint: Option<String>,
+    /// 応答に `X-Request-Id` ヘッダーが含まれていた場合、その値
+    #[serde(skip)]
+    request_id: Option<String>,
+}
+
+impl ServerError {
+    pub(crate) fn with_context(self, status: StatusCode, endpoint: String, request_id: Option<String>) -> Self {
+        Self {
+            status: Some(status),
+            endpoint: Some(endpoint),
+            request_id,
+            .. self
+        }
+    }
+
+    /// `INVALID_PARAM` の詳細を取得する。
+    pub fn invalid_param_info(&self) -> Option<InvalidParamInfo> {
+        if self.code != "INVALID_PARAM" {
+            return None;
+        }
+        self.info.as_ref().and_then(|a| serde_json::from_value(a.clone()).ok())
+    }
+
+    /// エラーコードを共通のエラーの種類として解釈する。
+    pub fn error_type(&self) -> ServerErrorType {
+        self.code.parse().unwrap_or_else(|e| match e {})
+    }
+
+    /// エラーを、リクエスト `R` のエンドポイント固有のエラーを含めて解釈する。<br />
+    /// エンドポイント固有のエラーはエラー ID で判別する。
+    pub fn error_type_for<R>(&self) -> ServerErrorType<R::Error> where R: MisskeyClientRequest {
+        match R::Error::from_id(&self.id) {
+            Some(e) => ServerErrorType::Endpoint(e),
+            None => self.error_type().with_endpoint(),
+        }
+    }
+}
+
+impl Error for ServerError {}
+
+impl Display for ServerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)?;
+        if let Some(info) = self.invalid_param_info() {
+            write!(f, " (param: {}, reason: {})", info.param, info.reason)?;
+        }
+        if let Some(endpoint) = &self.endpoint {
+            write!(f, " [endpoint: {}", endpoint)?;
+            if let Some(status) = self.status {
+                write!(f, ", status: {}", status.as_u16())?;
+            }
+            if let Some(request_id) = &self.request_id {
+                write!(f, ", request id: {}", request_id)?;
+            }
+            f.write_str("]")?;
+        }
+        write!(f, " (id: {})", self.id)
+    }
+}
+
+/// `INVALID_PARAM` のエラーに含まれる詳細
+#[derive(Clone, Debug, Deserialize, Getters)]
+pub struct InvalidParamInfo {
+    /// 不正なパラメーターの位置
+    param: String,
+    /// 不正と判断された理由
+    reason: String,
+}
+
+/// Misskey サーバーから返されるエラーの種類
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ServerErrorType<E = NoEndpointError> {
+    /// サーバーから CREDENTIAL_REQUIRED の応答があったとき。
+    CredentialRequired,
+    /// サーバーから AUTHENTICATION_FAILED の応答があったとき。
+    AuthenticationFailed,
+    /// サーバーから RATE_LIMIT_EXCEEDED の応答があったとき。
+    RateLimitExceeded,
+    /// サーバーから ACCESS_DENIED の応答があったとき。
+    AccessDenied,
+    /// サーバーから PERMISSION_DENIED の応答があったとき。
+    PermissionDenied,
+    /// サーバーから ROLE_PERMISSION_DENIED の応答があったとき。
+    RolePermissionDenied,
+    /// サーバーから YOUR_ACCOUNT_SUSPENDED の応答があったとき。
+    YourAccountSuspended,
+    /// サーバーから YOUR_ACCOUNT_MOVED の応答があったとき。
+    YourAccountMoved,
+    /// サーバーから INVALID_PARAM の応答があったとき。
+    InvalidParam,
+    /// サーバーから INTERNAL_ERROR の応答があったとき。
+    InternalError,
+    /// サーバーから NO_SUCH_ENDPOINT の応答があったとき。
+    NoSuchEndpoint,
+    /// エンドポイント固有のエラー
+    Endpoint(E),
+    /// 上記以外のエラーコード
+    Other(String),
+}
+
+impl ServerErrorType {
+    fn with_endpoint<E>(self) -> ServerErrorType<E> {
+        use ServerErrorType::*;
+        match self {
+            CredentialRequired => CredentialRequired,
+            AuthenticationFailed => AuthenticationFailed,
+            RateLimitExceeded => RateLimitExceeded,
+            AccessDenied => AccessDenied,
+            PermissionDenied => PermissionDenied,
+            RolePermissionDenied => RolePermissionDenied,
+            YourAccountSuspended => YourAccountSuspended,
+            YourAccountMoved => YourAccountMoved,
+            InvalidParam => InvalidParam,
+            InternalError => InternalError,
+            NoSuchEndpoint => NoSuchEndpoint,
+            Endpoint(e) => match e {},
+            Other(code) => Other(code),
+        }
+    }
+}
+
+impl<E> Display for ServerErrorType<E> where E: EndpointError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use ServerErrorType::*;
+        f.write_str(match self {
+            CredentialRequired => "CREDENTIAL_REQUIRED",
+            AuthenticationFailed => "AUTHENTICATION_FAILED",
+            RateLimitExceeded => "RATE_LIMIT_EXCEEDED",
+            AccessDenied => "ACCESS_DENIED",
+            PermissionDenied => "PERMISSION_DENIED",
+            RolePermissionDenied => "ROLE_PERMISSION_DENIED",
+            YourAccountSuspended => "YOUR_ACCOUNT_SUSPENDED",
+            YourAccountMoved => "YOUR_ACCOUNT_MOVED",
+            InvalidParam => "INVALID_PARAM",
+            InternalError => "INTERNAL_ERROR",
+            NoSuchEndpoint => "NO_SUCH_ENDPOINT",
+            Endpoint(e) => e.code(),
+            Other(code) => code,
+        })
+    }
+}
+
+impl FromStr for ServerErrorType {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use ServerErrorType::*;
+        Ok(match s {
+            "CREDENTIAL_REQUIRED" => CredentialRequired,
+            "AUTHENTICATION_FAILED" => AuthenticationFailed,
+            "RATE_LIMIT_EXCEEDED" => RateLimitExceeded,
+            "ACCESS_DENIED" => AccessDenied,
+            "PERMISSION_DENIED" => PermissionDenied,
+            "ROLE_PERMISSION_DENIED" => RolePermissionDenied,
+            "YOUR_ACCOUNT_SUSPENDED" => YourAccountSuspended,
+            "YOUR_ACCOUNT_MOVED" => YourAccountMoved,
+            "INVALID_PARAM" => InvalidParam,
+            "INTERNAL_ERROR" => InternalError,
+            "NO_SUCH_ENDPOINT" => NoSuchEndpoint,
+            _ => Other(s.to_string()),
+        })
+    }
+}
+
+/// エンドポイント固有のエラーを表すトレイト
+pub trait EndpointError: Sized {
+    /// エラー ID に対応する値を返す。
+    fn from_id(id: &str) -> Option<Self>;
+    /// エラーコード
+    fn code(&self) -> &'static str;
+    /// エラー ID
+    fn id(&self) -> &'static str;
+}
+
+/// 固有のエラーを持たないエンドポイントに使用する型
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum NoEndpointError {}
+
+impl EndpointError for NoEndpointError {
+    fn from_id(_: &str) -> Option<Self> {
+        None
+    }
+
+    fn code(&self) -> &'static str {
+        match *self {}
+    }
+
+    fn id(&self) -> &'static str {
+        match *self {}
+    }
+}
+
+/// エンドポイント固有のエラーの列挙型を定義するマクロ。<br />
+/// 各バリアントにはエラーコードとエラー ID を指定する。
+macro_rules! endpoint_error {
+    ($(#[$meta:meta])* $vis:vis enum $name:ident { $($(#[$variant_meta:meta])* $variant:ident = ($code:literal, $id:literal)),* $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+        $vis enum $name {
+            $($(#[$variant_meta])* $variant,)*
+        }
+
+        impl $crate::errors::EndpointError for $name {
+            fn from_id(id: &str) -> Option<Self> {
+                match id {
+                    $($id => Some(Self::$variant),)*
+                    _ => None,
+                }
+            }
+
+            fn code(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $code,)*
+                }
+            }
+
+            fn id(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $id,)*
+                }
+            }
+        }
+    };
 }
 
-// #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-// pub enum ServerErrorType {
-//     /// サーバーから CREDENTIAL_REQUIRED の応答があったとき。
-//     CredentialRequired,
-//     /// サーバーから RATE_LIMIT_EXCEEDED の応答があったとき。
-//     RateLimitExceeded,
-//     /// サーバーから ACCESS_DENIED の応答があったとき。
-//     AccessDenied,
-//     /// サーバーから PERMISSION_DENIED の応答があったとき。
-//     PermissionDenied,
-//     /// サーバーから YOUR_ACCOUNT_SUSPENDED の応答があったとき。
-//     YourAccountSuspended,
-//     /// サーバーから INVALID_PARAM の応答があったとき。
-//     InvalidParam,
-//     /// サーバーから INTERNAL_ERROR の応答があったとき。
-//     InternalError,
-// }
+pub(crate) use endpoint_error;
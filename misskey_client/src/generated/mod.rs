@@ -0,0 +1,7 @@
+// このファイルは misskey_client_codegen によって生成されている。直接編集しないこと。
+
+//! Misskey の OpenAPI 定義から生成した型
+
+pub mod permissions;
+pub mod requests;
+pub mod responses;
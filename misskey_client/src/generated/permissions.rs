@@ -0,0 +1,31 @@
+// このファイルは misskey_client_codegen によって生成されている。直接編集しないこと。
+
+//! エンドポイントごとの認証情報の要否と必要な権限
+
+use crate::miauth::Permission;
+
+/// エンドポイントの認証に関する情報
+#[derive(Clone, Copy, Debug)]
+pub struct EndpointInfo {
+    /// 先頭にスラッシュが付き、`/api` を含まないエンドポイント
+    pub endpoint: &'static str,
+    pub requires_credential: bool,
+    pub permissions: &'static [Permission],
+}
+
+/// エンドポイントの一覧。`endpoint` の昇順に並んでいる。
+pub const ENDPOINTS: &[EndpointInfo] = &[
+    EndpointInfo { endpoint: "/i/favorites", requires_credential: true, permissions: &[Permission::ReadFavorites] },
+    EndpointInfo { endpoint: "/notes/create", requires_credential: true, permissions: &[Permission::WriteNotes] },
+    EndpointInfo { endpoint: "/notes/favorites/create", requires_credential: true, permissions: &[Permission::WriteFavorites] },
+    EndpointInfo { endpoint: "/notes/featured", requires_credential: false, permissions: &[] },
+    EndpointInfo { endpoint: "/notes/reactions", requires_credential: false, permissions: &[] },
+    EndpointInfo { endpoint: "/notes/reactions/create", requires_credential: true, permissions: &[Permission::WriteReactions] },
+    EndpointInfo { endpoint: "/notes/reactions/delete", requires_credential: true, permissions: &[Permission::WriteReactions] },
+    EndpointInfo { endpoint: "/notes/show", requires_credential: false, permissions: &[] },
+];
+
+/// エンドポイントの情報を返す。
+pub fn find(endpoint: &str) -> Option<&'static EndpointInfo> {
+    ENDPOINTS.binary_search_by(|a| a.endpoint.cmp(endpoint)).ok().map(|i| &ENDPOINTS[i])
+}
@@ -0,0 +1,210 @@
+// このファイルは misskey_client_codegen によって生成されている。直接編集しないこと。
+
+use misskey_client_macroes::ConstParamJsonRequest;
+use serde_derive::Serialize;
+
+use crate::{common::{string_enum, ChannelId, DriveFileId, NoteId, UserId}, errors::endpoint_error};
+
+use super::responses::{Note, NoteFavorite, NoteReaction, NotesCreateResponse};
+
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/i/favorites", response = Vec<NoteFavorite>, credential = true, permissions(ReadFavorites), builder)]
+#[serde(rename_all = "camelCase")]
+pub struct IFavorites {
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] limit: Option<i64>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] since_id: Option<String>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] until_id: Option<String>,
+}
+
+endpoint_error! {
+    /// `NotesCreate` 固有のエラー
+    pub enum NotesCreateError {
+        /// Poll is already expired.
+        CannotCreateAlreadyExpiredPoll = ("CANNOT_CREATE_ALREADY_EXPIRED_POLL", "04da457d-b083-4055-9082-955525eda5a5"),
+        /// No such channel.
+        NoSuchChannel = ("NO_SUCH_CHANNEL", "b1653923-5453-4edc-b786-7c4f39bb0bbb"),
+        /// No such renote target.
+        NoSuchRenoteTarget = ("NO_SUCH_RENOTE_TARGET", "b5c90186-4ab0-49c8-9bba-a1f76c282ba4"),
+        /// No such reply target.
+        NoSuchReplyTarget = ("NO_SUCH_REPLY_TARGET", "749ee0f6-d3da-459a-bf02-282e2da4292c"),
+        /// You have been blocked by this user.
+        YouHaveBeenBlocked = ("YOU_HAVE_BEEN_BLOCKED", "b390d7e1-8a5e-46ed-b625-06271cafd3d3"),
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotesCreatePoll {
+    pub choices: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")] pub expired_after: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")] pub expires_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")] pub multiple: Option<bool>,
+}
+
+string_enum! {
+    pub enum NotesCreateReactionAcceptance {
+        LikeOnly = "likeOnly",
+        LikeOnlyForRemote = "likeOnlyForRemote",
+        NonSensitiveOnly = "nonSensitiveOnly",
+        NonSensitiveOnlyForLocalLikeOnlyForRemote = "nonSensitiveOnlyForLocalLikeOnlyForRemote",
+    }
+}
+
+string_enum! {
+    pub enum NotesCreateVisibility {
+        Public = "public",
+        Home = "home",
+        Followers = "followers",
+        Specified = "specified",
+    }
+}
+
+/// Post a new note.
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/create", response = NotesCreateResponse, error = NotesCreateError, credential = true, permissions(WriteNotes), builder)]
+#[serde(rename_all = "camelCase")]
+pub struct NotesCreate {
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] channel_id: Option<ChannelId>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] cw: Option<String>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] file_ids: Option<Vec<DriveFileId>>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] local_only: Option<bool>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] no_extract_emojis: Option<bool>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] no_extract_hashtags: Option<bool>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] no_extract_mentions: Option<bool>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] poll: Option<NotesCreatePoll>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] reaction_acceptance: Option<NotesCreateReactionAcceptance>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] renote_id: Option<NoteId>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] reply_id: Option<NoteId>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] text: Option<String>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] visibility: Option<NotesCreateVisibility>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] visible_user_ids: Option<Vec<UserId>>,
+}
+
+endpoint_error! {
+    /// `NotesFavoritesCreate` 固有のエラー
+    pub enum NotesFavoritesCreateError {
+        /// The note has already been marked as a favorite.
+        AlreadyFavorited = ("ALREADY_FAVORITED", "a402c12b-34dd-41d2-97d8-4d2ffd96a1a6"),
+        /// No such note.
+        NoSuchNote = ("NO_SUCH_NOTE", "6dd26674-e060-4816-909a-45ba3f4da458"),
+    }
+}
+
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/favorites/create", response = (), error = NotesFavoritesCreateError, can_be_empty = true, credential = true, permissions(WriteFavorites), builder)]
+#[serde(rename_all = "camelCase")]
+pub struct NotesFavoritesCreate {
+    #[misskey_client(required, into)]
+    note_id: NoteId,
+}
+
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/featured", response = Vec<Note>, builder)]
+#[serde(rename_all = "camelCase")]
+pub struct NotesFeatured {
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] channel_id: Option<ChannelId>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] limit: Option<i64>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] until_id: Option<String>,
+}
+
+endpoint_error! {
+    /// `NotesReactions` 固有のエラー
+    pub enum NotesReactionsError {
+        /// No such note.
+        NoSuchNote = ("NO_SUCH_NOTE", "263fff3d-d0e1-4af4-bea7-8408059b451a"),
+    }
+}
+
+/// Show the reactions on a note.
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/reactions", response = Vec<NoteReaction>, error = NotesReactionsError, builder)]
+#[serde(rename_all = "camelCase")]
+pub struct NotesReactions {
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] limit: Option<i64>,
+    #[misskey_client(required, into)]
+    note_id: NoteId,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] since_id: Option<String>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] type_: Option<String>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] until_id: Option<String>,
+}
+
+endpoint_error! {
+    /// `NotesReactionsCreate` 固有のエラー
+    pub enum NotesReactionsCreateError {
+        /// You are already reacting to that note.
+        AlreadyReacted = ("ALREADY_REACTED", "71efcf98-86d6-4e2b-b2ad-9d032369366b"),
+        /// No such note.
+        NoSuchNote = ("NO_SUCH_NOTE", "033d0620-5bfe-4027-965d-980b0c85a3ea"),
+        /// You cannot react this note because you have been blocked by this user.
+        YouHaveBeenBlocked = ("YOU_HAVE_BEEN_BLOCKED", "20ef5475-9f38-4e4c-bd33-de6d979498ec"),
+    }
+}
+
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/reactions/create", response = (), error = NotesReactionsCreateError, can_be_empty = true, credential = true, permissions(WriteReactions), builder)]
+#[serde(rename_all = "camelCase")]
+pub struct NotesReactionsCreate {
+    #[misskey_client(required, into)]
+    note_id: NoteId,
+    #[misskey_client(required, into)]
+    reaction: String,
+}
+
+endpoint_error! {
+    /// `NotesReactionsDelete` 固有のエラー
+    pub enum NotesReactionsDeleteError {
+        /// No such note.
+        NoSuchNote = ("NO_SUCH_NOTE", "764d9fce-f9f2-4a0e-92b1-6ceac9a7ad37"),
+        /// You are not reacting to that note.
+        NotReacted = ("NOT_REACTED", "92f4426d-4196-4125-aa5b-02943e2ec8fc"),
+    }
+}
+
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/reactions/delete", response = (), error = NotesReactionsDeleteError, can_be_empty = true, credential = true, permissions(WriteReactions), builder)]
+#[serde(rename_all = "camelCase")]
+pub struct NotesReactionsDelete {
+    #[misskey_client(required, into)]
+    note_id: NoteId,
+}
+
+endpoint_error! {
+    /// `NotesShow` 固有のエラー
+    pub enum NotesShowError {
+        /// No such note.
+        NoSuchNote = ("NO_SUCH_NOTE", "24fcbfc6-2e37-42b6-8388-c29b3861a08d"),
+    }
+}
+
+/// Show a note.
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/show", response = Note, error = NotesShowError, builder)]
+#[serde(rename_all = "camelCase")]
+pub struct NotesShow {
+    #[misskey_client(required, into)]
+    note_id: NoteId,
+}
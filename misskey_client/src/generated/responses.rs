@@ -0,0 +1,140 @@
+// このファイルは misskey_client_codegen によって生成されている。直接編集しないこと。
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use derive_getters::Getters;
+use misskey_client_macroes::Schema;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::common::{string_enum, ChannelId, DriveFileId, NoteId, UserId};
+
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotePollChoice {
+    is_voted: bool,
+    text: String,
+    votes: f64,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
+}
+
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotePoll {
+    choices: Vec<NotePollChoice>,
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "crate::schema::date_time")] expires_at: Option<DateTime<Utc>>,
+    multiple: bool,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
+}
+
+string_enum! {
+    pub enum NoteReactionAcceptance {
+        LikeOnly = "likeOnly",
+        LikeOnlyForRemote = "likeOnlyForRemote",
+        NonSensitiveOnly = "nonSensitiveOnly",
+        NonSensitiveOnlyForLocalLikeOnlyForRemote = "nonSensitiveOnlyForLocalLikeOnlyForRemote",
+    }
+}
+
+string_enum! {
+    pub enum UserLiteOnlineStatus {
+        Unknown = "unknown",
+        Online = "online",
+        Active = "active",
+        Offline = "offline",
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserLite {
+    avatar_blurhash: Option<String>,
+    avatar_url: Option<String>,
+    emojis: BTreeMap<String, String>,
+    /// The local host is represented with `null`.
+    host: Option<String>,
+    id: UserId,
+    #[serde(skip_serializing_if = "Option::is_none")] is_bot: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")] is_cat: Option<bool>,
+    name: Option<String>,
+    online_status: UserLiteOnlineStatus,
+    username: String,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
+}
+
+string_enum! {
+    pub enum NoteVisibility {
+        Public = "public",
+        Home = "home",
+        Followers = "followers",
+        Specified = "specified",
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Note {
+    #[serde(skip_serializing_if = "Option::is_none")] channel_id: Option<ChannelId>,
+    #[serde(serialize_with = "crate::schema::date_time")] created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")] cw: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")] file_ids: Option<Vec<DriveFileId>>,
+    id: NoteId,
+    #[serde(skip_serializing_if = "Option::is_none")] is_hidden: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")] local_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")] mentions: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")] my_reaction: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")] poll: Option<NotePoll>,
+    reaction_acceptance: Option<NoteReactionAcceptance>,
+    reaction_count: f64,
+    reaction_emojis: BTreeMap<String, String>,
+    reactions: BTreeMap<String, f64>,
+    #[serde(skip_serializing_if = "Option::is_none")] renote: Option<Box<Note>>,
+    renote_count: f64,
+    #[serde(skip_serializing_if = "Option::is_none")] renote_id: Option<NoteId>,
+    replies_count: f64,
+    #[serde(skip_serializing_if = "Option::is_none")] reply: Option<Box<Note>>,
+    #[serde(skip_serializing_if = "Option::is_none")] reply_id: Option<NoteId>,
+    #[serde(skip_serializing_if = "Option::is_none")] tags: Option<Vec<String>>,
+    text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")] uri: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")] url: Option<String>,
+    user: UserLite,
+    user_id: UserId,
+    visibility: NoteVisibility,
+    #[serde(skip_serializing_if = "Option::is_none")] visible_user_ids: Option<Vec<UserId>>,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
+}
+
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteFavorite {
+    #[serde(serialize_with = "crate::schema::date_time")] created_at: DateTime<Utc>,
+    id: String,
+    note: Note,
+    note_id: NoteId,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
+}
+
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotesCreateResponse {
+    created_note: Note,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
+}
+
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteReaction {
+    #[serde(serialize_with = "crate::schema::date_time")] created_at: DateTime<Utc>,
+    id: String,
+    type_: String,
+    user: UserLite,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
+}
@@ -0,0 +1,205 @@
+//! Misskey の ID 生成方式の判別と、ID に含まれる作成日時の取得
+
+use std::{fmt::Display, str::FromStr};
+
+use chrono::{DateTime, TimeZone, Utc};
+
+use crate::errors::InvalidEnumString;
+
+/// aid と aidx の時刻の起点 (2000-01-01T00:00:00Z) のミリ秒
+const TIME2000: i64 = 946_684_800_000;
+/// meid の時刻部分に加算される値
+const MEID_OFFSET: i64 = 0x8000_0000_0000;
+const CROCKFORD: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// サーバーの設定 `id` で選択される ID の生成方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IdGenMethod {
+    /// 時刻 8 文字 + 乱数 2 文字の 36 進数
+    Aid,
+    /// 時刻 8 文字 + ノード ID 4 文字 + カウンタ 4 文字の 36 進数
+    Aidx,
+    /// 時刻 12 文字 + 乱数 12 文字の 16 進数。時刻には `0x800000000000` が加算される。
+    Meid,
+    /// `g` + 時刻 11 文字 + 乱数 12 文字の 16 進数
+    Meidg,
+    /// 時刻 10 文字 + 乱数 16 文字の Crockford's Base32
+    Ulid,
+    /// 秒単位の時刻 8 文字 + 乱数 16 文字の 16 進数
+    ObjectId,
+}
+
+impl IdGenMethod {
+    pub const ALL: [IdGenMethod; 6] = [Self::Aid, Self::Aidx, Self::Meid, Self::Meidg, Self::Ulid, Self::ObjectId];
+
+    /// ID の形式から生成方式を推測する。<br />
+    /// meid と ObjectId は同じ形式のため、時刻部分の先頭のビットで区別する。
+    pub fn detect(id: &str) -> Option<Self> {
+        let bytes = id.as_bytes();
+        match bytes.len() {
+            10 if is_base36(bytes) => Some(Self::Aid),
+            16 if is_base36(bytes) => Some(Self::Aidx),
+            24 if bytes[0] == b'g' && is_hex(&bytes[1..]) => Some(Self::Meidg),
+            24 if is_hex(bytes) => match bytes[0] {
+                b'8'..=b'9' | b'a'..=b'f' => Some(Self::Meid),
+                _ => Some(Self::ObjectId),
+            },
+            26 if bytes.iter().all(|a| crockford_value(*a).is_some()) => Some(Self::Ulid),
+            _ => None,
+        }
+    }
+
+    /// この方式で生成された ID として解釈し、作成日時を返す。形式が一致しない場合は `None` を返す。
+    pub fn parse(self, id: &str) -> Option<DateTime<Utc>> {
+        let bytes = id.as_bytes();
+        let millis = match self {
+            Self::Aid | Self::Aidx => {
+                let len = if self == Self::Aid { 10 } else { 16 };
+                if bytes.len() != len || !is_base36(bytes) {
+                    return None;
+                }
+                i64::from_str_radix(&id[..8], 36).ok()? + TIME2000
+            },
+            Self::Meid => {
+                if bytes.len() != 24 || !is_hex(bytes) {
+                    return None;
+                }
+                i64::from_str_radix(&id[..12], 16).ok()? - MEID_OFFSET
+            },
+            Self::Meidg => {
+                if bytes.len() != 24 || bytes[0] != b'g' || !is_hex(&bytes[1..]) {
+                    return None;
+                }
+                i64::from_str_radix(&id[1..12], 16).ok()?
+            },
+            Self::Ulid => {
+                if bytes.len() != 26 || bytes.iter().any(|a| crockford_value(*a).is_none()) {
+                    return None;
+                }
+                bytes[..10].iter().fold(0, |acc, a| acc * 32 + crockford_value(*a).unwrap_or(0) as i64)
+            },
+            Self::ObjectId => {
+                if bytes.len() != 24 || !is_hex(bytes) {
+                    return None;
+                }
+                i64::from_str_radix(&id[..8], 16).ok()? * 1000
+            },
+        };
+        Utc.timestamp_millis_opt(millis).single()
+    }
+
+    /// 指定した日時に生成されうる ID のうち、最も小さいものを返す。<br />
+    /// `sinceId` や `untilId` に指定することで、日時による範囲指定の代わりに使用できる。
+    pub fn min_id(self, date: DateTime<Utc>) -> String {
+        let millis = date.timestamp_millis().max(0);
+        match self {
+            Self::Aid => format!("{}00", radix36((millis - TIME2000).max(0), 8)),
+            Self::Aidx => format!("{}00000000", radix36((millis - TIME2000).max(0), 8)),
+            Self::Meid => format!("{:012x}000000000000", millis + MEID_OFFSET),
+            Self::Meidg => format!("g{:011x}000000000000", millis),
+            Self::Ulid => {
+                let mut time = [b'0'; 10];
+                let mut rest = millis;
+                for a in time.iter_mut().rev() {
+                    *a = CROCKFORD[(rest % 32) as usize];
+                    rest /= 32;
+                }
+                format!("{}{}", String::from_utf8_lossy(&time), "0".repeat(16))
+            },
+            Self::ObjectId => format!("{:08x}0000000000000000", millis / 1000),
+        }
+    }
+}
+
+impl Display for IdGenMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use IdGenMethod::*;
+        match self {
+            Aid => "aid",
+            Aidx => "aidx",
+            Meid => "meid",
+            Meidg => "meidg",
+            Ulid => "ulid",
+            ObjectId => "objectid",
+        }.fmt(f)
+    }
+}
+
+impl FromStr for IdGenMethod {
+    type Err = InvalidEnumString;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use IdGenMethod::*;
+        Ok(match s {
+            "aid" => Aid,
+            "aidx" => Aidx,
+            "meid" => Meid,
+            "meidg" => Meidg,
+            "ulid" => Ulid,
+            "objectid" => ObjectId,
+            _ => return Err(InvalidEnumString),
+        })
+    }
+}
+
+/// ID の生成方式を推測し、作成日時を返す。
+pub fn parse_date(id: &str) -> Option<DateTime<Utc>> {
+    IdGenMethod::detect(id)?.parse(id)
+}
+
+fn is_base36(bytes: &[u8]) -> bool {
+    bytes.iter().all(|a| a.is_ascii_digit() || a.is_ascii_lowercase())
+}
+
+fn is_hex(bytes: &[u8]) -> bool {
+    bytes.iter().all(|a| matches!(a, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+fn crockford_value(byte: u8) -> Option<u8> {
+    CROCKFORD.iter().position(|a| *a == byte.to_ascii_uppercase()).map(|a| a as u8)
+}
+
+fn radix36(mut value: i64, width: usize) -> String {
+    const CHARS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    let mut buf = Vec::with_capacity(width);
+    while value > 0 {
+        buf.push(CHARS[(value % 36) as usize]);
+        value /= 36;
+    }
+    while buf.len() < width {
+        buf.push(b'0');
+    }
+    buf.reverse();
+    String::from_utf8(buf).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::{parse_date, IdGenMethod};
+
+    #[test]
+    fn id_dates() {
+        let date = Utc.with_ymd_and_hms(2024, 5, 1, 12, 34, 56).unwrap() + chrono::Duration::milliseconds(789);
+        let ids = [
+            ("9srd69w5a1", IdGenMethod::Aid),
+            ("9srd69w5k0z91234", IdGenMethod::Aidx),
+            ("818f34269c950123456789ab", IdGenMethod::Meid),
+            ("g18f34269c950123456789ab", IdGenMethod::Meidg),
+            ("01HWT2D74N7ZZZZZZZZZZZZZZZ", IdGenMethod::Ulid),
+        ];
+        for (id, method) in ids {
+            assert_eq!(IdGenMethod::detect(id), Some(method), "{}", id);
+            assert_eq!(parse_date(id), Some(date), "{}", id);
+            let min = method.min_id(date);
+            assert!(min.as_str() <= id, "{} > {}", min, id);
+            assert_eq!(method.parse(&min), Some(date));
+            assert_eq!(method.to_string().parse::<IdGenMethod>().ok(), Some(method));
+        }
+        assert_eq!(IdGenMethod::detect("663236f00123456789abcdef"), Some(IdGenMethod::ObjectId));
+        assert_eq!(parse_date("663236f00123456789abcdef"), Some(date - chrono::Duration::milliseconds(789)));
+        assert_eq!(IdGenMethod::detect("not an id"), None);
+        assert_eq!(IdGenMethod::Aid.parse("9srd69w5k0z91234"), None);
+    }
+}
@@ -1,5 +1,7 @@
 //! Misskey API へのアクセスを提供するクレート
 
+use std::collections::HashSet;
+
 use errors::ServerError;
 use http::uri::{Authority, InvalidUri, Scheme};
 
@@ -7,9 +9,8 @@ pub use traits::MisskeyClientRequest;
 pub use traits::json::{ConstParamJsonRequest, JsonRequest};
 
 use crate::errors::MisskeyConnectionResult;
-use crate::miauth::MiAuthBuilder;
-
-// TODO レスポンス型に Clone トレイトを実装するべきか否かの検討。
+use crate::miauth::{MiAuthBuilder, Permission};
+use crate::server::ServerInfo;
 
 pub mod errors;
 pub mod requests;
@@ -17,12 +18,22 @@ pub mod responses;
 pub mod traits;
 pub mod miauth;
 pub mod common;
+pub mod pagination;
+pub mod id_gen;
+pub mod schema;
+pub mod server;
+#[cfg(feature = "generated")]
+pub mod generated;
 mod connection;
 
 pub type UnknownValue = serde_json::Value;
 
 pub struct MisskeyHttpClient<T> {
     access_token: Option<String>,
+    /// MiAuth で許可された権限。トークンを直接指定した場合は不明なため `None` になる。
+    permissions: Option<HashSet<Permission>>,
+    /// `discover` などで取得したサーバーの情報
+    server_info: Option<ServerInfo>,
     authority: Authority,
     stream: T,
 }
@@ -30,22 +41,30 @@ pub struct MisskeyHttpClient<T> {
 impl<T> MisskeyHttpClient<T> {
     #[inline]
     pub fn new(stream: T, authority: impl TryInto<Authority, Error = InvalidUri>) -> MisskeyConnectionResult<Self> {
-        Ok(Self::internal_new(stream, authority.try_into()?, None))
+        Ok(Self { access_token: None, permissions: None, server_info: None, authority: authority.try_into()?, stream })
     }
 
     #[inline]
     pub fn login(self, access_token: impl Into<String>) -> Self {
-        Self::internal_new(self.stream, self.authority, Some(access_token.into()))
+        Self { access_token: Some(access_token.into()), permissions: None, .. self }
+    }
+
+    /// 許可された権限とともにトークンを設定する。<br />
+    /// 権限の足りないリクエストは送信前にエラーとなる。
+    #[inline]
+    pub fn login_with_permissions(self, access_token: impl Into<String>, permissions: impl IntoIterator<Item = Permission>) -> Self {
+        Self { access_token: Some(access_token.into()), permissions: Some(permissions.into_iter().collect()), .. self }
     }
  
     #[inline]
     pub fn logout(self) -> Self {
-        Self::internal_new(self.stream, self.authority, None)
+        Self { access_token: None, permissions: None, .. self }
     }
 
+    /// MiAuth などで許可された権限。不明な場合は `None`
     #[inline]
-    fn internal_new(stream: T, authority: Authority, access_token: Option<String>) -> MisskeyHttpClient<T> {
-        MisskeyHttpClient { access_token, authority, stream }
+    pub fn permissions(&self) -> Option<&HashSet<Permission>> {
+        self.permissions.as_ref()
     }
 
     #[inline]
@@ -5,7 +5,7 @@ use std::{collections::HashSet, fmt::Display, str::FromStr};
 use serde_derive::Deserialize;
 use uuid::Uuid;
 
-use crate::errors::MisskeyConnectionResult;
+use crate::errors::{MisskeyConnectionResult, NoEndpointError};
 use crate::{errors::InvalidEnumString, MisskeyClientRequest, MisskeyHttpClient};
 use crate::responses::users::DetailedUserInfo;
 
@@ -13,6 +13,7 @@ pub struct MiAuth<T> {
     pub(crate) client: MisskeyHttpClient<T>,
     uri: Uri,
     pub(crate) info: MiAuthInfo,
+    pub(crate) permission: HashSet<Permission>,
 }
 
 impl<T> MiAuth<T> {
@@ -27,20 +28,22 @@ pub(crate) struct MiAuthInfo(Uuid);
 
 impl MisskeyClientRequest for MiAuthInfo {
     type Response = MiAuthServerResponse;
+    type Error = NoEndpointError;
 
     fn endpoint(&self) -> impl ToString {
-        format!("/api/miauth/{}/check", self.0)
+        format!("/miauth/{}/check", self.0)
     }
 
     fn content_type(&self) -> Option<impl ToString> {
         Option::<&str>::None
     }
 
-    fn body(&self, _: Option<&str>) -> impl ToString {
-        ""
+    fn body(&self, _: Option<&str>) -> MisskeyConnectionResult<impl ToString> {
+        Ok("")
     }
 }
 
+#[allow(clippy::large_enum_variant)]
 pub enum MiAuthStatus<T> {
     Pending(MiAuth<T>),
     Succeed(MisskeyHttpClient<T>, DetailedUserInfo),
@@ -108,7 +111,7 @@ impl<S, T> MiAuthBuilder<S, T> where S: TryInto<Scheme, Error = InvalidUri> {
         let Self {callback, client, icon, name, permission, scheme, uri, uuid} = self;
         let mut list = Vec::with_capacity(5);
         if !permission.is_empty() {
-            list.push(format!("permission={}", permission.into_iter().join(",")));
+            list.push(format!("permission={}", permission.iter().join(",")));
         }
         for value in [("callback", callback), ("icon", icon), ("name", name), ("uri", uri)]
             .into_iter()
@@ -121,7 +124,7 @@ impl<S, T> MiAuthBuilder<S, T> where S: TryInto<Scheme, Error = InvalidUri> {
             if list.is_empty() {""} else {"?"},
             list.into_iter().join("&")
         )).build()?;
-        return Ok(MiAuth {client, info: MiAuthInfo(uuid), uri})
+        Ok(MiAuth {client, info: MiAuthInfo(uuid), uri, permission})
     }
 }
 
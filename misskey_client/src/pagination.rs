@@ -0,0 +1,185 @@
+//! `sinceId` と `untilId` によるページング
+
+use std::collections::VecDeque;
+
+use serde::Deserialize;
+
+use crate::{MisskeyClientRequest, MisskeyHttpClient};
+
+/// `sinceId` と `untilId` によるページングに対応したリクエスト
+pub trait PaginatedRequest : MisskeyClientRequest<Response = Vec<Self::Item>> + Clone where for<'de> Self::Item: Deserialize<'de> {
+    /// 1 ページに含まれる要素の型
+    type Item;
+    /// エンドポイントが受け付ける `limit` の最大値
+    const MAX_LIMIT: usize;
+
+    /// 要素の ID
+    fn item_id(item: &Self::Item) -> &str;
+    fn limit(&self) -> usize;
+    fn since_id(&self) -> Option<&str>;
+    fn until_id(&self) -> Option<&str>;
+    fn set_limit(&mut self, limit: usize);
+    fn set_since_id(&mut self, since_id: Option<String>);
+    fn set_until_id(&mut self, until_id: Option<String>);
+}
+
+/// ページングの方向
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PageDirection {
+    /// 新しいものから古いものへ `untilId` をずらしながら取得する。
+    Backward,
+    /// 古いものから新しいものへ `sinceId` をずらしながら取得する。
+    Forward,
+}
+
+/// リクエストを繰り返し送信し、要素を 1 件ずつ返す構造体。<br />
+/// 同期版では `Iterator`、非同期版では `into_stream` により `Stream` として使用する。
+pub struct Paginator<'a, T, R> where R: PaginatedRequest, for<'de> R::Item: Deserialize<'de> {
+    pub(crate) client: &'a mut MisskeyHttpClient<T>,
+    request: R,
+    direction: PageDirection,
+    cursor: Option<String>,
+    /// 前方向の取得で、これ以降の要素を返さない ID
+    bound: Option<String>,
+    remaining: Option<usize>,
+    buffer: VecDeque<R::Item>,
+    pub(crate) finished: bool,
+}
+
+impl<T> MisskeyHttpClient<T> {
+    /// リクエストを繰り返し送信し、すべてのページの要素を順に返す。<br />
+    /// 既定では新しい順に取得する。1 ページの件数はリクエストの `limit` を `MAX_LIMIT` で制限した値になる。
+    pub fn paginate<R>(&mut self, request: R) -> Paginator<'_, T, R> where R: PaginatedRequest, for<'de> R::Item: Deserialize<'de> {
+        let cursor = request.until_id().map(str::to_string);
+        Paginator {
+            client: self,
+            request,
+            direction: PageDirection::Backward,
+            cursor,
+            bound: None,
+            remaining: None,
+            buffer: VecDeque::new(),
+            finished: false,
+        }
+    }
+}
+
+impl<T, R> Paginator<'_, T, R> where R: PaginatedRequest, for<'de> R::Item: Deserialize<'de> {
+    /// 新しい順に取得する。リクエストの `untilId` が指定されていれば、そこから取得を始める。
+    pub fn backward(mut self) -> Self {
+        self.direction = PageDirection::Backward;
+        self.cursor = self.request.until_id().map(str::to_string);
+        self.bound = None;
+        self
+    }
+
+    /// `since_id` より新しい要素を古い順に取得する。<br />
+    /// リクエストの `untilId` が指定されていれば、その ID に達した時点で終了する。
+    pub fn forward(mut self, since_id: impl Into<String>) -> Self {
+        self.direction = PageDirection::Forward;
+        self.cursor = Some(since_id.into());
+        self.bound = self.request.until_id().map(str::to_string);
+        self.request.set_until_id(None);
+        self
+    }
+
+    /// 取得する要素の最大数を指定する。
+    pub fn max_items(self, max_items: usize) -> Self {
+        Self {
+            remaining: Some(max_items),
+            .. self
+        }
+    }
+
+    pub fn direction(&self) -> PageDirection {
+        self.direction
+    }
+
+    /// 取得済みの要素を 1 件取り出す。
+    pub(crate) fn pop(&mut self) -> Option<R::Item> {
+        if self.remaining == Some(0) {
+            return None;
+        }
+        let item = self.buffer.pop_front()?;
+        if let Some(remaining) = &mut self.remaining {
+            *remaining -= 1;
+        }
+        Some(item)
+    }
+
+    /// 次のページを取得するリクエストを生成する。取得を終える場合は `None` を返す。
+    pub(crate) fn next_request(&mut self) -> Option<R> {
+        if self.finished || self.remaining == Some(0) {
+            return None;
+        }
+        let mut request = self.request.clone();
+        let limit = request.limit().clamp(1, R::MAX_LIMIT);
+        request.set_limit(self.remaining.map_or(limit, |a| a.min(limit)));
+        match self.direction {
+            PageDirection::Backward => request.set_until_id(self.cursor.clone()),
+            PageDirection::Forward => request.set_since_id(self.cursor.clone()),
+        }
+        Some(request)
+    }
+
+    /// 取得したページを方向に合わせて並べ替え、カーソルを進める。
+    pub(crate) fn accept_page(&mut self, mut page: Vec<R::Item>) {
+        match self.direction {
+            PageDirection::Backward => page.sort_by(|a, b| R::item_id(b).cmp(R::item_id(a))),
+            PageDirection::Forward => page.sort_by(|a, b| R::item_id(a).cmp(R::item_id(b))),
+        }
+        if let Some(bound) = &self.bound {
+            let len = page.len();
+            page.retain(|a| R::item_id(a) < bound.as_str());
+            if page.len() < len {
+                self.finished = true;
+            }
+        }
+        match page.last() {
+            Some(last) => self.cursor = Some(R::item_id(last).to_string()),
+            None => self.finished = true,
+        }
+        self.buffer.extend(page);
+    }
+}
+
+/// `limit`、`since_id`、`until_id` フィールドを持つリクエストに `PaginatedRequest` を実装するマクロ。<br />
+/// 要素の型は `id()` で `common` の ID 型を取得できる必要がある。
+macro_rules! paginated_request {
+    ($request:ty, $item:ty, $max_limit:expr) => {
+        impl $crate::pagination::PaginatedRequest for $request {
+            type Item = $item;
+            const MAX_LIMIT: usize = $max_limit;
+
+            fn item_id(item: &$item) -> &str {
+                item.id().as_str()
+            }
+
+            fn limit(&self) -> usize {
+                self.limit
+            }
+
+            fn since_id(&self) -> Option<&str> {
+                self.since_id.as_ref().map(|a| a.as_str())
+            }
+
+            fn until_id(&self) -> Option<&str> {
+                self.until_id.as_ref().map(|a| a.as_str())
+            }
+
+            fn set_limit(&mut self, limit: usize) {
+                self.limit = limit;
+            }
+
+            fn set_since_id(&mut self, since_id: Option<String>) {
+                self.since_id = since_id.map(Into::into);
+            }
+
+            fn set_until_id(&mut self, until_id: Option<String>) {
+                self.until_id = until_id.map(Into::into);
+            }
+        }
+    };
+}
+
+pub(crate) use paginated_request;
@@ -3,3 +3,6 @@ pub mod users;
 pub mod notes;
 pub mod charts;
 pub mod channels;
+pub mod meta;
+pub mod nodeinfo;
+pub mod raw;
@@ -4,5 +4,5 @@ use serde_derive::Serialize;
 use crate::responses::channels::ChannelInfo;
 
 #[derive(Debug, Serialize, ConstParamJsonRequest)]
-#[misskey_client(endpoint = "/channels/my-favorites", response = Vec<ChannelInfo>)]
+#[misskey_client(endpoint = "/channels/my-favorites", response = Vec<ChannelInfo>, credential = true, permissions(ReadChannels))]
 pub struct GetFavoriteChannels;
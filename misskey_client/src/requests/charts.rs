@@ -27,7 +27,7 @@ impl CommonChartRequestBody {
 }
 
 #[derive(Debug, Serialize, ConstParamJsonRequest)]
-#[misskey_client(endpoint = "/chars/active-users", response = ActiveUserChart)]
+#[misskey_client(endpoint = "/charts/active-users", response = ActiveUserChart)]
 pub struct GetActiveUsersChart {
     #[serde(flatten)]
     common_body: CommonChartRequestBody,
@@ -68,6 +68,7 @@ impl GetDriveChart {
 #[derive(Debug, Serialize, ConstParamJsonRequest)]
 #[misskey_client(endpoint = "/charts/federation", response = FederationChart)]
 pub struct GetFederationChart {
+    #[serde(flatten)]
     common_body: CommonChartRequestBody,
 }
 
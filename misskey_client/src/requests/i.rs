@@ -1,3 +1,4 @@
+pub mod favorites;
 pub mod notifications;
 
 use misskey_client_macroes::ConstParamJsonRequest;
@@ -6,5 +7,5 @@ use serde_derive::Serialize;
 use crate::responses::users::LiteUserInfo;
 
 #[derive(Debug, Serialize, ConstParamJsonRequest)]
-#[misskey_client(endpoint = "/i", response = LiteUserInfo)]
+#[misskey_client(endpoint = "/i", response = LiteUserInfo, credential = true, permissions(ReadAccount))]
 pub struct GetSelfData;
@@ -0,0 +1,26 @@
+use misskey_client_macroes::ConstParamJsonRequest;
+use serde_derive::Serialize;
+
+use crate::{common::NoteFavoriteId, pagination::paginated_request, responses::notes::NoteFavoriteInfo};
+
+/// ログイン中のユーザーのお気に入りを、新しいものから順に取得する。
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/i/favorites", response = Vec<NoteFavoriteInfo>, credential = true, permissions(ReadFavorites), builder)]
+#[serde(rename_all = "camelCase")]
+pub struct GetFavorites {
+    /// 1以上100以下. 省略時は10.
+    #[misskey_client(default = 10)]
+    limit: usize,
+    #[misskey_client(into, setter = since)]
+    #[serde(skip_serializing_if = "Option::is_none")] since_id: Option<NoteFavoriteId>,
+    #[misskey_client(into, setter = until)]
+    #[serde(skip_serializing_if = "Option::is_none")] until_id: Option<NoteFavoriteId>,
+}
+
+impl Default for GetFavorites {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+paginated_request!(GetFavorites, NoteFavoriteInfo, 100);
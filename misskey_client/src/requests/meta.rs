@@ -0,0 +1,18 @@
+use misskey_client_macroes::ConstParamJsonRequest;
+use serde_derive::Serialize;
+
+use crate::responses::meta::MetaInfo;
+
+/// サーバーの情報を取得する。
+#[derive(Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/meta", response = MetaInfo, builder)]
+pub struct GetMeta {
+    /// `features` などの詳細な情報を含めるか否か
+    detail: bool,
+}
+
+/// サーバーが公開しているエンドポイントの一覧を取得する。<br />
+/// 各要素は `notes/create` のように先頭のスラッシュを含まない。
+#[derive(Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/endpoints", response = Vec<String>)]
+pub struct GetEndpoints;
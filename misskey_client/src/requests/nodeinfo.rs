@@ -0,0 +1,79 @@
+//! NodeInfo の取得。`/api` の外にあり、GET で取得する。
+
+use http::{Method, Uri};
+
+use crate::{errors::{MisskeyConnectionResult, NoEndpointError}, responses::nodeinfo::{NodeInfo, NodeInfoLink, NodeInfoLinks}, MisskeyClientRequest};
+
+/// `/.well-known/nodeinfo` から NodeInfo へのリンクを取得する。
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GetNodeInfoLinks;
+
+impl MisskeyClientRequest for GetNodeInfoLinks {
+    type Response = NodeInfoLinks;
+    type Error = NoEndpointError;
+
+    fn endpoint(&self) -> impl ToString {
+        "/.well-known/nodeinfo"
+    }
+
+    fn content_type(&self) -> Option<impl ToString> {
+        Option::<&str>::None
+    }
+
+    fn method(&self) -> Method {
+        Method::GET
+    }
+
+    fn path(&self) -> String {
+        "/.well-known/nodeinfo".to_string()
+    }
+
+    fn body(&self, _: Option<&str>) -> MisskeyConnectionResult<impl ToString> {
+        Ok("")
+    }
+}
+
+/// NodeInfo を取得する。
+#[derive(Clone, Debug)]
+pub struct GetNodeInfo {
+    path: String,
+}
+
+impl GetNodeInfo {
+    /// `path` には `/nodeinfo/2.1` のようなパスを指定する。
+    pub fn new(path: impl Into<String>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// リンクの `href` からホストを除いたパスを取得先とする。<br />
+    /// 接続先は常にクライアントのサーバーであるため、`href` のホストは使用しない。
+    pub fn from_link(link: &NodeInfoLink) -> Self {
+        let path = link.href().parse::<Uri>().ok().and_then(|a| a.path_and_query().map(|a| a.to_string()));
+        Self::new(path.unwrap_or_else(|| link.href().clone()))
+    }
+}
+
+impl MisskeyClientRequest for GetNodeInfo {
+    type Response = NodeInfo;
+    type Error = NoEndpointError;
+
+    fn endpoint(&self) -> impl ToString {
+        &self.path
+    }
+
+    fn content_type(&self) -> Option<impl ToString> {
+        Option::<&str>::None
+    }
+
+    fn method(&self) -> Method {
+        Method::GET
+    }
+
+    fn path(&self) -> String {
+        self.path.clone()
+    }
+
+    fn body(&self, _: Option<&str>) -> MisskeyConnectionResult<impl ToString> {
+        Ok("")
+    }
+}
@@ -1,218 +1,810 @@
+use chrono::{DateTime, Duration, Utc};
 use misskey_client_macroes::ConstParamJsonRequest;
 use serde_derive::Serialize;
 
-use crate::{common::{NoteVisibility, ReactionAcceptance}, responses::notes::{CreatedNoteInfo, NoteInfo}, traits::{ChannelId, NoteId}};
+use crate::{common::{ChannelId, DriveFileId, NoteId, NoteReactionId, NoteVisibility, Reaction, ReactionAcceptance, UserId}, errors::{endpoint_error, InvalidRequestReason}, pagination::paginated_request, responses::notes::{CreatedNoteInfo, NoteInfo, NoteReactionInfo, NoteStateInfo, NoteTranslationInfo, PollInfo}};
+
+endpoint_error! {
+    /// `CreateNote` 固有のエラー
+    pub enum CreateNoteError {
+        NoSuchRenoteTarget = ("NO_SUCH_RENOTE_TARGET", "b5c90186-4ab0-49c8-9bba-a1f76c282ba4"),
+        CannotRenoteToAPureRenote = ("CANNOT_RENOTE_TO_A_PURE_RENOTE", "fd4cc33e-2a37-48dd-99cc-9b806eb2031a"),
+        CannotRenoteDueToVisibility = ("CANNOT_RENOTE_DUE_TO_VISIBILITY", "be9529e9-fe72-4de0-ae43-0b363c4938af"),
+        NoSuchReplyTarget = ("NO_SUCH_REPLY_TARGET", "749ee0f6-d3da-459a-bf02-282e2da4292c"),
+        CannotReplyToAPureRenote = ("CANNOT_REPLY_TO_A_PURE_RENOTE", "3ac74a84-8fd5-4bb0-870f-01804f82ce15"),
+        CannotCreateAlreadyExpiredPoll = ("CANNOT_CREATE_ALREADY_EXPIRED_POLL", "04da457d-b083-4055-9082-955525eda5a5"),
+        NoSuchChannel = ("NO_SUCH_CHANNEL", "b1653923-5453-4edc-b786-7c4f39bb0bbb"),
+        YouHaveBeenBlocked = ("YOU_HAVE_BEEN_BLOCKED", "b390d7e1-8a5e-46ed-b625-06271cafd3d3"),
+        NoSuchFile = ("NO_SUCH_FILE", "b6992544-63e7-67f0-fa7f-32444b1b5306"),
+        CannotRenoteOutsideOfChannel = ("CANNOT_RENOTE_OUTSIDE_OF_CHANNEL", "33510210-8452-094c-6227-4a6c05d99f00"),
+        ContainsProhibitedWords = ("CONTAINS_PROHIBITED_WORDS", "aa6e01d3-a85c-669d-758a-76aab43af334"),
+        ContainsTooManyMentions = ("CONTAINS_TOO_MANY_MENTIONS", "4de0363a-3046-481b-9b0f-feff3e211025"),
+    }
+}
 
 #[derive(Debug, Serialize, ConstParamJsonRequest)]
-#[misskey_client(endpoint = "/notes/create", response = CreatedNoteInfo, can_be_empty = true)]
+#[misskey_client(endpoint = "/notes/create", response = CreatedNoteInfo, error = CreateNoteError, can_be_empty = true, credential = true, permissions(WriteNotes), validate = Self::validate_content, builder(new = empty, vis = pub(self)))]
 #[serde(rename_all = "camelCase")]
 pub struct CreateNote {
     visibility: NoteVisibility,
-    #[serde(skip_serializing_if = "Vec::is_empty")] visible_user_ids: Vec<String>,
+    #[misskey_client(skip)]
+    #[serde(skip_serializing_if = "Vec::is_empty")] visible_user_ids: Vec<UserId>,
+    #[misskey_client(into)]
     cw: Option<String>,
     local_only: bool,
     reaction_acceptance: Option<ReactionAcceptance>,
     no_extract_mentions: bool,
     no_extract_hashtags: bool,
     no_extract_emojis: bool,
-    reply_id: Option<String>,
-    renote_id: Option<String>,
-    channel_id: Option<String>,
-    text: String,
-    // file_ids: Vec<String>,
-    // media_ids: Vec<String>,
-    // poll: Poll,
-    // scheduled_at: usize,
+    #[misskey_client(into, setter = reply)]
+    reply_id: Option<NoteId>,
+    #[misskey_client(skip)]
+    renote_id: Option<NoteId>,
+    #[misskey_client(into, setter = channel)]
+    channel_id: Option<ChannelId>,
+    #[misskey_client(into)]
+    text: Option<String>,
+    /// 添付するドライブのファイル
+    #[misskey_client(into, add = file, extend = files)]
+    #[serde(skip_serializing_if = "Vec::is_empty")] file_ids: Vec<DriveFileId>,
+    /// `file_ids` の古い名前。`file_ids` を指定した場合は無視される。
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Vec::is_empty")] media_ids: Vec<DriveFileId>,
+    #[serde(skip_serializing_if = "Option::is_none")] poll: Option<Poll>,
+    /// 予約投稿の日時。予約投稿に対応したサーバーでのみ有効
+    #[serde(skip_serializing_if = "Option::is_none", with = "chrono::serde::ts_milliseconds_option")] scheduled_at: Option<DateTime<Utc>>,
+    /// `true` をセットすると、作製に成功したときのレスポンスが `204` になります。
     no_created_note: bool,
 }
 
 impl CreateNote {
-    pub fn renote(renote_id: impl NoteId) -> Self {
-        Self {
-            visibility: NoteVisibility::Public,
-            visible_user_ids: vec![],
-            cw: None,
-            local_only: false,
-            reaction_acceptance: None,
-            no_extract_hashtags: false,
-            no_extract_mentions: false,
-            no_extract_emojis: false,
-            reply_id: None,
-            renote_id: Some(renote_id.to_note_id()),
-            channel_id: None,
-            text: String::new(),
-            no_created_note: false,
-        }
-    }
-
-    pub fn note(text: impl ToString) -> Self {
-        Self {
-            visibility: NoteVisibility::Public,
-            visible_user_ids: vec![],
-            cw: None,
-            local_only: false,
-            reaction_acceptance: None,
-            no_extract_hashtags: false,
-            no_extract_mentions: false,
-            no_extract_emojis: false,
-            reply_id: None,
-            renote_id: None,
-            channel_id: None,
-            text: text.to_string(),
-            no_created_note: false,
-        }
-    }
-
-    pub fn quote(text: impl ToString, renote_id: impl NoteId) -> Self {
-        Self {
-            visibility: NoteVisibility::Public,
-            visible_user_ids: vec![],
-            cw: None,
-            local_only: false,
-            reaction_acceptance: None,
-            no_extract_hashtags: false,
-            no_extract_mentions: false,
-            no_extract_emojis: false,
-            reply_id: None,
-            renote_id: Some(renote_id.to_note_id()),
-            channel_id: None,
-            text: text.to_string(),
-            no_created_note: false,
-        }
-    }
-
-    pub fn visibility(self, visibility: NoteVisibility) -> Self {
+    /// 添付できるファイルの最大数
+    pub const MAX_FILES: usize = 16;
+    /// `cw` の最大の長さ
+    pub const MAX_CW_LENGTH: usize = 100;
+
+    pub fn renote(renote_id: impl Into<NoteId>) -> Self {
         Self {
-            visibility,
-            .. self
+            renote_id: Some(renote_id.into()),
+            .. Self::empty()
         }
     }
 
+    pub fn note(text: impl Into<String>) -> Self {
+        Self::empty().text(text)
+    }
+
+    pub fn quote(text: impl Into<String>, renote_id: impl Into<NoteId>) -> Self {
+        Self::renote(renote_id).text(text)
+    }
+
+    /// 本文のない、ファイルのみのノート
+    pub fn with_files<F: Into<DriveFileId>>(files: impl IntoIterator<Item = F>) -> Self {
+        Self::empty().files(files)
+    }
+
+    /// 本文のない、投票のみのノート
+    pub fn with_poll(poll: Poll) -> Self {
+        Self::empty().poll(poll)
+    }
+
     /// 閲覧可能なユーザー ID を指定する関数。
     /// 公開範囲は自動で `NoteVisibility::Specified` に変更される。
-    pub fn visible_users(self, users: Vec<String>) -> Self {
+    pub fn visible_users<U: Into<UserId>>(self, users: impl IntoIterator<Item = U>) -> Self {
         Self {
             visibility: NoteVisibility::Specified,
-            visible_user_ids: users,
+            visible_user_ids: users.into_iter().map(Into::into).collect(),
             .. self
         }
     }
 
-    pub fn cw(mut self, cw: impl ToString) -> Self {
-        self.cw = Some(cw.to_string());
-        self
+    /// 本文、ファイル、Renote、投票のいずれかが必要。
+    fn validate_content(&self) -> Result<(), InvalidRequestReason> {
+        if self.text.is_none() && self.file_ids.is_empty() && self.media_ids.is_empty() && self.renote_id.is_none() && self.poll.is_none() {
+            return Err(InvalidRequestReason::MissingAnyOf(&["text", "fileIds", "mediaIds", "renoteId", "poll"]));
+        }
+        if let Some(text) = &self.text {
+            InvalidRequestReason::check_not_empty("text", text)?;
+        }
+        if let Some(cw) = &self.cw {
+            InvalidRequestReason::check_length("cw", cw, Self::MAX_CW_LENGTH)?;
+        }
+        if !self.file_ids.is_empty() {
+            InvalidRequestReason::check_items("fileIds", &self.file_ids, 1, Self::MAX_FILES)?;
+        }
+        if !self.media_ids.is_empty() {
+            InvalidRequestReason::check_items("mediaIds", &self.media_ids, 1, Self::MAX_FILES)?;
+        }
+        if let Some(poll) = &self.poll {
+            poll.validate()?;
+        }
+        if self.scheduled_at.is_some_and(|a| a <= Utc::now()) {
+            return Err(InvalidRequestReason::NotInFuture { param: "scheduledAt" });
+        }
+        Ok(())
     }
+}
 
-    pub fn local_only(self, local_only: bool) -> Self {
+/// ノートに付ける投票
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Poll {
+    choices: Vec<String>,
+    multiple: bool,
+    #[serde(skip_serializing_if = "Option::is_none", with = "chrono::serde::ts_milliseconds_option")] expires_at: Option<DateTime<Utc>>,
+    /// 投稿してから締め切るまでのミリ秒
+    #[serde(skip_serializing_if = "Option::is_none")] expired_after: Option<i64>,
+}
+
+impl Poll {
+    /// 選択肢の最小数
+    pub const MIN_CHOICES: usize = 2;
+    /// 選択肢の最大数
+    pub const MAX_CHOICES: usize = 10;
+    /// 選択肢の最大の長さ
+    pub const MAX_CHOICE_LENGTH: usize = 50;
+
+    /// 締め切りのない、単一選択の投票
+    pub fn new<S: Into<String>>(choices: impl IntoIterator<Item = S>) -> Self {
         Self {
-            local_only,
-            .. self
+            choices: choices.into_iter().map(Into::into).collect(),
+            multiple: false,
+            expires_at: None,
+            expired_after: None,
         }
     }
 
-    pub fn reaction_acceptance(self, acceptance: ReactionAcceptance) -> Self {
+    /// 複数の選択肢に投票できるか否か
+    pub fn multiple(self, multiple: bool) -> Self {
+        Self { multiple, .. self }
+    }
+
+    /// 締め切りの日時。`expired_after` の指定は取り消される。
+    pub fn expires_at(self, expires_at: DateTime<Utc>) -> Self {
         Self {
-            reaction_acceptance: Some(acceptance),
+            expires_at: Some(expires_at),
+            expired_after: None,
             .. self
         }
     }
 
-    pub fn reply(self, reply_id: impl NoteId) -> Self {
+    /// 投稿してから締め切るまでの期間。`expires_at` の指定は取り消される。
+    pub fn expired_after(self, expired_after: Duration) -> Self {
         Self {
-            reply_id: Some(reply_id.to_note_id()),
+            expires_at: None,
+            expired_after: Some(expired_after.num_milliseconds()),
             .. self
         }
     }
 
-    pub fn channel(self, channel_id: impl ChannelId) -> Self {
-        Self {
-             channel_id: Some(channel_id.to_channel_id()),
-             .. self
+    fn validate(&self) -> Result<(), InvalidRequestReason> {
+        InvalidRequestReason::check_items("poll.choices", &self.choices, Self::MIN_CHOICES, Self::MAX_CHOICES)?;
+        for choice in &self.choices {
+            InvalidRequestReason::check_not_empty("poll.choices", choice)?;
+            InvalidRequestReason::check_length("poll.choices", choice, Self::MAX_CHOICE_LENGTH)?;
+        }
+        if self.expires_at.is_some_and(|a| a <= Utc::now()) {
+            return Err(InvalidRequestReason::NotInFuture { param: "poll.expiresAt" });
         }
+        if self.expired_after.is_some_and(|a| a < 1) {
+            return Err(InvalidRequestReason::NotInFuture { param: "poll.expiredAfter" });
+        }
+        Ok(())
     }
+}
 
-    /// `true` をセットすると、作製に成功したときのレスポンスが `204` になります。
-    pub fn no_created_note(self, no_created_note: bool) -> Self {
-        Self {
-            no_created_note,
-            .. self
-        }
+endpoint_error! {
+    /// `SearchNote` 固有のエラー
+    pub enum SearchNoteError {
+        /// サーバーでノートの検索が無効になっている。
+        Unavailable = ("UNAVAILABLE", "0b44998d-77aa-4427-80d0-d2c9b8523011"),
     }
 }
 
-#[derive(Debug, Serialize, ConstParamJsonRequest)]
-#[misskey_client(endpoint = "/notes/search", response = Vec<NoteInfo>)]
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/search", response = Vec<NoteInfo>, error = SearchNoteError, builder)]
 #[serde(rename_all = "camelCase")]
 pub struct SearchNote<'a> {
+    #[misskey_client(required)]
     query: &'a str,
-    #[serde(skip_serializing_if = "Option::is_none")] since_id: Option<&'a str>,
-    #[serde(skip_serializing_if = "Option::is_none")] until_id: Option<&'a str>,
+    #[misskey_client(into, setter = since)]
+    #[serde(skip_serializing_if = "Option::is_none")] since_id: Option<NoteId>,
+    #[misskey_client(into, setter = until)]
+    #[serde(skip_serializing_if = "Option::is_none")] until_id: Option<NoteId>,
+    #[misskey_client(default = 10)]
     limit: usize,
     offset: usize,
     #[serde(skip_serializing_if = "Option::is_none")] host: Option<&'a str>,
-    #[serde(skip_serializing_if = "Option::is_none")] user_id: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")] channel_id: Option<String>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] user_id: Option<UserId>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] channel_id: Option<ChannelId>,
 }
 
-impl<'a> SearchNote<'a> {
-    pub fn new(query: &'a str) -> Self {
-        Self {
-            query,
-            since_id: None,
-            until_id: None,
-            limit: 10,
-            offset: 0,
-            host: None,
-            user_id: None,
-            channel_id: None,
+endpoint_error! {
+    /// `DeleteNote` 固有のエラー
+    pub enum DeleteNoteError {
+        NoSuchNote = ("NO_SUCH_NOTE", "490be23f-8c1f-4796-819f-94cb4f9d1630"),
+        /// 他のユーザーのノートを削除しようとした。
+        AccessDenied = ("ACCESS_DENIED", "fe8d7103-0ea8-4ec3-814d-f8b401dc69e9"),
+    }
+}
+
+paginated_request!(SearchNote<'_>, NoteInfo, 100);
+
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/delete", response = (), error = DeleteNoteError, can_be_empty = true, credential = true, permissions(WriteNotes), builder)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteNote {
+    #[misskey_client(required, into)]
+    note_id: NoteId,
+}
+
+endpoint_error! {
+    /// `UpdateNote` 固有のエラー
+    pub enum UpdateNoteError {
+        NoSuchNote = ("NO_SUCH_NOTE", "a6584e14-6e01-4ad3-b566-851e7bf0d474"),
+    }
+}
+
+/// ノートの本文、CW と添付ファイルを編集する。<br />
+/// Misskey 本体にはなく、編集に対応したフォークでのみ使用できる。`/api/endpoints` の一覧で対応を確認する。<br />
+/// ファイルの変更は CherryPick のように対応したフォークでのみ反映される。
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/update", response = (), error = UpdateNoteError, can_be_empty = true, credential = true, permissions(WriteNotes), validate = Self::validate_content, builder)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateNote {
+    #[misskey_client(required, into)]
+    note_id: NoteId,
+    #[misskey_client(into)]
+    text: Option<String>,
+    /// `None` の場合は CW が削除される。
+    #[misskey_client(into)]
+    cw: Option<String>,
+    #[misskey_client(into, add = file, extend = files)]
+    #[serde(skip_serializing_if = "Vec::is_empty")] file_ids: Vec<DriveFileId>,
+}
+
+impl UpdateNote {
+    /// 本文と添付ファイルのいずれかが必要。
+    fn validate_content(&self) -> Result<(), InvalidRequestReason> {
+        if self.text.is_none() && self.file_ids.is_empty() {
+            return Err(InvalidRequestReason::MissingAnyOf(&["text", "fileIds"]));
         }
+        if let Some(text) = &self.text {
+            InvalidRequestReason::check_not_empty("text", text)?;
+        }
+        if let Some(cw) = &self.cw {
+            InvalidRequestReason::check_length("cw", cw, CreateNote::MAX_CW_LENGTH)?;
+        }
+        if !self.file_ids.is_empty() {
+            InvalidRequestReason::check_items("fileIds", &self.file_ids, 1, CreateNote::MAX_FILES)?;
+        }
+        Ok(())
+    }
+}
+
+endpoint_error! {
+    /// `Unrenote` 固有のエラー
+    pub enum UnrenoteError {
+        NoSuchNote = ("NO_SUCH_NOTE", "efd4a259-2442-496b-8dd7-b255aa1a160f"),
+    }
+}
+
+/// ノートに対する自分のリノートをすべて削除する。引用リノートは削除されない。
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/unrenote", response = (), error = UnrenoteError, can_be_empty = true, credential = true, permissions(WriteNotes), builder)]
+#[serde(rename_all = "camelCase")]
+pub struct Unrenote {
+    #[misskey_client(required, into)]
+    note_id: NoteId,
+}
+
+endpoint_error! {
+    /// `GetLocalTimeline` 固有のエラー
+    pub enum GetLocalTimelineError {
+        /// サーバーでローカルタイムラインが無効になっている。
+        LtlDisabled = ("LTL_DISABLED", "45a6eb02-7695-4393-b023-dd3be9aaaefd"),
+        BothWithRepliesAndWithFiles = ("BOTH_WITH_REPLIES_AND_WITH_FILES", "dd9c8400-1cb5-4eef-8a31-200c5f933793"),
+    }
+}
+
+endpoint_error! {
+    /// `GetHybridTimeline` 固有のエラー
+    pub enum GetHybridTimelineError {
+        /// サーバーでソーシャルタイムラインが無効になっている。
+        StlDisabled = ("STL_DISABLED", "620763f4-f621-4533-ab33-0577a1a3c342"),
+        BothWithRepliesAndWithFiles = ("BOTH_WITH_REPLIES_AND_WITH_FILES", "dfaa3eb7-8002-4cb7-bcc4-1095df46656f"),
+    }
+}
+
+endpoint_error! {
+    /// `GetGlobalTimeline` 固有のエラー
+    pub enum GetGlobalTimelineError {
+        /// サーバーでグローバルタイムラインが無効になっている。
+        GtlDisabled = ("GTL_DISABLED", "0332fc13-6ab2-4427-ae80-a9fadffd1a6b"),
+    }
+}
+
+/// ホームタイムライン
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/timeline", response = Vec<NoteInfo>, credential = true, permissions(ReadAccount), builder)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTimeline {
+    /// ファイルが添付されたノートのみを取得する。
+    with_files: bool,
+    #[misskey_client(default = true)]
+    with_renotes: bool,
+    /// 1以上100以下. 省略時は10.
+    #[misskey_client(default = 10)]
+    limit: usize,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] since_id: Option<NoteId>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] until_id: Option<NoteId>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "chrono::serde::ts_milliseconds_option")] since_date: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "chrono::serde::ts_milliseconds_option")] until_date: Option<DateTime<Utc>>,
+    /// キャッシュが不完全な場合に、データベースへの問い合わせを省略して取得できた分だけを返す。
+    allow_partial: bool,
+}
+
+/// ローカルタイムライン
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/local-timeline", response = Vec<NoteInfo>, error = GetLocalTimelineError, builder)]
+#[serde(rename_all = "camelCase")]
+pub struct GetLocalTimeline {
+    /// ファイルが添付されたノートのみを取得する。`with_replies` とは同時に指定できない。
+    with_files: bool,
+    #[misskey_client(default = true)]
+    with_renotes: bool,
+    /// 他のユーザーへのリプライを含める。
+    with_replies: bool,
+    /// 1以上100以下. 省略時は10.
+    #[misskey_client(default = 10)]
+    limit: usize,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] since_id: Option<NoteId>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] until_id: Option<NoteId>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "chrono::serde::ts_milliseconds_option")] since_date: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "chrono::serde::ts_milliseconds_option")] until_date: Option<DateTime<Utc>>,
+    /// キャッシュが不完全な場合に、データベースへの問い合わせを省略して取得できた分だけを返す。
+    allow_partial: bool,
+}
+
+/// ソーシャルタイムライン
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/hybrid-timeline", response = Vec<NoteInfo>, error = GetHybridTimelineError, credential = true, permissions(ReadAccount), builder)]
+#[serde(rename_all = "camelCase")]
+pub struct GetHybridTimeline {
+    /// ファイルが添付されたノートのみを取得する。`with_replies` とは同時に指定できない。
+    with_files: bool,
+    #[misskey_client(default = true)]
+    with_renotes: bool,
+    /// 他のユーザーへのリプライを含める。
+    with_replies: bool,
+    /// 1以上100以下. 省略時は10.
+    #[misskey_client(default = 10)]
+    limit: usize,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] since_id: Option<NoteId>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] until_id: Option<NoteId>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "chrono::serde::ts_milliseconds_option")] since_date: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "chrono::serde::ts_milliseconds_option")] until_date: Option<DateTime<Utc>>,
+    /// キャッシュが不完全な場合に、データベースへの問い合わせを省略して取得できた分だけを返す。
+    allow_partial: bool,
+}
+
+/// グローバルタイムライン。<br />
+/// Misskey のグローバルタイムラインはキャッシュを使用しないため、`allow_partial` と `with_replies` はない。
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/global-timeline", response = Vec<NoteInfo>, error = GetGlobalTimelineError, builder)]
+#[serde(rename_all = "camelCase")]
+pub struct GetGlobalTimeline {
+    /// ファイルが添付されたノートのみを取得する。
+    with_files: bool,
+    #[misskey_client(default = true)]
+    with_renotes: bool,
+    /// 1以上100以下. 省略時は10.
+    #[misskey_client(default = 10)]
+    limit: usize,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] since_id: Option<NoteId>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] until_id: Option<NoteId>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "chrono::serde::ts_milliseconds_option")] since_date: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "chrono::serde::ts_milliseconds_option")] until_date: Option<DateTime<Utc>>,
+}
+
+paginated_request!(GetTimeline, NoteInfo, 100);
+paginated_request!(GetLocalTimeline, NoteInfo, 100);
+paginated_request!(GetHybridTimeline, NoteInfo, 100);
+paginated_request!(GetGlobalTimeline, NoteInfo, 100);
+
+endpoint_error! {
+    /// `GetNote` 固有のエラー
+    pub enum GetNoteError {
+        NoSuchNote = ("NO_SUCH_NOTE", "24fcbfc6-2e37-42b6-8388-c29b3861a08d"),
+    }
+}
+
+/// ID をもとにノートを取得する。
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/show", response = NoteInfo, error = GetNoteError, builder)]
+#[serde(rename_all = "camelCase")]
+pub struct GetNote {
+    #[misskey_client(required, into)]
+    note_id: NoteId,
+}
+
+/// ノートへのリプライと引用リノート
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/children", response = Vec<NoteInfo>, builder)]
+#[serde(rename_all = "camelCase")]
+pub struct GetNoteChildren {
+    #[misskey_client(required, into)]
+    note_id: NoteId,
+    /// 1以上100以下. 省略時は10.
+    #[misskey_client(default = 10)]
+    limit: usize,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] since_id: Option<NoteId>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] until_id: Option<NoteId>,
+}
+
+endpoint_error! {
+    /// `GetConversation` 固有のエラー
+    pub enum GetConversationError {
+        NoSuchNote = ("NO_SUCH_NOTE", "e1035875-9551-45ec-afa8-1ded1fcb53c8"),
+    }
+}
+
+/// ノートのリプライ元を、直接のリプライ元から順にさかのぼって取得する。
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/conversation", response = Vec<NoteInfo>, error = GetConversationError, builder)]
+#[serde(rename_all = "camelCase")]
+pub struct GetConversation {
+    #[misskey_client(required, into)]
+    note_id: NoteId,
+    /// 1以上100以下. 省略時は10.
+    #[misskey_client(default = 10)]
+    limit: usize,
+    offset: usize,
+}
+
+/// ノートへのリプライ
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/replies", response = Vec<NoteInfo>, builder)]
+#[serde(rename_all = "camelCase")]
+pub struct GetReplies {
+    #[misskey_client(required, into)]
+    note_id: NoteId,
+    /// 1以上100以下. 省略時は10.
+    #[misskey_client(default = 10)]
+    limit: usize,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] since_id: Option<NoteId>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] until_id: Option<NoteId>,
+}
+
+endpoint_error! {
+    /// `GetRenotes` 固有のエラー
+    pub enum GetRenotesError {
+        NoSuchNote = ("NO_SUCH_NOTE", "12908022-2e21-46cd-ba6a-3edaf6093f46"),
+    }
+}
+
+/// ノートのリノートと引用リノート
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/renotes", response = Vec<NoteInfo>, error = GetRenotesError, builder)]
+#[serde(rename_all = "camelCase")]
+pub struct GetRenotes {
+    #[misskey_client(required, into)]
+    note_id: NoteId,
+    /// 指定したユーザーのリノートのみを取得する。
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] user_id: Option<UserId>,
+    /// 1以上100以下. 省略時は10.
+    #[misskey_client(default = 10)]
+    limit: usize,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] since_id: Option<NoteId>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] until_id: Option<NoteId>,
+}
+
+endpoint_error! {
+    /// `GetNoteReactions` 固有のエラー
+    pub enum GetNoteReactionsError {
+        NoSuchNote = ("NO_SUCH_NOTE", "263fff3d-d0e1-4af4-bea7-8408059b451a"),
+    }
+}
+
+/// ノートに付けられたリアクションを、新しいものから順に取得する。
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/reactions", response = Vec<NoteReactionInfo>, error = GetNoteReactionsError, builder)]
+#[serde(rename_all = "camelCase")]
+pub struct GetNoteReactions {
+    #[misskey_client(required, into)]
+    note_id: NoteId,
+    /// 指定した種類のリアクションのみを取得する。
+    #[misskey_client(into, setter = reaction)]
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")] reaction_type: Option<Reaction>,
+    /// 1以上100以下. 省略時は10.
+    #[misskey_client(default = 10)]
+    limit: usize,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] since_id: Option<NoteReactionId>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] until_id: Option<NoteReactionId>,
+}
+
+/// ログイン中のユーザーから見たノートの状態を取得する。
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/state", response = NoteStateInfo, credential = true, permissions(ReadFavorites), builder)]
+#[serde(rename_all = "camelCase")]
+pub struct GetNoteState {
+    #[misskey_client(required, into)]
+    note_id: NoteId,
+}
+
+endpoint_error! {
+    /// `CreateReaction` 固有のエラー
+    pub enum CreateReactionError {
+        NoSuchNote = ("NO_SUCH_NOTE", "033d0620-5bfe-4027-965d-980b0c85a3ea"),
+        AlreadyReacted = ("ALREADY_REACTED", "71efcf98-86d6-4e2b-b2ad-9d032369366b"),
+        YouHaveBeenBlocked = ("YOU_HAVE_BEEN_BLOCKED", "20ef5475-9f38-4e4c-bd33-de6d979498ec"),
+        CannotReactToRenote = ("CANNOT_REACT_TO_RENOTE", "eaccdc08-ddef-43fe-908f-d108faad57f5"),
+    }
+}
+
+/// ノートにリアクションする。<br />
+/// ノートの `reaction_acceptance` が受け付けないリアクションは、サーバーでいいねに置き換えられる。
+/// 置き換えられるかは `NoteInfo::accepts_reaction` で確認できる。
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/reactions/create", response = (), error = CreateReactionError, can_be_empty = true, credential = true, permissions(WriteReactions), builder)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateReaction {
+    #[misskey_client(required, into)]
+    note_id: NoteId,
+    #[misskey_client(required, into)]
+    reaction: Reaction,
+}
+
+endpoint_error! {
+    /// `DeleteReaction` 固有のエラー
+    pub enum DeleteReactionError {
+        NoSuchNote = ("NO_SUCH_NOTE", "764d9fce-f9f2-4a0e-92b1-6ceac9a7ad37"),
+        NotReacted = ("NOT_REACTED", "92f4426d-4196-4125-aa5b-02943e2ec8fc"),
+    }
+}
+
+/// ノートへのリアクションを取り消す。
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/reactions/delete", response = (), error = DeleteReactionError, can_be_empty = true, credential = true, permissions(WriteReactions), builder)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteReaction {
+    #[misskey_client(required, into)]
+    note_id: NoteId,
+}
+
+endpoint_error! {
+    /// `CreateFavorite` 固有のエラー
+    pub enum CreateFavoriteError {
+        NoSuchNote = ("NO_SUCH_NOTE", "6dd26674-e060-4816-909a-45ba3f4da458"),
+        AlreadyFavorited = ("ALREADY_FAVORITED", "a402c12b-34dd-41d2-97d8-4d2ffd96a1a6"),
     }
+}
+
+/// ノートをお気に入りに登録する。
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/favorites/create", response = (), error = CreateFavoriteError, can_be_empty = true, credential = true, permissions(WriteFavorites), builder)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateFavorite {
+    #[misskey_client(required, into)]
+    note_id: NoteId,
+}
 
-    pub fn since(mut self, since_id: &'a str) -> Self {
-        self.since_id = Some(since_id);
-        self
+endpoint_error! {
+    /// `DeleteFavorite` 固有のエラー
+    pub enum DeleteFavoriteError {
+        NoSuchNote = ("NO_SUCH_NOTE", "80848a2c-398f-4343-baa9-df1d57696c56"),
+        NotFavorited = ("NOT_FAVORITED", "b625fc69-635e-45e9-86f4-dbefbef35af5"),
     }
+}
+
+/// ノートをお気に入りから削除する。
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/favorites/delete", response = (), error = DeleteFavoriteError, can_be_empty = true, credential = true, permissions(WriteFavorites), builder)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteFavorite {
+    #[misskey_client(required, into)]
+    note_id: NoteId,
+}
 
-    pub fn until(mut self, until_id: &'a str) -> Self {
-        self.until_id = Some(until_id);
-        self
+endpoint_error! {
+    /// `MuteThread` 固有のエラー
+    pub enum MuteThreadError {
+        NoSuchNote = ("NO_SUCH_NOTE", "5ff67ada-ed3b-2e71-8e87-a1a421e177d2"),
     }
+}
 
-    pub fn host(mut self, host: &'a str) -> Self {
-        self.host = Some(host);
-        self
+/// ノートが属するスレッドをミュートし、スレッド内のノートの通知を受け取らないようにする。
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/thread-muting/create", response = (), error = MuteThreadError, can_be_empty = true, credential = true, permissions(WriteAccount), builder)]
+#[serde(rename_all = "camelCase")]
+pub struct MuteThread {
+    #[misskey_client(required, into)]
+    note_id: NoteId,
+}
+
+endpoint_error! {
+    /// `UnmuteThread` 固有のエラー
+    pub enum UnmuteThreadError {
+        NoSuchNote = ("NO_SUCH_NOTE", "bddd57ac-ceb3-b29d-4334-86ea5fae481a"),
     }
+}
 
-    pub fn limit(mut self, limit: usize) -> Self {
-        self.limit = limit;
-        self
+/// ノートが属するスレッドのミュートを解除する。
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/thread-muting/delete", response = (), error = UnmuteThreadError, can_be_empty = true, credential = true, permissions(WriteAccount), builder)]
+#[serde(rename_all = "camelCase")]
+pub struct UnmuteThread {
+    #[misskey_client(required, into)]
+    note_id: NoteId,
+}
+
+endpoint_error! {
+    /// `VotePoll` 固有のエラー
+    pub enum VotePollError {
+        NoSuchNote = ("NO_SUCH_NOTE", "ecafbd2e-c283-4d6d-aecb-1a0a33b75396"),
+        NoPoll = ("NO_POLL", "5f979967-52d9-4314-a911-1c673727f92f"),
+        InvalidChoice = ("INVALID_CHOICE", "e0cc9a04-f2e8-41e4-a5f1-4127293260cc"),
+        /// 単一選択の投票に投票済みか、複数選択の投票で同じ選択肢に投票済み
+        AlreadyVoted = ("ALREADY_VOTED", "0963fc77-efac-419b-9424-b391608dc6d8"),
+        AlreadyExpired = ("ALREADY_EXPIRED", "1022a357-b085-4054-9083-8f8de358337e"),
+        YouHaveBeenBlocked = ("YOU_HAVE_BEEN_BLOCKED", "85a5377e-b1e9-4617-b0b9-5bea73331e49"),
     }
+}
 
-    pub fn offset(mut self, offset: usize) -> Self {
-        self.offset = offset;
-        self
+/// ノートの投票に投票する。
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/polls/vote", response = (), error = VotePollError, can_be_empty = true, credential = true, permissions(WriteVotes), validate = Self::validate_choice, builder)]
+#[serde(rename_all = "camelCase")]
+pub struct VotePoll {
+    #[misskey_client(required, into)]
+    note_id: NoteId,
+    /// 選択肢の 0 から始まる位置
+    #[misskey_client(required)]
+    choice: usize,
+    /// `for_note` で指定したノートの投票
+    #[misskey_client(skip)]
+    #[serde(skip)] poll: FetchedPoll,
+}
+
+/// `VotePoll` が送信前の確認に使う、取得済みのノートの投票
+#[derive(Clone, Debug, Default)]
+enum FetchedPoll {
+    /// ノートを取得していない。送信前に確認しない。
+    #[default]
+    NotFetched,
+    /// 取得したノートに投票がない。
+    NoPoll,
+    Poll(PollInfo),
+}
+
+impl VotePoll {
+    /// 取得済みのノートの投票に投票する。<br />
+    /// 投票の有無、選択肢の範囲、締め切りと、単一選択の投票に投票済みでないかを送信前に確認する。
+    pub fn for_note(note: &NoteInfo, choice: usize) -> Self {
+        Self {
+            poll: match note.poll() {
+                Some(poll) => FetchedPoll::Poll(poll.clone()),
+                None => FetchedPoll::NoPoll,
+            },
+            .. Self::new(note, choice)
+        }
     }
 
-    pub fn user_id(mut self, user_id: String) -> Self {
-        self.user_id = Some(user_id);
-        self
+    /// 選択肢を本文で指定して、取得済みのノートの投票に投票する。<br />
+    /// 一致する選択肢がない場合は `None`
+    pub fn for_choice(note: &NoteInfo, text: &str) -> Option<Self> {
+        note.poll_choice_index(text).map(|a| Self::for_note(note, a))
     }
 
-    pub fn channel_id(mut self, channel_id: String) -> Self {
-        self.channel_id = Some(channel_id);
-        self
+    fn validate_choice(&self) -> Result<(), InvalidRequestReason> {
+        let poll = match &self.poll {
+            FetchedPoll::NotFetched => return Ok(()),
+            FetchedPoll::NoPoll => return Err(InvalidRequestReason::NoPoll),
+            FetchedPoll::Poll(poll) => poll,
+        };
+        let Some(target) = poll.choices().get(self.choice) else {
+            return Err(InvalidRequestReason::OutOfRange { param: "choice", max: poll.choices().len().saturating_sub(1), actual: self.choice });
+        };
+        if poll.is_expired() {
+            return Err(InvalidRequestReason::PollExpired);
+        }
+        if *target.is_voted() || (!poll.can_choose_multiple() && poll.has_voted()) {
+            return Err(InvalidRequestReason::AlreadyVoted);
+        }
+        Ok(())
     }
 }
 
+/// 投票していない、締め切られていない他のユーザーの投票付きのノートを取得する。
 #[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
-#[misskey_client(endpoint = "/notes/delete", response = (), can_be_empty = true)]
+#[misskey_client(endpoint = "/notes/polls/recommendation", response = Vec<NoteInfo>, credential = true, permissions(ReadAccount), builder)]
 #[serde(rename_all = "camelCase")]
-pub struct DeleteNote {
-    note_id: String,
+pub struct GetRecommendedPolls {
+    /// 1以上100以下. 省略時は10.
+    #[misskey_client(default = 10)]
+    limit: usize,
+    offset: usize,
+    /// チャンネルのノートを除外するか否か
+    exclude_channels: bool,
 }
 
-impl DeleteNote {
-    pub fn new(note_id: impl NoteId) -> Self {
-        Self { note_id: note_id.to_note_id() }
+endpoint_error! {
+    /// `TranslateNote` 固有のエラー
+    pub enum TranslateNoteError {
+        /// サーバーで翻訳が設定されていない。
+        Unavailable = ("UNAVAILABLE", "50a70314-2d8a-431b-b433-efa5cc56444c"),
+        NoSuchNote = ("NO_SUCH_NOTE", "bea9b03f-36e0-49c5-a4db-627a029f8971"),
+        CannotTranslateInvisibleNote = ("CANNOT_TRANSLATE_INVISIBLE_NOTE", "ea29f2ca-c368-43b3-aaf1-5ac3e74bbe5d"),
     }
 }
+
+/// ノートの本文を翻訳する。<br />
+/// 本文のないノートでは `204` が返り、レスポンスは `None` になる。
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/translate", response = NoteTranslationInfo, error = TranslateNoteError, can_be_empty = true, credential = true, permissions(ReadAccount), builder)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslateNote {
+    #[misskey_client(required, into)]
+    note_id: NoteId,
+    /// `ja-JP` や `en-US` のような翻訳先の言語
+    #[misskey_client(required, into)]
+    target_lang: String,
+}
+
+/// サーバーで話題のノートを取得する。
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/featured", response = Vec<NoteInfo>, builder)]
+#[serde(rename_all = "camelCase")]
+pub struct GetFeaturedNotes {
+    /// 1以上100以下. 省略時は10.
+    #[misskey_client(default = 10)]
+    limit: usize,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] until_id: Option<NoteId>,
+    /// 指定したチャンネルで話題のノートを取得する。
+    #[misskey_client(into, setter = channel)]
+    #[serde(skip_serializing_if = "Option::is_none")] channel_id: Option<ChannelId>,
+}
+
+/// ログイン中のユーザーへのメンションとリプライを取得する。
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/mentions", response = Vec<NoteInfo>, credential = true, permissions(ReadAccount), builder)]
+#[serde(rename_all = "camelCase")]
+pub struct GetMentions {
+    /// フォローしているユーザーからのものに限る。
+    following: bool,
+    /// 1以上100以下. 省略時は10.
+    #[misskey_client(default = 10)]
+    limit: usize,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] since_id: Option<NoteId>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] until_id: Option<NoteId>,
+    /// 指定した公開範囲のノートに限る。`NoteVisibility::Specified` でダイレクトのみを取得できる。
+    #[serde(skip_serializing_if = "Option::is_none")] visibility: Option<NoteVisibility>,
+}
+
+paginated_request!(GetNoteChildren, NoteInfo, 100);
+paginated_request!(GetReplies, NoteInfo, 100);
+paginated_request!(GetRenotes, NoteInfo, 100);
+paginated_request!(GetNoteReactions, NoteReactionInfo, 100);
+paginated_request!(GetMentions, NoteInfo, 100);
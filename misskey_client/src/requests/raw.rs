@@ -0,0 +1,152 @@
+//! 任意のエンドポイントへのリクエスト
+
+use std::marker::PhantomData;
+
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::{common::ServerVersion, errors::{InvalidRequestReason, NoEndpointError}, miauth::Permission, JsonRequest};
+
+/// エンドポイントと本文を実行時に指定するリクエスト。<br />
+/// 本文は `Serialize` を実装した任意の型 (既定では `serde_json::Value`) で、JSON のオブジェクトとして送信される。
+/// オブジェクトにならない本文は送信前に `InvalidRequestReason::NotAnObject` として拒否される。
+/// レスポンスは既定では `serde_json::Value` として受け取り、`response` で任意の型に変更できる。
+///
+/// アクセストークンの付与、エラーの変換、空のレスポンスの扱いは型付きのリクエストと同じ。
+#[derive(Debug)]
+pub struct RawRequest<B = Value, R = Value> {
+    endpoint: String,
+    body: B,
+    can_be_empty: bool,
+    requires_credential: bool,
+    permissions: Vec<Permission>,
+    min_version: Option<ServerVersion>,
+    response: PhantomData<fn() -> R>,
+}
+
+impl<B> RawRequest<B> where B: Serialize {
+    /// `endpoint` には `/notes/show` のようなアドレスを指定する。先頭のスラッシュと `/api` は省略できる。
+    pub fn new(endpoint: impl AsRef<str>, body: B) -> Self {
+        let endpoint = endpoint.as_ref().trim_start_matches('/');
+        let endpoint = endpoint.strip_prefix("api/").unwrap_or(endpoint);
+        Self {
+            endpoint: format!("/{}", endpoint),
+            body,
+            can_be_empty: false,
+            requires_credential: false,
+            permissions: Vec::new(),
+            min_version: None,
+            response: PhantomData,
+        }
+    }
+}
+
+impl RawRequest<Value> {
+    /// パラメーターを持たないリクエスト
+    pub fn without_body(endpoint: impl AsRef<str>) -> Self {
+        Self::new(endpoint, Value::Object(Default::default()))
+    }
+}
+
+impl<B, R> RawRequest<B, R> where B: Serialize, for<'de> R: Deserialize<'de> {
+    /// レスポンスを指定した型として受け取る。
+    pub fn response<T>(self) -> RawRequest<B, T> where for<'de> T: Deserialize<'de> {
+        RawRequest {
+            endpoint: self.endpoint,
+            body: self.body,
+            can_be_empty: self.can_be_empty,
+            requires_credential: self.requires_credential,
+            permissions: self.permissions,
+            min_version: self.min_version,
+            response: PhantomData,
+        }
+    }
+
+    /// レスポンスが空 (204 No Content) になりうるか否か
+    pub fn can_be_empty(self, can_be_empty: bool) -> Self {
+        Self {
+            can_be_empty,
+            .. self
+        }
+    }
+
+    /// 認証情報が必要か否か。ログインしていない場合は送信前にエラーとなる。
+    pub fn credential(self, requires_credential: bool) -> Self {
+        Self {
+            requires_credential,
+            .. self
+        }
+    }
+
+    /// トークンに必要な権限。権限が判明している場合は送信前に確認される。
+    pub fn permissions(self, permissions: impl IntoIterator<Item = Permission>) -> Self {
+        Self {
+            permissions: permissions.into_iter().collect(),
+            .. self
+        }
+    }
+
+    /// 対応する最も古い Misskey のバージョン。サーバーの情報を取得済みの場合は送信前に確認される。
+    pub fn min_version(self, min_version: ServerVersion) -> Self {
+        Self {
+            min_version: Some(min_version),
+            .. self
+        }
+    }
+
+    pub fn body(&self) -> &B {
+        &self.body
+    }
+}
+
+impl<B, R> Clone for RawRequest<B, R> where B: Clone {
+    fn clone(&self) -> Self {
+        Self {
+            endpoint: self.endpoint.clone(),
+            body: self.body.clone(),
+            can_be_empty: self.can_be_empty,
+            requires_credential: self.requires_credential,
+            permissions: self.permissions.clone(),
+            min_version: self.min_version.clone(),
+            response: PhantomData,
+        }
+    }
+}
+
+impl<B, R> Serialize for RawRequest<B, R> where B: Serialize {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        self.body.serialize(serializer)
+    }
+}
+
+impl<B, R> JsonRequest for RawRequest<B, R> where B: Serialize, for<'de> R: Deserialize<'de> {
+    type Response = R;
+    type Error = NoEndpointError;
+
+    fn endpoint(&self) -> String {
+        self.endpoint.clone()
+    }
+
+    fn can_be_empty(&self) -> bool {
+        self.can_be_empty
+    }
+
+    fn requires_credential(&self) -> bool {
+        self.requires_credential
+    }
+
+    fn permissions(&self) -> &[Permission] {
+        &self.permissions
+    }
+
+    fn min_version(&self) -> Option<ServerVersion> {
+        self.min_version.clone()
+    }
+
+    fn validate(&self) -> Result<(), InvalidRequestReason> {
+        match serde_json::to_value(&self.body) {
+            Ok(Value::Object(_)) => Ok(()),
+            _ => Err(InvalidRequestReason::NotAnObject),
+        }
+    }
+}
@@ -2,17 +2,25 @@ use chrono::{DateTime, Utc};
 use misskey_client_macroes::ConstParamJsonRequest;
 use serde_derive::Serialize;
 
-use crate::{responses::{notes::NoteInfo, users::{LiteUserInfo, RelationInfo}}, traits::UserId, MaybeMultiple};
+use crate::{common::{NoteId, UserId}, errors::endpoint_error, pagination::paginated_request, responses::{notes::NoteInfo, users::{LiteUserInfo, RelationInfo}}, MaybeMultiple};
+
+endpoint_error! {
+    /// `GetLiteUserInfo` 固有のエラー
+    pub enum GetLiteUserInfoError {
+        FailedToResolveRemoteUser = ("FAILED_TO_RESOLVE_REMOTE_USER", "ef7b9be4-9cba-4e6f-ab41-90ed171c7d3c"),
+        NoSuchUser = ("NO_SUCH_USER", "4362f8dc-731f-4ad8-a694-be5a88922a24"),
+    }
+}
 
 /// ユーザー名をもとに、簡略化されたユーザー情報を取得する
 #[derive(Debug, Serialize, ConstParamJsonRequest)]
-#[misskey_client(endpoint = "/users/show", response = LiteUserInfo)]
+#[misskey_client(endpoint = "/users/show", response = LiteUserInfo, error = GetLiteUserInfoError)]
 #[serde(rename_all = "camelCase")]
 pub struct GetLiteUserInfo<'a> {
     #[serde(skip_serializing_if = "Option::is_none")]
     username: Option<&'a str>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    user_id: Option<&'a str>,
+    user_id: Option<UserId>,
     detailed: bool,
 }
 
@@ -23,138 +31,64 @@ impl<'a> GetLiteUserInfo<'a> {
         }
     }
 
-    pub fn from_id(user_id: &'a str) -> Self {
+    pub fn from_id(user_id: impl Into<UserId>) -> Self {
         Self {
-            username: None, user_id: Some(user_id), detailed: false,
+            username: None, user_id: Some(user_id.into()), detailed: false,
         }
     }
 }
 
-#[derive(Debug, Serialize, ConstParamJsonRequest)]
-#[misskey_client(endpoint = "/users/notes", response = Vec<NoteInfo>)]
+endpoint_error! {
+    /// `GetNotes` 固有のエラー
+    pub enum GetNotesError {
+        NoSuchUser = ("NO_SUCH_USER", "27e494ba-2ac2-48e8-893b-10d4d8c2387b"),
+    }
+}
+
+#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/users/notes", response = Vec<NoteInfo>, error = GetNotesError, builder)]
 #[serde(rename_all = "camelCase")]
-pub struct GetNotes<'a> {
-    user_id: &'a str,
+pub struct GetNotes {
+    #[misskey_client(required, into)]
+    user_id: UserId,
     with_replies: bool,
+    #[misskey_client(default = true)]
     with_renotes: bool,
     with_channel_notes: bool,
     /// 1以上100以下. 省略時は10.
+    #[misskey_client(default = 10)]
     limit: usize,
-    #[serde(skip_serializing_if = "Option::is_none")] since_id: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")] until_id: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")] since_date: Option<i64>,
-    #[serde(skip_serializing_if = "Option::is_none")] until_date: Option<i64>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] since_id: Option<NoteId>,
+    #[misskey_client(into)]
+    #[serde(skip_serializing_if = "Option::is_none")] until_id: Option<NoteId>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "chrono::serde::ts_milliseconds_option")] since_date: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "chrono::serde::ts_milliseconds_option")] until_date: Option<DateTime<Utc>>,
     allow_partial: bool,
     with_files: bool,
 }
 
-impl<'a> GetNotes<'a> {
-    pub fn new(user_id: &'a str) -> Self {
-        Self {
-            user_id,
-            with_replies: false,
-            with_renotes: true,
-            with_channel_notes: false,
-            limit: 10,
-            since_id: None,
-            until_id: None,
-            since_date: None,
-            until_date: None,
-            allow_partial: false,
-            with_files: false,
-        }
-    }
-
-    pub fn with_replies(self, with_replies: bool) -> Self {
-        Self {
-            with_replies,
-            .. self
-        }
-    }
-
-    pub fn with_renotes(self, with_renotes: bool) -> Self {
-        Self {
-            with_renotes,
-            .. self
-        }
-    }
-
-    pub fn with_channel_notes(self, with_channel_notes: bool) -> Self {
-        Self {
-            with_channel_notes,
-            .. self
-        }
-    }
-
-    pub fn limit(self, limit: usize) -> Self {
-        Self {
-            limit,
-            .. self
-        }
-    }
-
-    pub fn since_id(self, since_id: String) -> Self {
-        Self {
-            since_id: Some(since_id),
-            .. self
-        }
-    }
-
-    pub fn until_id(self, until_id: String) -> Self {
-        Self {
-            until_id: Some(until_id),
-            .. self
-        }
-    }
-
-    pub fn since_date(self, since_date: DateTime<Utc>) -> Self {
-        Self {
-            since_date: Some(since_date.timestamp()),
-            .. self
-        }
-    }
-
-    pub fn until_date(self, until_date: DateTime<Utc>) -> Self {
-        Self {
-            until_date: Some(until_date.timestamp()),
-            .. self
-        }
-    }
-
-    pub fn allow_partial(self, allow_partial: bool) -> Self {
-        Self {
-            allow_partial,
-            .. self
-        }
-    }
-
-    pub fn with_files(self, with_files: bool) -> Self {
-        Self {
-            with_files,
-            .. self
-        }
-    }
-}
-
 #[derive(Debug, Serialize, ConstParamJsonRequest)]
-#[misskey_client(endpoint = "/users/relation", response = MaybeMultiple<RelationInfo>)]
+#[misskey_client(endpoint = "/users/relation", response = MaybeMultiple<RelationInfo>, credential = true, permissions(ReadAccount))]
 pub struct GetRelation {
-    #[serde(rename = "userId", skip_serializing_if = "Option::is_none")] user_id: Option<String>,
-    #[serde(rename = "userId", skip_serializing_if = "Vec::is_empty")] user_ids: Vec<String>,
+    #[serde(rename = "userId", skip_serializing_if = "Option::is_none")] user_id: Option<UserId>,
+    #[serde(rename = "userId", skip_serializing_if = "Vec::is_empty")] user_ids: Vec<UserId>,
 }
 
 impl GetRelation {
-    pub fn single(user_id: impl UserId) -> Self {
+    pub fn single(user_id: impl Into<UserId>) -> Self {
         Self {
-            user_id: Some(user_id.to_user_id()),
+            user_id: Some(user_id.into()),
             user_ids: Vec::with_capacity(0),
         }
     }
 
-    pub fn multiple<T: UserId>(user_ids: impl IntoIterator<Item = T>) -> Self {
+    pub fn multiple<T: Into<UserId>>(user_ids: impl IntoIterator<Item = T>) -> Self {
         Self {
             user_id: None,
-            user_ids: user_ids.into_iter().map(T::to_user_id).collect(),
+            user_ids: user_ids.into_iter().map(Into::into).collect(),
         }
     }
 }
+
+paginated_request!(GetNotes, NoteInfo, 100);
@@ -1,5 +1,9 @@
+pub mod announcements;
 pub mod channels;
 pub mod charts;
+pub mod meta;
+pub mod nodeinfo;
 pub mod notifications;
 pub mod notes;
+pub mod pages;
 pub mod users;
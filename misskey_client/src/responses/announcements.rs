@@ -0,0 +1,43 @@
+use chrono::{DateTime, Utc};
+use derive_getters::Getters;
+use misskey_client_macroes::Schema;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::common::string_enum;
+
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnouncementInfo {
+    id: String,
+    #[serde(serialize_with = "crate::schema::date_time")] created_at: DateTime<Utc>,
+    #[serde(serialize_with = "crate::schema::date_time")] updated_at: Option<DateTime<Utc>>,
+    text: String,
+    title: String,
+    image_url: Option<String>,
+    icon: AnnouncementIcon,
+    display: AnnouncementDisplay,
+    #[serde(default)] need_confirmation_to_read: bool,
+    #[serde(default)] silence: bool,
+    #[serde(default)] for_you: bool,
+    is_read: Option<bool>,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
+}
+
+string_enum! {
+    pub enum AnnouncementIcon {
+        Info = "info",
+        Warning = "warning",
+        Error = "error",
+        Success = "success",
+    }
+}
+
+string_enum! {
+    /// お知らせの表示方法
+    pub enum AnnouncementDisplay {
+        Normal = "normal",
+        Banner = "banner",
+        Dialog = "dialog",
+    }
+}
@@ -1,20 +1,21 @@
 use chrono::{DateTime, Utc};
 use derive_getters::Getters;
-use serde_derive::Deserialize;
+use misskey_client_macroes::Schema;
+use serde_derive::{Deserialize, Serialize};
 
-use crate::responses::notes::NoteInfo;
+use crate::{common::{ChannelId, NoteId, UserId}, responses::notes::NoteInfo};
 
-#[derive(Debug, Deserialize, Getters)]
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ChannelInfo {
-    id: String,
-    created_at: DateTime<Utc>,
-    last_noted_at: Option<DateTime<Utc>>,
+    id: ChannelId,
+    #[serde(serialize_with = "crate::schema::date_time")] created_at: DateTime<Utc>,
+    #[serde(serialize_with = "crate::schema::date_time")] last_noted_at: Option<DateTime<Utc>>,
     name: String,
     description: Option<String>,
-    user_id: Option<String>,
+    user_id: Option<UserId>,
     banner_url: Option<String>,
-    #[serde(default)] pinned_note_ids: Vec<String>,
+    #[serde(default)] pinned_note_ids: Vec<NoteId>,
     color: String,
     is_archived: bool,
     users_count: usize,
@@ -24,15 +25,31 @@ pub struct ChannelInfo {
     is_following: bool,
     is_favorited: bool,
     #[serde(default)] pinned_notes: Vec<NoteInfo>,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
 }
 
-#[derive(Debug, Deserialize, Getters)]
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LiteChannelInfo {
-    id: String,
+    id: ChannelId,
     name: String,
-    user_id: Option<String>,
+    user_id: Option<UserId>,
     color: String,
     is_sensitive: bool,
     allow_renote_to_external: bool,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
+}
+
+impl From<&ChannelInfo> for ChannelId {
+    fn from(value: &ChannelInfo) -> Self {
+        value.id.clone()
+    }
+}
+
+impl From<&LiteChannelInfo> for ChannelId {
+    fn from(value: &LiteChannelInfo) -> Self {
+        value.id.clone()
+    }
 }
@@ -1,7 +1,8 @@
 use derive_getters::Getters;
-use serde_derive::Deserialize;
+use misskey_client_macroes::Schema;
+use serde_derive::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Getters)]
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ActiveUserChart {
     read_write: Vec<usize>,
@@ -13,33 +14,41 @@ pub struct ActiveUserChart {
     registered_outside_week: Vec<usize>,
     registered_outside_month: Vec<usize>,
     registered_outside_year: Vec<usize>,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
 }
 
-#[derive(Debug, Deserialize, Getters)]
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ApRequestChart {
     deliver_failed: Vec<usize>,
     deliver_succeeded: Vec<usize>,
-    inbox_received: Vec<usize>
+    inbox_received: Vec<usize>,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
 }
 
-#[derive(Debug, Deserialize, Getters)]
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DriveChart {
     local: DriveChartDetail,
     remote: DriveChartDetail,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
 }
 
-#[derive(Debug, Deserialize, Getters)]
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DriveChartDetail {
     inc_count: Vec<usize>,
     inc_size: Vec<usize>,
     dec_count: Vec<usize>,
     dec_size: Vec<usize>,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
 }
 
-#[derive(Debug, Deserialize, Getters)]
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FederationChart {
     delivered_instances: Vec<usize>,
@@ -52,4 +61,6 @@ pub struct FederationChart {
     pubsub: Vec<usize>,
     sub_active: Vec<usize>,
     pub_active: Vec<usize>,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
 }
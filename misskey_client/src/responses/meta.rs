@@ -0,0 +1,61 @@
+use derive_getters::Getters;
+use misskey_client_macroes::Schema;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// `/meta` の応答
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetaInfo {
+    maintainer_name: Option<String>,
+    maintainer_email: Option<String>,
+    /// サーバーのソフトウェアのバージョン。フォークの場合はフォーク自身のバージョン
+    version: String,
+    /// フォークが報告する、基にした Misskey のバージョン
+    #[serde(skip_serializing_if = "Option::is_none")] based_misskey_version: Option<String>,
+    name: Option<String>,
+    #[serde(default)] short_name: Option<String>,
+    uri: String,
+    description: Option<String>,
+    #[serde(default)] langs: Vec<String>,
+    #[serde(default)] tos_url: Option<String>,
+    #[serde(default)] repository_url: Option<String>,
+    #[serde(default)] feedback_url: Option<String>,
+    #[serde(default)] icon_url: Option<String>,
+    #[serde(default)] banner_url: Option<String>,
+    #[serde(default)] theme_color: Option<String>,
+    #[serde(default)] disable_registration: bool,
+    #[serde(default)] email_required_for_signup: bool,
+    #[serde(default)] enable_hcaptcha: bool,
+    #[serde(default)] enable_recaptcha: bool,
+    #[serde(default)] enable_turnstile: bool,
+    #[serde(default)] enable_email: bool,
+    #[serde(default)] enable_service_worker: bool,
+    #[serde(default)] translator_available: bool,
+    max_note_text_length: usize,
+    #[serde(default)] server_rules: Vec<String>,
+    /// 未ログイン時に適用されるロールのポリシー
+    #[serde(default)] policies: Map<String, Value>,
+    /// `detail` を指定した場合のみ含まれる。
+    #[serde(default)] features: Option<MetaFeatures>,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
+}
+
+/// サーバーで有効になっている機能
+#[derive(Clone, Debug, Default, Deserialize, Getters, Schema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetaFeatures {
+    #[serde(default)] registration: bool,
+    #[serde(default)] email_required_for_signup: bool,
+    #[serde(default)] local_timeline: bool,
+    #[serde(default)] global_timeline: bool,
+    #[serde(default)] hcaptcha: bool,
+    #[serde(default)] recaptcha: bool,
+    #[serde(default)] turnstile: bool,
+    #[serde(default)] object_storage: bool,
+    #[serde(default)] service_worker: bool,
+    #[serde(default)] miauth: bool,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
+}
@@ -0,0 +1,71 @@
+use derive_getters::Getters;
+use misskey_client_macroes::Schema;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// `/.well-known/nodeinfo` の応答
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
+pub struct NodeInfoLinks {
+    links: Vec<NodeInfoLink>,
+}
+
+impl NodeInfoLinks {
+    /// 対応しているうち最も新しいスキーマの NodeInfo へのリンク
+    pub fn latest(&self) -> Option<&NodeInfoLink> {
+        self.links.iter().filter(|a| a.schema_version().is_some()).max_by(|a, b| a.schema_version().cmp(&b.schema_version()))
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
+pub struct NodeInfoLink {
+    /// `http://nodeinfo.diaspora.software/ns/schema/2.1` のようなスキーマの URI
+    rel: String,
+    href: String,
+}
+
+impl NodeInfoLink {
+    /// `rel` に含まれるスキーマのバージョン。NodeInfo のスキーマでない場合は `None`
+    pub fn schema_version(&self) -> Option<&str> {
+        self.rel.strip_prefix("http://nodeinfo.diaspora.software/ns/schema/")
+    }
+}
+
+/// NodeInfo 2.0 および 2.1
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeInfo {
+    version: String,
+    software: NodeInfoSoftware,
+    #[serde(default)] protocols: Vec<String>,
+    #[serde(default)] open_registrations: bool,
+    #[serde(default)] usage: Option<NodeInfoUsage>,
+    /// ソフトウェアごとの追加情報
+    #[serde(default)] metadata: Map<String, Value>,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
+}
+
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
+pub struct NodeInfoSoftware {
+    /// `misskey` のような小文字のソフトウェア名
+    name: String,
+    version: String,
+    #[serde(default)] repository: Option<String>,
+    #[serde(default)] homepage: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeInfoUsage {
+    #[serde(default)] users: NodeInfoUsers,
+    #[serde(default)] local_posts: Option<u64>,
+    #[serde(default)] local_comments: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Getters, Schema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeInfoUsers {
+    #[serde(default)] total: Option<u64>,
+    #[serde(default)] active_halfyear: Option<u64>,
+    #[serde(default)] active_month: Option<u64>,
+}
@@ -2,71 +2,103 @@ use std::collections::BTreeMap;
 
 use chrono::{DateTime, Utc};
 use derive_getters::Getters;
-use serde_derive::Deserialize;
+use misskey_client_macroes::Schema;
+use serde_derive::{Deserialize, Serialize};
 
-use crate::{common::NoteVisibility, traits::NoteId, UnknownValue};
+use crate::{common::{ChannelId, DriveFileId, DriveFolderId, NoteFavoriteId, NoteId, NoteReactionId, NoteVisibility, Reaction, ReactionAcceptance, RoleId, UserId}};
 
 use super::{channels::LiteChannelInfo, users::LiteUserInfo};
 
-#[derive(Debug, Deserialize, Getters)]
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct NoteInfo {
-    id: String,
-    created_at: DateTime<Utc>,
-    deleted_at: Option<DateTime<Utc>>,
+    id: NoteId,
+    #[serde(serialize_with = "crate::schema::date_time")] created_at: DateTime<Utc>,
+    /// 最後に編集された日時。編集されていないか、サーバーが編集に対応していない場合は `None`
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "crate::schema::date_time")] updated_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "crate::schema::date_time")] deleted_at: Option<DateTime<Utc>>,
     text: Option<String>,
     cw: Option<String>,
-    user_id: String,
+    user_id: UserId,
     user: LiteUserInfo,
-    reply_id: Option<String>,
-    renote_id: Option<String>,
-    reply: Option<Box<NoteInfo>>,
-    renote: Option<Box<NoteInfo>>,
-    #[serde(default)] is_hidden: bool,
+    reply_id: Option<NoteId>,
+    renote_id: Option<NoteId>,
+    #[serde(skip_serializing_if = "Option::is_none")] reply: Option<Box<NoteInfo>>,
+    #[serde(skip_serializing_if = "Option::is_none")] renote: Option<Box<NoteInfo>>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")] is_hidden: bool,
     visibility: NoteVisibility,
-    #[serde(default)] mentions: Vec<String>,
-    #[serde(default)] visible_user_ids: Vec<String>,
-    #[serde(default)] file_ids: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")] mentions: Vec<UserId>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")] visible_user_ids: Vec<UserId>,
+    #[serde(default)] file_ids: Vec<DriveFileId>,
     #[serde(default)] files: Vec<FileInfo>,
-    #[serde(default)] tags: Vec<String>,
-    poll: Option<PollInfo>,
-    emojis: Option<UnknownValue>, // 用途不明
-    channel_id: Option<String>,
-    channel: Option<LiteChannelInfo>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")] tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")] poll: Option<PollInfo>,
+    #[serde(default)] emojis: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")] channel_id: Option<ChannelId>,
+    #[serde(skip_serializing_if = "Option::is_none")] channel: Option<LiteChannelInfo>,
     local_only: bool,
-    reaction_acceptance: Option<String>,
+    reaction_acceptance: Option<ReactionAcceptance>,
     reaction_emojis: BTreeMap<String, usize>,
-    reactions: BTreeMap<String, usize>,
+    reactions: BTreeMap<Reaction, usize>,
     reaction_count: usize,
     renote_count: usize,
     replies_count: usize,
-    uri: Option<String>,
-    url: Option<String>,
-    #[serde(default)] reaction_and_user_pair_cache: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")] uri: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")] url: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")] reaction_and_user_pair_cache: Vec<String>,
     clipped_count: usize,
-    my_reaction: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")] my_reaction: Option<Reaction>,
+    /// 編集された日時の履歴。CherryPick のように履歴を公開するサーバーでのみ含まれる。
+    #[serde(default, skip_serializing_if = "Vec::is_empty", serialize_with = "crate::schema::date_time")] updated_at_history: Vec<DateTime<Utc>>,
+    /// 編集前の本文の履歴。`updated_at_history` と同じ順に並ぶ。
+    #[serde(default, skip_serializing_if = "Vec::is_empty")] note_edit_history: Vec<String>,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
 }
 
-impl NoteId for NoteInfo {
-    fn to_note_id(self) -> String {
-        self.id
+impl NoteInfo {
+    /// ログイン中のユーザーのリアクションが、いいねに置き換えられずにそのまま受け付けられるか否か。<br />
+    /// Misskey はローカルのユーザーからのリモートのカスタム絵文字をいいねに置き換える。
+    pub fn accepts_reaction(&self, reaction: &Reaction) -> bool {
+        !matches!(reaction, Reaction::Remote { .. }) && self.reaction_acceptance.as_ref().is_none_or(|a| a.accepts(reaction, false))
+    }
+
+    /// 編集されたことがあるか否か
+    pub fn is_edited(&self) -> bool {
+        self.updated_at.is_some() || !self.updated_at_history.is_empty()
+    }
+
+    /// 編集された日時と編集前の本文の組。履歴を公開していないサーバーでは空になる。
+    pub fn edit_history(&self) -> impl Iterator<Item = (&DateTime<Utc>, &str)> {
+        self.updated_at_history.iter().zip(self.note_edit_history.iter().map(String::as_str))
+    }
+
+    /// 本文が `text` と一致する投票の選択肢の位置。投票がない場合も `None`
+    pub fn poll_choice_index(&self, text: &str) -> Option<usize> {
+        self.poll.as_ref()?.choices.iter().position(|a| a.text == text)
+    }
+}
+
+impl From<NoteInfo> for NoteId {
+    fn from(value: NoteInfo) -> Self {
+        value.id
     }
 }
 
-impl NoteId for &NoteInfo {
-    fn to_note_id(self) -> String {
-        self.id.clone()
+impl From<&NoteInfo> for NoteId {
+    fn from(value: &NoteInfo) -> Self {
+        value.id.clone()
     }
 }
 
-#[derive(Debug, Deserialize, Getters)]
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FileInfo {
     blurhash: Option<String>,
     comment: Option<String>,
-    created_at: DateTime<Utc>,
-    folder_id: Option<String>,
-    id: String,
+    #[serde(serialize_with = "crate::schema::date_time")] created_at: DateTime<Utc>,
+    folder_id: Option<DriveFolderId>,
+    id: DriveFileId,
     is_sensitive: bool,
     md5: String,
     name: String,
@@ -75,49 +107,77 @@ pub struct FileInfo {
     thumbnail_url: Option<String>,
     #[serde(rename = "type")] file_type: String,
     url: String,
-    user_id: Option<String>,
+    user_id: Option<UserId>,
     folder: Option<FolderInfo>,
     user: Option<LiteUserInfo>,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
 }
 
-#[derive(Debug, Deserialize, Getters)]
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FolderInfo {
-    created_at: DateTime<Utc>,
-    id: String,
+    #[serde(serialize_with = "crate::schema::date_time")] created_at: DateTime<Utc>,
+    id: DriveFolderId,
     name: String,
-    parent_id: Option<String>,
+    parent_id: Option<DriveFolderId>,
     filed_count: usize,
     folders_count: usize,
     parent: Option<Box<FolderInfo>>,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
 }
 
-#[derive(Debug, Deserialize, Getters)]
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FileProperties {
     avg_color: Option<String>,
     height: Option<usize>,
     orientation: Option<usize>,
     width: Option<usize>,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
 }
 
-#[derive(Debug, Deserialize, Getters)]
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PollInfo {
-    expires_at: Option<DateTime<Utc>>,
-    can_choose_multiple: bool,
+    #[serde(default, serialize_with = "crate::schema::date_time")] expires_at: Option<DateTime<Utc>>,
+    /// Misskey の応答では `multiple`
+    #[serde(rename = "multiple", alias = "canChooseMultiple")] can_choose_multiple: bool,
     choices: Vec<PollChoiceInfo>,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
 }
 
-#[derive(Debug, Deserialize, Getters)]
+impl PollInfo {
+    /// 締め切られているか否か
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.is_some_and(|a| a <= Utc::now())
+    }
+
+    /// ログイン中のユーザーがいずれかの選択肢に投票済みか否か
+    pub fn has_voted(&self) -> bool {
+        self.choices.iter().any(|a| a.is_voted)
+    }
+
+    /// 投票の総数。複数選択の投票では投票したユーザーの数と一致しない。
+    pub fn total_votes(&self) -> usize {
+        self.choices.iter().map(|a| a.votes).sum()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PollChoiceInfo {
     is_voted: bool,
     text: String,
     votes: usize,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Schema, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreatedNoteInfo {
     created_note: NoteInfo,
@@ -129,21 +189,79 @@ impl CreatedNoteInfo {
     }
 }
 
-#[derive(Debug, Deserialize, Getters)]
+/// ノートに付けられたリアクション
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteReactionInfo {
+    id: NoteReactionId,
+    #[serde(serialize_with = "crate::schema::date_time")] created_at: DateTime<Utc>,
+    user: LiteUserInfo,
+    #[serde(rename = "type")] reaction: Reaction,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
+}
+
+/// お気に入りに登録したノート
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteFavoriteInfo {
+    /// お気に入りの ID。ページングに使用する。
+    id: NoteFavoriteId,
+    /// お気に入りに登録した日時
+    #[serde(serialize_with = "crate::schema::date_time")] created_at: DateTime<Utc>,
+    note_id: NoteId,
+    note: NoteInfo,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
+}
+
+impl From<NoteFavoriteInfo> for NoteInfo {
+    fn from(value: NoteFavoriteInfo) -> Self {
+        value.note
+    }
+}
+
+/// ノートの本文の翻訳
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteTranslationInfo {
+    /// 翻訳サービスが検出した翻訳元の言語
+    source_lang: String,
+    text: String,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
+}
+
+/// ログイン中のユーザーから見たノートの状態
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteStateInfo {
+    is_favorited: bool,
+    /// ノートのスレッドをミュートしているか否か
+    is_muted_thread: bool,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
+}
+
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AchievementInfo {
     name: String,
     unlocked_at: usize,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
 }
 
-#[derive(Debug, Deserialize, Getters)]
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FieldInfo {
     name: String,
     value: String,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
 }
 
-#[derive(Debug, Deserialize, Getters)]
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UserPolicies {
     always_mark_nsfw: bool,
@@ -187,17 +305,21 @@ pub struct UserPolicies {
     user_list_limit: usize,
     webhook_limit: usize,
     word_mute_limit: usize,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
 }
 
-#[derive(Debug, Deserialize, Getters)]
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RoleInfo {
     color: Option<String>,
     description: String,
     display_order: i32,
     icon_url: Option<String>,
-    id: String,
+    id: RoleId,
     is_administrator: bool,
     is_moderator: bool,
     name: String,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
 }
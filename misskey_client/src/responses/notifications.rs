@@ -1,80 +1,115 @@
+use chrono::{DateTime, Utc};
 use derive_getters::Getters;
+use misskey_client_macroes::Schema;
+use serde::{Deserialize, Deserializer};
 use serde_derive::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
-use crate::UnknownValue;
+use crate::{common::{string_enum, DriveFileId, NotificationId, NotificationType, Reaction, RoleId, UserId}, schema};
 
 use super::{notes::{NoteInfo, UserPolicies}, users::LiteUserInfo};
 
-#[derive(Debug, Deserialize, Getters)]
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct NotificationInfo {
-    id: String,
-    created_at: String,
+    id: NotificationId,
+    #[serde(serialize_with = "crate::schema::date_time")] created_at: DateTime<Utc>,
     #[serde(flatten)]
     notification: NotificationDetail,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Schema, Serialize)]
 #[serde(rename_all = "camelCase", rename_all_fields = "camelCase", tag = "type")]
 pub enum NotificationDetail {
     Note {
         user: LiteUserInfo,
-        user_id: String,
+        user_id: UserId,
         note: NoteInfo,
     },
     Mention {
         user: LiteUserInfo,
-        user_id: String,
+        user_id: UserId,
         note: NoteInfo,
     },
     Reply {
         user: LiteUserInfo,
-        user_id: String,
+        user_id: UserId,
         note: NoteInfo,
     },
     Renote {
         user: LiteUserInfo,
-        user_id: String,
+        user_id: UserId,
         note: NoteInfo,
     },
     Quote {
         user: LiteUserInfo,
-        user_id: String,
+        user_id: UserId,
         note: NoteInfo,
     },
     Reaction {
         user: LiteUserInfo,
-        user_id: String,
+        user_id: UserId,
         note: NoteInfo,
-        reaction: String,
+        reaction: Reaction,
     },
     PollEnded {
         user: LiteUserInfo,
-        user_id: String,
+        user_id: UserId,
+        note: NoteInfo,
+    },
+    /// 古いサーバーでのみ送信される。
+    PollVote {
+        user: LiteUserInfo,
+        user_id: UserId,
         note: NoteInfo,
+        choice: usize,
     },
     Follow {
         user: LiteUserInfo,
-        user_id: String,
+        user_id: UserId,
     },
     ReceiveFollowRequest {
         user: LiteUserInfo,
-        user_id: String,
+        user_id: UserId,
     },
     FollowRequestAccepted {
         user: LiteUserInfo,
-        user_id: String,
+        user_id: UserId,
+        #[serde(default)]
+        message: Option<String>,
+    },
+    FollowRequestRejected {
+        user: LiteUserInfo,
+        user_id: UserId,
+        #[serde(default)]
+        message: Option<String>,
     },
     RoleAssigned {
         role: RoleInfo,
     },
+    ChatRoomInvitationReceived {
+        invitation: ChatRoomInvitationInfo,
+    },
     AchievementEarned {
         achievement: String,
     },
+    /// エクスポートしたファイルがドライブに保存された。
+    ExportCompleted {
+        exported_entity: ExportedEntity,
+        file_id: DriveFileId,
+    },
+    /// アカウントにログインされた。
+    Login,
+    /// アクセストークンが作成された。
+    CreateToken,
+    /// 予約投稿が投稿された。
+    ScheduledNotePosted {
+        note: NoteInfo,
+    },
     App {
         body: String,
-        header: String,
-        icon: String,
+        header: Option<String>,
+        icon: Option<String>,
     },
     #[serde(rename = "reaction:grouped")]
     ReactionGrouped {
@@ -89,18 +124,128 @@ pub enum NotificationDetail {
         users: Vec<LiteUserInfo>,
     },
     Test,
+    /// このクレートが対応していない種類の通知
+    #[serde(untagged)]
+    Other {
+        #[serde(rename = "type", deserialize_with = "unknown_notification_type")]
+        notification_type: String,
+        #[serde(flatten)]
+        body: Map<String, Value>,
+    },
+}
+
+impl NotificationDetail {
+    /// 通知の種類。グループ化された通知は元の種類を返す。
+    pub fn notification_type(&self) -> NotificationType {
+        use NotificationDetail::*;
+        match self {
+            Note { .. } => NotificationType::Note,
+            Mention { .. } => NotificationType::Mention,
+            Reply { .. } => NotificationType::Reply,
+            Renote { .. } | RenoteGrouped { .. } => NotificationType::Renote,
+            Quote { .. } => NotificationType::Quote,
+            Reaction { .. } | ReactionGrouped { .. } => NotificationType::Reaction,
+            PollEnded { .. } => NotificationType::PollEnded,
+            PollVote { .. } => NotificationType::PollVote,
+            Follow { .. } => NotificationType::Follow,
+            ReceiveFollowRequest { .. } => NotificationType::ReceiveFollowRequest,
+            FollowRequestAccepted { .. } => NotificationType::FollowRequestAccepted,
+            FollowRequestRejected { .. } => NotificationType::FollowRequestRejected,
+            RoleAssigned { .. } => NotificationType::RoleAssigned,
+            ChatRoomInvitationReceived { .. } => NotificationType::ChatRoomInvitationReceived,
+            AchievementEarned { .. } => NotificationType::AchievementEarned,
+            ExportCompleted { .. } => NotificationType::ExportCompleted,
+            Login => NotificationType::Login,
+            CreateToken => NotificationType::CreateToken,
+            ScheduledNotePosted { .. } => NotificationType::ScheduledNotePosted,
+            App { .. } => NotificationType::App,
+            Test => NotificationType::Test,
+            Other { notification_type, .. } => NotificationType::Other(notification_type.clone()),
+        }
+    }
+
+    /// グループ化された通知か否か
+    pub fn is_grouped(&self) -> bool {
+        matches!(self, NotificationDetail::ReactionGrouped { .. } | NotificationDetail::RenoteGrouped { .. })
+    }
+}
+
+impl NotificationInfo {
+    pub fn notification_type(&self) -> NotificationType {
+        self.notification.notification_type()
+    }
+}
+
+/// 対応している種類の通知が `Other` として解釈されないよう、既知の `type` を拒否する。
+fn unknown_notification_type<'de, D>(deserializer: D) -> Result<String, D::Error> where D: Deserializer<'de> {
+    let notification_type = String::deserialize(deserializer)?;
+    let known = match notification_type.as_str() {
+        "reaction:grouped" | "renote:grouped" => true,
+        a => !matches!(a.parse(), Ok(NotificationType::Other(_))),
+    };
+    if known {
+        return Err(serde::de::Error::custom(format!("invalid `{}` notification", notification_type)));
+    }
+    schema::record_unknown_value("NotificationDetail", &notification_type);
+    Ok(notification_type)
+}
+
+string_enum! {
+    /// エクスポートの対象
+    pub enum ExportedEntity {
+        Antenna = "antenna",
+        Blocking = "blocking",
+        Clip = "clip",
+        CustomEmoji = "customEmoji",
+        Favorite = "favorite",
+        Following = "following",
+        Muting = "muting",
+        Note = "note",
+        UserList = "userList",
+    }
 }
 
-#[derive(Debug, Deserialize, Getters)]
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatRoomInvitationInfo {
+    id: String,
+    #[serde(serialize_with = "crate::schema::date_time")] created_at: DateTime<Utc>,
+    user_id: UserId,
+    user: LiteUserInfo,
+    invitee_id: UserId,
+    room_id: String,
+    room: ChatRoomInfo,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
+}
+
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatRoomInfo {
+    id: String,
+    #[serde(serialize_with = "crate::schema::date_time")] created_at: DateTime<Utc>,
+    owner_id: UserId,
+    owner: LiteUserInfo,
+    name: String,
+    #[serde(default)] description: String,
+    #[serde(skip_serializing_if = "Option::is_none")] is_muted: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")] invitation_exists: Option<bool>,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
+}
+
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
 pub struct UserReactionPair {
     user: LiteUserInfo,
-    reaction: String,
+    reaction: Reaction,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
 }
 
-#[derive(Debug, Deserialize, Getters)]
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RoleInfo {
-    id: String,
+    id: RoleId,
     name: String,
     color: Option<String>,
     icon_url: Option<String>,
@@ -108,10 +253,10 @@ pub struct RoleInfo {
     is_moderator: bool,
     is_administrator: bool,
     display_order: i32,
-    created_at: String,
-    updated_at: String,
+    #[serde(serialize_with = "crate::schema::date_time")] created_at: DateTime<Utc>,
+    #[serde(serialize_with = "crate::schema::date_time")] updated_at: DateTime<Utc>,
     target: RoleTarget,
-    cond_formula: UnknownValue,
+    cond_formula: RoleCondFormula,
     is_public: bool,
     is_explorable: bool,
     as_badge: bool,
@@ -119,13 +264,83 @@ pub struct RoleInfo {
     can_edit_members_by_moderator: bool,
     policies: UserPolicies,
     users_count: usize,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub enum RoleTarget {
-    Manual,
-    Conditional,
+string_enum! {
+    pub enum RoleTarget {
+        Manual = "manual",
+        Conditional = "conditional",
+    }
+}
+
+/// ロールを自動で割り当てる条件の式。<br />
+/// 手動で割り当てるロールでは空のオブジェクトになり、`condition` は `RoleCondition::Other` になる。
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
+pub struct RoleCondFormula {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    id: String,
+    #[serde(flatten)]
+    condition: RoleCondition,
+}
+
+#[derive(Clone, Debug, Deserialize, Schema, Serialize)]
+#[serde(rename_all = "camelCase", rename_all_fields = "camelCase", tag = "type")]
+pub enum RoleCondition {
+    And {
+        values: Vec<RoleCondFormula>,
+    },
+    Or {
+        values: Vec<RoleCondFormula>,
+    },
+    Not {
+        value: Box<RoleCondFormula>,
+    },
+    IsLocal,
+    IsRemote,
+    IsSuspended,
+    IsLocked,
+    IsBot,
+    IsCat,
+    IsExplorable,
+    RoleAssignedTo {
+        role_id: RoleId,
+    },
+    /// アカウントの作成から指定した秒数が経過していない。
+    CreatedLessThan {
+        sec: u64,
+    },
+    /// アカウントの作成から指定した秒数が経過している。
+    CreatedMoreThan {
+        sec: u64,
+    },
+    FollowersLessThanOrEq {
+        value: u64,
+    },
+    FollowersMoreThanOrEq {
+        value: u64,
+    },
+    FollowingLessThanOrEq {
+        value: u64,
+    },
+    FollowingMoreThanOrEq {
+        value: u64,
+    },
+    NotesLessThanOrEq {
+        value: u64,
+    },
+    NotesMoreThanOrEq {
+        value: u64,
+    },
+    IsFromInstance {
+        host: String,
+        #[serde(default)]
+        subdomains: bool,
+    },
+    /// 空の式、またはこのクレートが対応していない条件
+    #[serde(untagged, deserialize_with = "schema::unknown_variant::<RoleCondition, _>")]
+    Other(Map<String, Value>),
 }
 
 // pub struct RolePolicy {
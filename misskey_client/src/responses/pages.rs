@@ -0,0 +1,64 @@
+use chrono::{DateTime, Utc};
+use derive_getters::Getters;
+use misskey_client_macroes::Schema;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{common::{DriveFileId, NoteId, PageId, UserId}, schema};
+
+use super::{notes::FileInfo, users::LiteUserInfo};
+
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageInfo {
+    id: PageId,
+    #[serde(serialize_with = "crate::schema::date_time")] created_at: DateTime<Utc>,
+    #[serde(serialize_with = "crate::schema::date_time")] updated_at: DateTime<Utc>,
+    user_id: UserId,
+    user: LiteUserInfo,
+    #[serde(default)] content: Vec<PageBlock>,
+    #[serde(default)] variables: Vec<Value>,
+    title: String,
+    name: String,
+    summary: Option<String>,
+    #[serde(default)] hide_title_when_pinned: bool,
+    #[serde(default)] align_center: bool,
+    font: String,
+    #[serde(default)] script: String,
+    eye_catching_image_id: Option<DriveFileId>,
+    eye_catching_image: Option<FileInfo>,
+    #[serde(default)] attached_files: Vec<FileInfo>,
+    #[serde(default)] liked_count: usize,
+    is_liked: Option<bool>,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
+}
+
+/// ページの本文を構成するブロック
+#[derive(Clone, Debug, Deserialize, Schema, Serialize)]
+#[serde(rename_all = "camelCase", rename_all_fields = "camelCase", tag = "type")]
+pub enum PageBlock {
+    Text {
+        id: String,
+        text: String,
+    },
+    Section {
+        id: String,
+        title: String,
+        #[serde(default)]
+        children: Vec<PageBlock>,
+    },
+    Image {
+        id: String,
+        file_id: Option<DriveFileId>,
+    },
+    Note {
+        id: String,
+        #[serde(default)]
+        detailed: bool,
+        note: Option<NoteId>,
+    },
+    /// このクレートが対応していない種類のブロック
+    #[serde(untagged, deserialize_with = "schema::unknown_variant::<PageBlock, _>")]
+    Other(Map<String, Value>),
+}
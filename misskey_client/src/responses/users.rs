@@ -1,33 +1,50 @@
-use std::{fmt::{Debug, Display}, str::FromStr};
+use std::{collections::{BTreeMap, HashMap}, fmt::{Debug, Display}, str::FromStr};
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
 use derive_getters::Getters;
+use misskey_client_macroes::Schema;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_derive::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
-use crate::{common::StatusVisibility, errors::InvalidEnumString, responses::notes::{AchievementInfo, FieldInfo, NoteInfo, RoleInfo, UserPolicies}, UnknownValue};
+use crate::{common::{string_enum, DriveFileId, NoteId, NotificationType, PageId, StatusVisibility, UserId}, errors::InvalidDateString, schema, responses::{announcements::AnnouncementInfo, notes::{AchievementInfo, FieldInfo, NoteInfo, RoleInfo, UserPolicies}, pages::PageInfo}};
 
-#[derive(Debug, Deserialize, Getters)]
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LiteUserInfo {
-    avatar_blur_hash: Option<String>,
+    avatar_blurhash: Option<String>,
     avatar_decorations: Vec<AvatarDecorationInfo>,
     avatar_url: Option<String>,
-    emojis: UnknownValue, // 用途不明
+    #[serde(default)] emojis: BTreeMap<String, String>,
     host: Option<String>,
-    id: String,
+    id: UserId,
     name: Option<String>,
     online_status: OnlineStatus,
     username: String,
     #[serde(default)] badge_roles: Vec<BadgeRoleInfo>,
-    instance: Option<InstanceInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")] instance: Option<InstanceInfo>,
     is_bot: bool,
     is_cat: bool,
     // make_notes_followers_only_before: Option<i32>,
     // make_notes_hidden_before: Option<i32>,
     // require_signin_to_view_cotents: bool,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
+}
+
+impl From<LiteUserInfo> for UserId {
+    fn from(value: LiteUserInfo) -> Self {
+        value.id
+    }
+}
+
+impl From<&LiteUserInfo> for UserId {
+    fn from(value: &LiteUserInfo) -> Self {
+        value.id.clone()
+    }
 }
 
-#[derive(Debug, Deserialize, Getters)]
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct InstanceInfo {
     name: Option<String>,
@@ -36,43 +53,20 @@ pub struct InstanceInfo {
     icon_url: Option<String>,
     favicon_url: Option<String>,
     theme_color: Option<String>,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
 }
 
-#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub enum OnlineStatus {
-    Active,
-    Offline,
-    Online,
-    Unknown,
-}
-
-impl Display for OnlineStatus {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", match self {
-            Self::Active => "active",
-            Self::Offline => "offline",
-            Self::Online => "online",
-            Self::Unknown => "unknown",
-        })
-    }
-}
-
-impl FromStr for OnlineStatus {
-    type Err = InvalidEnumString;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(match s {
-            "active" => Self::Active,
-            "offline" => Self::Offline,
-            "online" => Self::Online,
-            "unknown" => Self::Unknown,
-            _ => return Err(InvalidEnumString),
-        })
+string_enum! {
+    pub enum OnlineStatus {
+        Active = "active",
+        Offline = "offline",
+        Online = "online",
+        Unknown = "unknown",
     }
 }
 
-#[derive(Debug, Deserialize, Getters)]
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AvatarDecorationInfo {
     id: String,
@@ -81,21 +75,25 @@ pub struct AvatarDecorationInfo {
     url: String,
     #[serde(default)] offset_x: f64,
     #[serde(default)] offset_y: f64,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
 }
 
-#[derive(Debug, Deserialize, Getters)]
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BadgeRoleInfo {
     name: String,
     icon_url: Option<String>,
     display_order: i32,
     behavior: Option<String>,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
 }
 
-#[derive(Debug, Deserialize, Getters)]
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RelationInfo {
-    id: String,
+    id: UserId,
     is_following: bool,
     has_pending_follow_request_from_you: bool,
     has_pending_follow_request_to_you: bool,
@@ -104,88 +102,196 @@ pub struct RelationInfo {
     is_blocked: bool,
     is_muted: bool,
     is_renote_muted: bool,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
 }
 
-#[derive(Debug, Deserialize, Getters)]
+/// 省略されうるフィールド (ログイン中のユーザー自身を取得した場合のみ含まれるものなど) は `None` の場合にシリアライズしない。
+#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DetailedUserInfo {
-    #[serde(default)] achievements: Vec<AchievementInfo>,
-    also_known_as: UnknownValue,
-    always_mark_nsfw: Option<bool>,
-    auto_accept_followed: Option<bool>,
-    auto_sensitive: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")] achievements: Option<Vec<AchievementInfo>>,
+    #[serde(default)] also_known_as: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")] always_mark_nsfw: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")] auto_accept_followed: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")] auto_sensitive: Option<bool>,
     avatar_blurhash: Option<String>,
     #[serde(default)] avatar_decorations: Vec<AvatarDecorationInfo>,
-    avatar_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")] avatar_id: Option<DriveFileId>,
     avatar_url: Option<String>, //  TODO Uri に変更を検討
     #[serde(default)] badge_roles: Vec<BadgeRoleInfo>,
     banner_blurhash: Option<String>,
-    banner_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")] banner_id: Option<DriveFileId>,
     banner_url: Option<String>,
-    birthday: Option<String>, // TODO 専用の構造体の作成を検討
-    #[serde(default)] careful_bot: bool,
-    created_at: Option<DateTime<Utc>>,
+    birthday: Option<Birthday>,
+    #[serde(skip_serializing_if = "Option::is_none")] careful_bot: Option<bool>,
+    #[serde(serialize_with = "crate::schema::date_time")] created_at: Option<DateTime<Utc>>,
     description: Option<String>, // TODO null が入ることがあるかのチェック
-    #[serde(default)] email_notification_types: Vec<UnknownValue>, // 中身は何か
-    emojis: UnknownValue,
+    #[serde(skip_serializing_if = "Option::is_none")] email_notification_types: Option<Vec<NotificationType>>,
+    #[serde(default)] emojis: BTreeMap<String, String>,
     #[serde(default)] fields: Vec<FieldInfo>,
     followers_count: usize,
     followers_visibility: StatusVisibility,
     following_count: usize,
     following_visibility: StatusVisibility,
-    has_pending_received_follow_request: Option<bool>,
-    has_unread_announcement: Option<bool>,
-    has_unread_antenna: Option<bool>,
-    has_unread_channel: Option<bool>,
-    has_unread_mentions: Option<bool>,
-    has_unread_notifications: Option<bool>,
-    has_unread_specified_notes: Option<bool>,
-    #[serde(default)] hide_online_status: bool,
+    #[serde(skip_serializing_if = "Option::is_none")] has_pending_received_follow_request: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")] has_unread_announcement: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")] has_unread_antenna: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")] has_unread_channel: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")] has_unread_mentions: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")] has_unread_notifications: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")] has_unread_specified_notes: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")] hide_online_status: Option<bool>,
     host: Option<String>,
-    id: String,
-    #[serde(default)] inject_featured_note: bool,
-    #[serde(default)] is_admin: bool,
+    id: UserId,
+    #[serde(skip_serializing_if = "Option::is_none")] inject_featured_note: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")] is_admin: Option<bool>,
     is_bot: bool,
     is_cat: bool,
-    #[serde(default)] is_deleted: bool,
-    #[serde(default)] is_explorable: bool,
-    #[serde(default)] is_limited: bool,
+    #[serde(skip_serializing_if = "Option::is_none")] is_deleted: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")] is_explorable: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")] is_limited: Option<bool>,
     is_locked: bool,
-    #[serde(default)] is_moderator: bool,
+    #[serde(skip_serializing_if = "Option::is_none")] is_moderator: Option<bool>,
     is_silenced: bool,
     is_suspended: bool,
     lang: Option<String>,
-    last_fetched_at: Option<UnknownValue>, // TODO DateTime に変更
+    #[serde(serialize_with = "crate::schema::date_time")] last_fetched_at: Option<DateTime<Utc>>,
     location: Option<String>,
-    #[serde(default)] logged_in_days: usize,
+    #[serde(skip_serializing_if = "Option::is_none")] logged_in_days: Option<usize>,
     memo: Option<String>,
-    moved_to: Option<UnknownValue>,
-    #[serde(default)] muted_instances: Vec<String>,
-    #[serde(default)] muted_words: Vec<Vec<String>>,
-    #[serde(default)] muting_notification_types: Vec<String>, // TODO 列挙型を作る
-    #[serde(default)] mutual_link_sections: Vec<String>,
+    moved_to: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")] muted_instances: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")] muted_words: Option<Vec<Vec<String>>>,
+    #[serde(skip_serializing_if = "Option::is_none")] muting_notification_types: Option<Vec<NotificationType>>,
+    #[serde(skip_serializing_if = "Option::is_none")] mutual_link_sections: Option<Vec<String>>,
     #[serde(default)] name: Option<String>,
-    #[serde(default)] no_crawle: bool,
+    #[serde(skip_serializing_if = "Option::is_none")] no_crawle: Option<bool>,
     notes_count: usize,
-    notification_receive_config: Option<UnknownValue>, // TODO 構造体を作る
+    #[serde(skip_serializing_if = "Option::is_none")] notification_receive_config: Option<HashMap<NotificationType, NotificationReceiveConfig>>,
     online_status: OnlineStatus,
-    #[serde(default)] pinned_note_ids: Vec<String>,
+    #[serde(default)] pinned_note_ids: Vec<NoteId>,
     #[serde(default)] pinned_notes: Vec<NoteInfo>,
-    pinned_page: Option<UnknownValue>, // TODO 構造体を作る
-    pinned_page_id: Option<String>,
-    policies: Option<UserPolicies>,
-    #[serde(default)] prevent_ai_learning: bool,
+    pinned_page: Option<PageInfo>,
+    pinned_page_id: Option<PageId>,
+    #[serde(skip_serializing_if = "Option::is_none")] policies: Option<UserPolicies>,
+    #[serde(skip_serializing_if = "Option::is_none")] prevent_ai_learning: Option<bool>,
     public_reactions: bool,
-    receive_announcement_email: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")] receive_announcement_email: Option<bool>,
     #[serde(default)] roles: Vec<RoleInfo>,
     #[serde(default)] security_keys: bool,
     #[serde(default)] two_factor_enabled: bool,
-    #[serde(default)] unread_announcements: Vec<UnknownValue>,
-    #[serde(default)] unread_notifications_count: usize,
-    #[serde(default)] updated_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")] unread_announcements: Option<Vec<AnnouncementInfo>>,
+    #[serde(skip_serializing_if = "Option::is_none")] unread_notifications_count: Option<usize>,
+    #[serde(default, serialize_with = "crate::schema::date_time")] updated_at: Option<DateTime<Utc>>,
     uri: Option<String>,
     url: Option<String>,
-    #[serde(default)] user_password_less_login: bool,
+    #[serde(default)] use_password_less_login: bool,
     username: String,
-    #[serde(default)] verified_links: Vec<UnknownValue>,
+    #[serde(default)] verified_links: Vec<String>,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten, skip_serializing_if = "crate::schema::skip_extra")] extra: crate::schema::ExtraFields,
+}
+
+impl From<&DetailedUserInfo> for UserId {
+    fn from(value: &DetailedUserInfo) -> Self {
+        value.id.clone()
+    }
+}
+
+/// `YYYY-MM-DD` 形式の誕生日。<br />
+/// 年を公開しない場合などに実在しない日付が設定されていることがあるため、`NaiveDate` ではなく各要素をそのまま保持する。
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Birthday {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl Birthday {
+    pub fn to_naive_date(self) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(self.year as i32, self.month as u32, self.day as u32)
+    }
+
+    /// 指定した日付が誕生日 (月と日が一致する日) か否か
+    pub fn is_birthday(self, date: impl Datelike) -> bool {
+        date.month() == self.month as u32 && date.day() == self.day as u32
+    }
+}
+
+impl Display for Birthday {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl FromStr for Birthday {
+    type Err = InvalidDateString;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(3, '-');
+        let mut next = |len: usize| parts.next().filter(|a| a.len() == len && a.bytes().all(|a| a.is_ascii_digit())).ok_or(InvalidDateString);
+        let (year, month, day) = (next(4)?, next(2)?, next(2)?);
+        Ok(Self {
+            year: year.parse().map_err(|_| InvalidDateString)?,
+            month: month.parse().map_err(|_| InvalidDateString)?,
+            day: day.parse().map_err(|_| InvalidDateString)?,
+        })
+    }
+}
+
+impl Serialize for Birthday {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        serializer.collect_str(self)
+    }
+}
+
+impl schema::Schema for Birthday {}
+
+impl<'de> Deserialize<'de> for Birthday {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        let value = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        value.parse().map_err(|_| serde::de::Error::invalid_value(serde::de::Unexpected::Str(&value), &"a date in YYYY-MM-DD format"))
+    }
+}
+
+/// 通知の種類ごとの受信設定
+#[derive(Clone, Debug, Deserialize, Schema, Serialize)]
+#[serde(rename_all = "camelCase", rename_all_fields = "camelCase", tag = "type")]
+pub enum NotificationReceiveConfig {
+    All,
+    Following,
+    Follower,
+    MutualFollow,
+    FollowingOrFollower,
+    /// 指定したリストのユーザーからのみ受信する。
+    List {
+        user_list_id: String,
+    },
+    Never,
+    /// このクレートが対応していない設定
+    #[serde(untagged, deserialize_with = "schema::unknown_variant::<NotificationReceiveConfig, _>")]
+    Other(Map<String, Value>),
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use serde_json::json;
+
+    use super::Birthday;
+
+    #[test]
+    fn birthday() {
+        let birthday = "2000-02-29".parse::<Birthday>().unwrap();
+        assert_eq!(birthday.to_string(), "2000-02-29");
+        assert_eq!(birthday.to_naive_date(), NaiveDate::from_ymd_opt(2000, 2, 29));
+        assert!(birthday.is_birthday(NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()));
+        assert!(!birthday.is_birthday(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()));
+        assert_eq!("0000-12-31".parse::<Birthday>().unwrap().to_naive_date(), NaiveDate::from_ymd_opt(0, 12, 31));
+        assert_eq!("2000-02-30".parse::<Birthday>().unwrap().to_naive_date(), None);
+        assert!("2000-2-29".parse::<Birthday>().is_err());
+        assert!(serde_json::from_value::<Birthday>(json!("not a date")).is_err());
+        assert_eq!(serde_json::to_value(birthday).unwrap(), json!("2000-02-29"));
+    }
 }
@@ -0,0 +1,244 @@
+//! レスポンスの型と実際の JSON との差異の検出
+//!
+//! `extra-fields` フィーチャーを有効にすると、レスポンスの構造体は対応していないフィールドを `extra` に保持する。<br />
+//! `from_str_strict` はテストでの使用を想定したもので、保存しておいたレスポンスに型が追従できているかを確認できる。<br />
+//! 比較に使う型の定義は `Schema` で表し、レスポンスの構造体と内部タグ付きの列挙型には `#[derive(Schema)]` で実装する。
+
+use std::{cell::RefCell, collections::{BTreeMap, HashMap}, error::Error, fmt::Display};
+
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+pub use serde_json::{Map, Value};
+
+/// 構造体が対応していないフィールド
+pub type ExtraFields = BTreeMap<String, Value>;
+
+thread_local! {
+    /// `from_str_strict` の実行中に検出した差異
+    static STRICT: RefCell<Option<Vec<SchemaDrift>>> = const { RefCell::new(None) };
+}
+
+/// 型と JSON との差異
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SchemaDrift {
+    /// 型に存在しないフィールド
+    UnknownField { path: String },
+    /// 型に存在するが JSON に存在しないフィールド。省略されうるフィールドは含めない。<br />
+    /// `Option` でも既定値もないフィールドが存在しない場合はデシリアライズ自体が失敗する。
+    MissingField { path: String },
+    /// 列挙型が対応していない値
+    UnknownValue { type_name: &'static str, value: String },
+}
+
+impl Display for SchemaDrift {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemaDrift::UnknownField { path } => write!(f, "unknown field `{}`", path),
+            SchemaDrift::MissingField { path } => write!(f, "missing field `{}`", path),
+            SchemaDrift::UnknownValue { type_name, value } => write!(f, "unknown value `{}` for {}", value, type_name),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum StrictError {
+    SerdeError(serde_json::Error),
+    Drift(Vec<SchemaDrift>),
+}
+
+impl Display for StrictError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StrictError::SerdeError(e) => write!(f, "failed to deserialize: {}", e),
+            StrictError::Drift(drifts) => {
+                f.write_str("schema drift detected:")?;
+                for drift in drifts {
+                    write!(f, "\n  {}", drift)?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+impl Error for StrictError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            StrictError::SerdeError(e) => Some(e),
+            StrictError::Drift(_) => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for StrictError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::SerdeError(value)
+    }
+}
+
+/// JSON をデシリアライズし、型との差異があればエラーを返す。<br />
+/// 対応していないフィールド、存在しないフィールド、列挙型の `Other` に分類された値を差異として扱う。
+pub fn from_str_strict<T>(json: &str) -> Result<T, StrictError> where T: DeserializeOwned + Schema {
+    let original = serde_json::from_str::<Value>(json)?;
+    STRICT.with(|a| *a.borrow_mut() = Some(Vec::new()));
+    let result = T::deserialize(&original);
+    let mut drifts = STRICT.with(|a| a.borrow_mut().take()).unwrap_or_default();
+    let parsed = result?;
+    T::check(&original, "", &mut drifts);
+    match drifts.is_empty() {
+        true => Ok(parsed),
+        false => Err(StrictError::Drift(drifts)),
+    }
+}
+
+/// `from_str_strict` の実行中であれば、列挙型が対応していない値を記録する。
+pub(crate) fn record_unknown_value(type_name: &'static str, value: &str) {
+    STRICT.with(|a| {
+        if let Some(drifts) = a.borrow_mut().as_mut() {
+            drifts.push(SchemaDrift::UnknownValue { type_name, value: value.to_string() });
+        }
+    });
+}
+
+/// 列挙型の対応していないバリアントをそのまま保持する。空のオブジェクトは差異として扱わない。
+pub(crate) fn unknown_variant<'de, T, D>(deserializer: D) -> Result<Map<String, Value>, D::Error> where D: Deserializer<'de> {
+    let value = Map::deserialize(deserializer)?;
+    if !value.is_empty() {
+        let name = std::any::type_name::<T>();
+        let tag = value.get("type").map_or_else(|| Value::Object(value.clone()).to_string(), |a| a.as_str().map_or_else(|| a.to_string(), str::to_string));
+        record_unknown_value(name.rsplit("::").next().unwrap_or(name), &tag);
+    }
+    Ok(value)
+}
+
+/// `extra` が空の場合はシリアライズしない。
+#[cfg(feature = "extra-fields")]
+pub(crate) fn skip_extra(extra: &ExtraFields) -> bool {
+    extra.is_empty()
+}
+
+/// 日時を Misskey と同じくミリ秒までの形式 (`2024-01-01T00:00:00.000Z`) でシリアライズする。<br />
+/// `chrono` の既定の形式はミリ秒が 0 の場合に省略するため、レスポンスの日時のフィールドにはこれを指定する。
+pub(crate) fn date_time<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error> where T: MillisDateTime, S: Serializer {
+    value.serialize_millis(serializer)
+}
+
+/// `date_time` でシリアライズできる型
+pub(crate) trait MillisDateTime {
+    fn serialize_millis<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer;
+}
+
+impl MillisDateTime for DateTime<Utc> {
+    fn serialize_millis<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        serializer.serialize_str(&self.to_rfc3339_opts(SecondsFormat::Millis, true))
+    }
+}
+
+impl<T> MillisDateTime for Option<T> where T: MillisDateTime {
+    fn serialize_millis<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        match self {
+            Some(a) => serializer.serialize_some(&Millis(a)),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+impl<T> MillisDateTime for Vec<T> where T: MillisDateTime {
+    fn serialize_millis<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        serializer.collect_seq(self.iter().map(Millis))
+    }
+}
+
+struct Millis<'a, T>(&'a T);
+
+impl<T> Serialize for Millis<'_, T> where T: MillisDateTime {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        self.0.serialize_millis(serializer)
+    }
+}
+
+/// `from_str_strict` が JSON と比較する、レスポンスの型の定義。<br />
+/// `#[derive(Schema)]` は `#[serde(skip_serializing_if = "...")]` を指定したフィールドを省略されうるものとして扱い、
+/// それ以外のフィールドが JSON に存在しない場合は差異とする。
+pub trait Schema {
+    /// `value` を型の定義と比較し、差異を `drifts` に追加する。
+    fn check(value: &Value, path: &str, drifts: &mut Vec<SchemaDrift>) {
+        if let Value::Object(object) = value {
+            let mut known = Vec::new();
+            if Self::check_fields(object, path, &mut known, drifts) {
+                for key in object.keys().filter(|a| !known.contains(&a.as_str())) {
+                    drifts.push(SchemaDrift::UnknownField { path: format!("{}/{}", path, key) });
+                }
+            }
+        }
+    }
+
+    /// オブジェクトとして表される型のフィールドを確認し、型が対応しているキーを `known` に追加する。<br />
+    /// 型が任意のキーを受け入れる場合は `false` を返す。`#[serde(flatten)]` したフィールドの確認にも使う。
+    fn check_fields(object: &Map<String, Value>, path: &str, known: &mut Vec<&'static str>, drifts: &mut Vec<SchemaDrift>) -> bool {
+        let _ = (object, path, known, drifts);
+        false
+    }
+}
+
+/// `#[derive(Schema)]` が生成する、構造体の 1 つのフィールドの確認。<br />
+/// `names` は JSON 上の名前と別名で、最初の要素を差異の位置に使う。
+pub fn check_field<T>(object: &Map<String, Value>, path: &str, names: &[&'static str], optional: bool, known: &mut Vec<&'static str>, drifts: &mut Vec<SchemaDrift>) where T: Schema + ?Sized {
+    known.extend_from_slice(names);
+    match names.iter().find_map(|a| object.get(*a).map(|b| (a, b))) {
+        Some((name, value)) => T::check(value, &format!("{}/{}", path, name), drifts),
+        None if !optional => drifts.push(SchemaDrift::MissingField { path: format!("{}/{}", path, names[0]) }),
+        None => {},
+    }
+}
+
+/// フィールドを持たない型に `Schema` を実装する。
+macro_rules! leaf_schema {
+    ($($ty:ty),* $(,)?) => {
+        $(impl Schema for $ty {})*
+    };
+}
+
+leaf_schema!(bool, i8, i16, i32, i64, u8, u16, u32, u64, usize, f32, f64, String, Value, Map<String, Value>, DateTime<Utc>, NaiveDate);
+
+impl<T> Schema for Option<T> where T: Schema {
+    fn check(value: &Value, path: &str, drifts: &mut Vec<SchemaDrift>) {
+        if !value.is_null() {
+            T::check(value, path, drifts);
+        }
+    }
+}
+
+impl<T> Schema for Box<T> where T: Schema {
+    fn check(value: &Value, path: &str, drifts: &mut Vec<SchemaDrift>) {
+        T::check(value, path, drifts);
+    }
+
+    fn check_fields(object: &Map<String, Value>, path: &str, known: &mut Vec<&'static str>, drifts: &mut Vec<SchemaDrift>) -> bool {
+        T::check_fields(object, path, known, drifts)
+    }
+}
+
+impl<T> Schema for Vec<T> where T: Schema {
+    fn check(value: &Value, path: &str, drifts: &mut Vec<SchemaDrift>) {
+        for (i, value) in value.as_array().into_iter().flatten().enumerate() {
+            T::check(value, &format!("{}/{}", path, i), drifts);
+        }
+    }
+}
+
+impl<K, V> Schema for BTreeMap<K, V> where V: Schema {
+    fn check(value: &Value, path: &str, drifts: &mut Vec<SchemaDrift>) {
+        for (key, value) in value.as_object().into_iter().flatten() {
+            V::check(value, &format!("{}/{}", path, key), drifts);
+        }
+    }
+}
+
+impl<K, V, S> Schema for HashMap<K, V, S> where V: Schema {
+    fn check(value: &Value, path: &str, drifts: &mut Vec<SchemaDrift>) {
+        for (key, value) in value.as_object().into_iter().flatten() {
+            V::check(value, &format!("{}/{}", path, key), drifts);
+        }
+    }
+}
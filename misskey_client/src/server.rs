@@ -0,0 +1,113 @@
+//! `/api/meta`、`/api/endpoints` と NodeInfo によるサーバーの判別
+
+use std::collections::BTreeSet;
+
+use derive_getters::Getters;
+use http::Response;
+
+use crate::{common::{string_enum, ServerVersion}, errors::{MisskeyConnectionError, MisskeyConnectionResult, UnsupportedReason}, responses::{meta::{MetaFeatures, MetaInfo}, nodeinfo::NodeInfo}, MisskeyClientRequest, MisskeyHttpClient};
+
+string_enum! {
+    /// サーバーのソフトウェア
+    pub enum Software {
+        Misskey = "misskey",
+        Sharkey = "sharkey",
+        CherryPick = "cherrypick",
+    }
+}
+
+/// サーバーのソフトウェア、バージョンと対応している機能
+#[derive(Clone, Debug, Getters)]
+pub struct ServerInfo {
+    /// NodeInfo を取得できなかった場合は `Misskey` とみなす。
+    software: Software,
+    /// ソフトウェア自身のバージョン。解釈できない場合は `None`
+    version: Option<ServerVersion>,
+    /// 互換性のある Misskey のバージョン。リクエストの `min_version` と比較する。<br />
+    /// フォークが `basedMisskeyVersion` を報告しない場合は、Sharkey のように Misskey と同じ体系のものを除いて `None`
+    misskey_version: Option<ServerVersion>,
+    meta: MetaInfo,
+    /// `/api/endpoints` で公開されているエンドポイント。`/notes/create` のように先頭のスラッシュを含む。<br />
+    /// 取得できなかった場合は `None`
+    endpoints: Option<BTreeSet<String>>,
+    nodeinfo: Option<NodeInfo>,
+}
+
+impl ServerInfo {
+    pub fn new(meta: MetaInfo, endpoints: Option<Vec<String>>, nodeinfo: Option<NodeInfo>) -> Self {
+        let software = nodeinfo.as_ref().map_or(Software::Misskey, |a| a.software().name().to_ascii_lowercase().parse().unwrap_or_else(|e| match e {}));
+        let version = meta.version().parse::<ServerVersion>().ok().or_else(|| nodeinfo.as_ref().and_then(|a| a.software().version().parse().ok()));
+        let misskey_version = match meta.based_misskey_version() {
+            Some(a) => a.parse().ok(),
+            None if matches!(software, Software::Misskey | Software::Sharkey) => version.clone(),
+            None => None,
+        };
+        let endpoints = endpoints.map(|a| a.iter().map(|a| format!("/{}", a.trim_start_matches('/'))).collect());
+        Self { software, version, misskey_version, meta, endpoints, nodeinfo }
+    }
+
+    /// `/meta` に `detail` を指定した場合に含まれる機能の一覧
+    pub fn features(&self) -> Option<&MetaFeatures> {
+        self.meta.features().as_ref()
+    }
+
+    /// エンドポイントが公開されているか否か。一覧を取得できなかった場合は `None`
+    pub fn has_endpoint(&self, endpoint: &str) -> Option<bool> {
+        let endpoint = format!("/{}", endpoint.trim_start_matches('/'));
+        self.endpoints.as_ref().map(|a| a.contains(&endpoint))
+    }
+
+    /// 互換性のある Misskey のバージョンが `version` 以上か否か。不明な場合は `None`
+    pub fn at_least(&self, version: &ServerVersion) -> Option<bool> {
+        self.misskey_version.as_ref().map(|a| a >= version)
+    }
+
+    /// リクエストがサーバーで使用できるかを確認する。判断できない場合は使用できるものとみなす。
+    pub fn check<R>(&self, request: &R) -> Result<(), UnsupportedReason> where R: MisskeyClientRequest {
+        if let (Some(required), Some(actual)) = (request.min_version(), &self.misskey_version) {
+            if actual < &required {
+                return Err(UnsupportedReason::Version { required, actual: actual.clone() });
+            }
+        }
+        let endpoint = request.endpoint().to_string();
+        // `/api` の外にあるリクエストと、一覧に含まれない MiAuth は確認しない。
+        if request.path().starts_with("/api/") && !endpoint.starts_with("/miauth/") && self.has_endpoint(&endpoint) == Some(false) {
+            return Err(UnsupportedReason::Endpoint);
+        }
+        Ok(())
+    }
+}
+
+impl<T> MisskeyHttpClient<T> {
+    /// 取得済みのサーバーの情報
+    #[inline]
+    pub fn server_info(&self) -> Option<&ServerInfo> {
+        self.server_info.as_ref()
+    }
+
+    /// 以前に取得したサーバーの情報を設定する。以降のリクエストは送信前に対応しているかが確認される。
+    #[inline]
+    pub fn with_server_info(self, server_info: ServerInfo) -> Self {
+        Self {
+            server_info: Some(server_info),
+            .. self
+        }
+    }
+
+    /// 取得済みのサーバーの情報をもとに、リクエストが使用できるかを送信前に確認する。
+    pub(crate) fn check_support<R>(&self, request: &R) -> MisskeyConnectionResult<()> where R: MisskeyClientRequest {
+        match self.server_info.as_ref().map(|a| a.check(request)) {
+            Some(Err(reason)) => Err(MisskeyConnectionError::Unsupported { endpoint: request.endpoint().to_string(), reason }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// 判別に必須でないリクエストの結果を、通信自体の失敗を除いて `None` に変換する。
+pub(crate) fn optional<T>(result: MisskeyConnectionResult<Response<Option<T>>>) -> MisskeyConnectionResult<Option<T>> {
+    match result {
+        Ok(a) => Ok(a.into_body()),
+        Err(MisskeyConnectionError::IoError(e)) => Err(e.into()),
+        Err(_) => Ok(None),
+    }
+}
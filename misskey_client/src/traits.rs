@@ -1,85 +1,29 @@
 pub mod json;
 
+use http::Method;
 use serde::Deserialize;
 
-use crate::responses::users::LiteUserInfo;
+use crate::{common::ServerVersion, errors::{EndpointError, InvalidRequestReason, MisskeyConnectionResult}, miauth::Permission};
 
 pub trait MisskeyClientRequest where for<'de> Self::Response: Deserialize<'de> {
     type Response;
+    /// エンドポイント固有のエラーの型
+    type Error: EndpointError;
     fn endpoint(&self) -> impl ToString;
     fn content_type(&self) -> Option<impl ToString>;
     fn can_be_empty(&self) -> bool { false }
-    fn body(&self, token: Option<&str>) -> impl ToString;
-}
-
-pub trait NoteId {
-    fn to_note_id(self) -> String;
-}
-
-impl NoteId for String {
-    fn to_note_id(self) -> String {
-        self
-    }
-}
-
-impl NoteId for &str {
-    fn to_note_id(self) -> String {
-        self.to_string()
-    }
-}
-
-impl<T> NoteId for &T where T: NoteId + Clone {
-    fn to_note_id(self) -> String {
-        self.clone().to_note_id()
-    }
-}
-
-pub trait UserId {
-    fn to_user_id(self) -> String;
-}
-
-impl UserId for String {
-    fn to_user_id(self) -> String {
-        self
-    }
-}
-
-impl UserId for &str {
-    fn to_user_id(self) -> String {
-        self.to_string()
-    }
-}
-
-impl UserId for LiteUserInfo {
-    fn to_user_id(self) -> String {
-        self.id().to_string()
-    }
-}
-
-impl<T> UserId for &T where T: UserId + Clone {
-    fn to_user_id(self) -> String {
-        self.clone().to_user_id()
-    }
-}
-
-pub trait ChannelId {
-    fn to_channel_id(self) -> String;
-}
-
-impl ChannelId for String {
-    fn to_channel_id(self) -> String {
-        self
-    }
-}
-
-impl ChannelId for &str {
-    fn to_channel_id(self) -> String {
-        self.to_string()
-    }
-}
-
-impl<T> ChannelId for &T where T: ChannelId + Clone {
-    fn to_channel_id(self) -> String {
-        self.clone().to_channel_id()
-    }
+    /// 認証情報が必要か否か
+    fn requires_credential(&self) -> bool { false }
+    /// トークンに必要な権限
+    fn permissions(&self) -> &[Permission] { &[] }
+    /// リクエストに対応する最も古い Misskey のバージョン。<br />
+    /// サーバーの情報を取得済みの場合は送信前に確認される。
+    fn min_version(&self) -> Option<ServerVersion> { None }
+    /// サーバーが拒否するパラメーターの組み合わせを送信前に確認する。
+    fn validate(&self) -> Result<(), InvalidRequestReason> { Ok(()) }
+    fn method(&self) -> Method { Method::POST }
+    /// リクエスト先のパス。既定では `/api` とエンドポイントを連結したもの
+    fn path(&self) -> String { format!("/api{}", self.endpoint().to_string()) }
+    /// 送信する本文。シリアル化に失敗した場合はエラーになる。
+    fn body(&self, token: Option<&str>) -> MisskeyConnectionResult<impl ToString>;
 }
@@ -1,18 +1,31 @@
 use serde::{Deserialize, Serialize};
 
+use crate::{common::ServerVersion, errors::{EndpointError, InvalidRequestReason, MisskeyConnectionError, MisskeyConnectionResult}, miauth::Permission};
+
 use super::MisskeyClientRequest;
 
 pub trait ConstParamJsonRequest : Serialize where for<'de> Self::Response: Deserialize<'de> {
     /// レスポンスの型
     type Response;
+    /// エンドポイント固有のエラーの型
+    type Error: EndpointError;
     /// リクエスト先のエンドポイントのアドレス。<br />
     /// 先頭にスラッシュが必要。`/api` は不要。
     const ENDPOINT: &'static str;
     const CAN_BE_EMPTY: bool = false;
+    /// 認証情報が必要か否か
+    const REQUIRES_CREDENTIAL: bool = false;
+    /// トークンに必要な権限
+    const PERMISSIONS: &'static [Permission] = &[];
+    /// リクエストに対応する最も古い Misskey のバージョン
+    const MIN_VERSION: Option<ServerVersion> = None;
+    /// サーバーが拒否するパラメーターの組み合わせを送信前に確認する。
+    fn validate(&self) -> Result<(), InvalidRequestReason> { Ok(()) }
 }
 
 impl<T> JsonRequest for T where T: ConstParamJsonRequest {
     type Response = T::Response;
+    type Error = T::Error;
 
     fn endpoint(&self) -> String {
         Self::ENDPOINT.to_string()
@@ -21,20 +34,47 @@ impl<T> JsonRequest for T where T: ConstParamJsonRequest {
     fn can_be_empty(&self) -> bool {
         Self::CAN_BE_EMPTY
     }
+
+    fn requires_credential(&self) -> bool {
+        Self::REQUIRES_CREDENTIAL
+    }
+
+    fn permissions(&self) -> &[Permission] {
+        Self::PERMISSIONS
+    }
+
+    fn min_version(&self) -> Option<ServerVersion> {
+        Self::MIN_VERSION
+    }
+
+    fn validate(&self) -> Result<(), InvalidRequestReason> {
+        <Self as ConstParamJsonRequest>::validate(self)
+    }
 }
 
 /// Misskey サーバーへ送信可能な構造体であることを示すトレイト
 pub trait JsonRequest : Serialize where for<'de> Self::Response: Deserialize<'de> {
     /// レスポンスの型
     type Response;
+    /// エンドポイント固有のエラーの型
+    type Error: EndpointError;
     /// リクエスト先のエンドポイントのアドレス。<br />
     /// 先頭にスラッシュが必要。`/api` は不要。
     fn endpoint(&self) -> String;
     fn can_be_empty(&self) -> bool { false }
+    /// 認証情報が必要か否か
+    fn requires_credential(&self) -> bool { false }
+    /// トークンに必要な権限
+    fn permissions(&self) -> &[Permission] { &[] }
+    /// リクエストに対応する最も古い Misskey のバージョン
+    fn min_version(&self) -> Option<ServerVersion> { None }
+    /// サーバーが拒否するパラメーターの組み合わせを送信前に確認する。
+    fn validate(&self) -> Result<(), InvalidRequestReason> { Ok(()) }
 }
 
 impl<T> MisskeyClientRequest for T where T: JsonRequest {
     type Response = T::Response;
+    type Error = T::Error;
 
     fn endpoint(&self) -> impl ToString {
         JsonRequest::endpoint(self)
@@ -44,13 +84,29 @@ impl<T> MisskeyClientRequest for T where T: JsonRequest {
         Some("application/json")
     }
 
-    fn body(&self, token: Option<&str>) -> impl ToString {
-        serde_json::to_string(&RequestWithToken::new(token, self)).unwrap()
+    fn body(&self, token: Option<&str>) -> MisskeyConnectionResult<impl ToString> {
+        serde_json::to_string(&RequestWithToken::new(token, self)).map_err(MisskeyConnectionError::SerializeError)
     }
 
     fn can_be_empty(&self) -> bool {
         <Self as JsonRequest>::can_be_empty(self)
     }
+
+    fn requires_credential(&self) -> bool {
+        <Self as JsonRequest>::requires_credential(self)
+    }
+
+    fn permissions(&self) -> &[Permission] {
+        <Self as JsonRequest>::permissions(self)
+    }
+
+    fn min_version(&self) -> Option<ServerVersion> {
+        <Self as JsonRequest>::min_version(self)
+    }
+
+    fn validate(&self) -> Result<(), InvalidRequestReason> {
+        <Self as JsonRequest>::validate(self)
+    }
 }
 
 #[derive(Debug, serde_derive::Serialize)]
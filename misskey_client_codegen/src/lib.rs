@@ -0,0 +1,508 @@
+//! Misskey の OpenAPI 定義 (`api.json`) から misskey_client のリクエストとレスポンスの型を生成する。
+//!
+//! 生成したコードは misskey_client の `generated` フィーチャーで有効になる `generated` モジュールに出力する。<br />
+//! リクエストは `ConstParamJsonRequest` と `builder` を使用した構造体、レスポンスはゲッターを持つ構造体として出力し、
+//! エンドポイントごとの認証情報の要否と権限の一覧を `generated::permissions` に出力する。
+//!
+//! このクレートに同梱している `api.json` は、生成器のテストのために Misskey の `/api.json` から一部のエンドポイントとスキーマを抜き出したもので、
+//! サーバーのすべてのエンドポイントは含まない。<br />
+//! 生成するエンドポイントは `endpoints.txt` の一覧で絞り込む。サーバーのエンドポイントに合わせて生成する場合は、
+//! `--spec` でサーバーの `/api.json` を、`--all` で一覧によらずすべてのエンドポイントを指定する。
+
+mod naming;
+
+use std::{collections::{BTreeSet, HashSet}, error::Error, fmt::{Display, Write}, str::FromStr};
+
+use misskey_client::{errors::ServerErrorType, miauth::Permission};
+use serde_json::{Map, Value};
+
+use naming::{id_type, pascal_case, serde_camel_case, singular, snake_case};
+
+const HEADER: &str = "// このファイルは misskey_client_codegen によって生成されている。直接編集しないこと。\n";
+
+#[derive(Debug)]
+pub struct GenerateError(String);
+
+impl Display for GenerateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid OpenAPI document: {}", self.0)
+    }
+}
+
+impl Error for GenerateError {}
+
+/// 生成したファイルの内容
+#[derive(Debug)]
+pub struct Generated {
+    /// `info.version` に記載された Misskey のバージョン
+    pub version: Option<String>,
+    pub requests: String,
+    pub responses: String,
+    pub permissions: String,
+    /// 生成はできたが、一部を省略したエンドポイントなどに関する警告
+    pub warnings: Vec<String>,
+}
+
+impl Generated {
+    /// `generated` ディレクトリからの相対パスとファイルの内容の組
+    pub fn files(&self) -> [(&'static str, String); 4] {
+        [
+            ("mod.rs", format!("{}\n//! Misskey {}の OpenAPI 定義から生成した型\n\npub mod permissions;\npub mod requests;\npub mod responses;\n", HEADER, self.version.as_ref().map(|a| format!("{} ", a)).unwrap_or_default())),
+            ("requests.rs", self.requests.clone()),
+            ("responses.rs", self.responses.clone()),
+            ("permissions.rs", self.permissions.clone()),
+        ]
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Side {
+    Request,
+    Response,
+}
+
+/// 1つのファイルに出力する定義と、それに必要な `use`
+#[derive(Default)]
+struct Output {
+    items: Vec<String>,
+    names: HashSet<String>,
+    ids: BTreeSet<&'static str>,
+    date_time: bool,
+    map: bool,
+    value: bool,
+    string_enum: bool,
+    /// リクエストから参照しているレスポンスの型
+    responses: BTreeSet<String>,
+}
+
+struct Generator<'a> {
+    schemas: &'a Map<String, Value>,
+    requests: Output,
+    responses: Output,
+    /// 生成中のコンポーネント。再帰している参照は `Box` で包む。
+    stack: Vec<String>,
+    warnings: Vec<String>,
+}
+
+/// `null` を許容するかを取り除いたスキーマ
+fn split_null(schema: &Value) -> (Value, bool) {
+    let mut schema = schema.clone();
+    let mut nullable = schema.get("nullable").and_then(Value::as_bool).unwrap_or(false);
+    if let Some(Value::Array(types)) = schema.get("type") {
+        let rest = types.iter().filter(|a| a.as_str() != Some("null")).cloned().collect::<Vec<_>>();
+        nullable |= rest.len() < types.len();
+        schema["type"] = match <[Value; 1]>::try_from(rest) {
+            Ok([a]) => a,
+            Err(rest) => Value::Array(rest),
+        };
+    }
+    if let Some(Value::Array(values)) = schema.get("enum") {
+        let rest = values.iter().filter(|a| !a.is_null()).cloned().collect::<Vec<_>>();
+        nullable |= rest.len() < values.len();
+        schema["enum"] = Value::Array(rest);
+    }
+    for key in ["oneOf", "anyOf"] {
+        if let Some(Value::Array(variants)) = schema.get(key) {
+            let rest = variants.iter().filter(|a| a.get("type").and_then(Value::as_str) != Some("null")).collect::<Vec<_>>();
+            if let ([variant], true) = (rest.as_slice(), rest.len() < variants.len()) {
+                let (variant, _) = split_null(variant);
+                return (variant, true);
+            }
+        }
+    }
+    (schema, nullable)
+}
+
+fn doc_comment(description: Option<&str>, indent: &str) -> String {
+    let Some(description) = description.map(str::trim).filter(|a| !a.is_empty()) else { return String::new() };
+    let lines = description.lines().map(str::trim_end).collect::<Vec<_>>();
+    lines.iter().enumerate().map(|(i, a)| match (a.is_empty(), i + 1 == lines.len()) {
+        (true, _) => format!("{}///\n", indent),
+        (false, true) => format!("{}/// {}\n", indent, a),
+        (false, false) => format!("{}/// {}<br />\n", indent, a),
+    }).collect()
+}
+
+/// `#[serde(rename_all = "camelCase")]` で元の名前にならない場合の `rename`
+fn rename(field: &str, original: &str) -> Option<String> {
+    (serde_camel_case(field) != original).then(|| format!("rename = \"{}\"", original.escape_default()))
+}
+
+impl<'a> Generator<'a> {
+    fn output(&mut self, side: Side) -> &mut Output {
+        match side {
+            Side::Request => &mut self.requests,
+            Side::Response => &mut self.responses,
+        }
+    }
+
+    /// レスポンスの型を `requests.rs` で使えるようにする。
+    fn import_response(&mut self, ty: &str) {
+        for word in ty.split(|a: char| !a.is_ascii_alphanumeric()).filter(|a| !a.is_empty()) {
+            match word {
+                "DateTime" => self.requests.date_time = true,
+                "BTreeMap" => self.requests.map = true,
+                "Value" => self.requests.value = true,
+                word if self.responses.names.contains(word) => {
+                    self.requests.responses.insert(word.to_string());
+                },
+                word => if let Some(id) = self.responses.ids.iter().find(|a| **a == word) {
+                    self.requests.ids.insert(id);
+                },
+            }
+        }
+    }
+
+    /// スキーマに対応する Rust の型を返す。`name` はインラインの構造体や列挙型を定義する場合に使う名前。
+    fn rust_type(&mut self, schema: &Value, name: &str, owner: &str, property: &str, side: Side) -> Result<String, GenerateError> {
+        let (schema, nullable) = split_null(schema);
+        let ty = self.non_null_type(&schema, name, owner, property, side)?;
+        Ok(match nullable {
+            true => format!("Option<{}>", ty),
+            false => ty,
+        })
+    }
+
+    fn non_null_type(&mut self, schema: &Value, name: &str, owner: &str, property: &str, side: Side) -> Result<String, GenerateError> {
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let component = reference.strip_prefix("#/components/schemas/").ok_or_else(|| GenerateError(format!("unsupported reference: {}", reference)))?;
+            let ty = self.component(component)?;
+            if side == Side::Request {
+                self.requests.responses.insert(ty.clone());
+            }
+            return Ok(match self.stack.iter().any(|a| a == component) {
+                true => format!("Box<{}>", ty),
+                false => ty,
+            });
+        }
+        if let Some(Value::Array(all)) = schema.get("allOf") && let [single] = all.as_slice() {
+            return self.rust_type(single, name, owner, property, side);
+        }
+        let ty = match schema.get("type").and_then(Value::as_str) {
+            Some("string") => match (schema.get("enum"), schema.get("format").and_then(Value::as_str)) {
+                (Some(Value::Array(values)), _) if !values.is_empty() && values.iter().all(Value::is_string) => self.string_enum(name, schema, values, side),
+                (_, Some("date-time")) => {
+                    self.output(side).date_time = true;
+                    "DateTime<Utc>".to_string()
+                },
+                (_, Some("id" | "misskey:id")) => match id_type(owner, property) {
+                    Some(id) => {
+                        self.output(side).ids.insert(id);
+                        id.to_string()
+                    },
+                    None => "String".to_string(),
+                },
+                _ => "String".to_string(),
+            },
+            Some("integer") => "i64".to_string(),
+            Some("number") => "f64".to_string(),
+            Some("boolean") => "bool".to_string(),
+            Some("array") => {
+                let item = self.rust_type(schema.get("items").unwrap_or(&Value::Null), singular(name), owner, singular(property), side)?;
+                format!("Vec<{}>", item)
+            },
+            Some("object") => match (schema.get("properties").and_then(Value::as_object), schema.get("additionalProperties")) {
+                (Some(properties), _) if !properties.is_empty() => self.object(name, schema, side)?,
+                (_, Some(additional)) if additional.is_object() => {
+                    let value = self.rust_type(additional, name, owner, property, side)?;
+                    self.output(side).map = true;
+                    format!("BTreeMap<String, {}>", value)
+                },
+                _ => {
+                    self.output(side).value = true;
+                    "Value".to_string()
+                },
+            },
+            _ => {
+                self.output(side).value = true;
+                "Value".to_string()
+            },
+        };
+        Ok(ty)
+    }
+
+    /// コンポーネントをレスポンスの型として定義し、型名を返す。
+    fn component(&mut self, component: &str) -> Result<String, GenerateError> {
+        let name = pascal_case(component);
+        if !self.responses.names.contains(&name) {
+            let schema = self.schemas.get(component).ok_or_else(|| GenerateError(format!("no such component: {}", component)))?;
+            self.stack.push(component.to_string());
+            let ty = self.non_null_type(&split_null(schema).0, &name, &name, "", Side::Response);
+            self.stack.pop();
+            if ty? != name {
+                return Err(GenerateError(format!("component {} must be an object or a string enum", component)));
+            }
+        }
+        Ok(name)
+    }
+
+    fn string_enum(&mut self, name: &str, schema: &Value, values: &[Value], side: Side) -> String {
+        let output = self.output(side);
+        if output.names.insert(name.to_string()) {
+            let mut variants = Vec::<String>::new();
+            let mut body = String::new();
+            for value in values.iter().filter_map(Value::as_str) {
+                let mut variant = pascal_case(value);
+                if variant == "Other" || variants.contains(&variant) {
+                    variant = format!("{}{}", variant, variants.len());
+                }
+                writeln!(body, "        {} = \"{}\",", variant, value.escape_default()).unwrap();
+                variants.push(variant);
+            }
+            let doc = doc_comment(schema.get("description").and_then(Value::as_str), "    ");
+            output.string_enum = true;
+            output.items.push(format!("string_enum! {{\n{}    pub enum {} {{\n{}    }}\n}}\n", doc, name, body));
+        }
+        name.to_string()
+    }
+
+    /// `properties` を持つオブジェクトを構造体として定義する。
+    fn object(&mut self, name: &str, schema: &Value, side: Side) -> Result<String, GenerateError> {
+        if !self.output(side).names.insert(name.to_string()) {
+            return Ok(name.to_string());
+        }
+        let required = schema.get("required").and_then(Value::as_array).map(|a| a.iter().filter_map(Value::as_str).collect::<HashSet<_>>()).unwrap_or_default();
+        let mut body = String::new();
+        for (property, property_schema) in schema["properties"].as_object().into_iter().flatten() {
+            let field = snake_case(property);
+            let ty = self.rust_type(property_schema, &format!("{}{}", name, pascal_case(property)), name, property, side)?;
+            let optional = !required.contains(property.as_str()) && !ty.starts_with("Option<");
+            let ty = match optional {
+                true => format!("Option<{}>", ty),
+                false => ty,
+            };
+            body += &doc_comment(property_schema.get("description").and_then(Value::as_str), "    ");
+            let mut serde = rename(&field, property).into_iter().collect::<Vec<_>>();
+            if side == Side::Request && ty.starts_with("Option<") {
+                serde.push("skip_serializing_if = \"Option::is_none\"".to_string());
+            }
+            if side == Side::Response && !required.contains(property.as_str()) {
+                serde.push("skip_serializing_if = \"Option::is_none\"".to_string());
+            }
+            if side == Side::Response && ty.contains("DateTime<Utc>") {
+                serde.push("serialize_with = \"crate::schema::date_time\"".to_string());
+            }
+            let serde = match serde.is_empty() {
+                true => String::new(),
+                false => format!("#[serde({})] ", serde.join(", ")),
+            };
+            match side {
+                Side::Request => writeln!(body, "    {}pub {}: {},", serde, field, ty).unwrap(),
+                Side::Response => writeln!(body, "    {}{}: {},", serde, field, ty).unwrap(),
+            }
+        }
+        let doc = doc_comment(schema.get("description").and_then(Value::as_str), "");
+        let item = match side {
+            Side::Request => format!("{}#[derive(Clone, Debug, Serialize)]\n#[serde(rename_all = \"camelCase\")]\npub struct {} {{\n{}}}\n", doc, name, body),
+            Side::Response => format!(
+                "{}#[derive(Clone, Debug, Deserialize, Getters, Schema, Serialize)]\n#[serde(rename_all = \"camelCase\")]\npub struct {} {{\n{}    #[cfg(feature = \"extra-fields\")]\n    #[serde(flatten, skip_serializing_if = \"crate::schema::skip_extra\")] extra: crate::schema::ExtraFields,\n}}\n",
+                doc, name, body,
+            ),
+        };
+        self.output(side).items.push(item);
+        Ok(name.to_string())
+    }
+
+    fn endpoint(&mut self, path: &str, operation: &Value, permissions: &mut String) -> Result<(), GenerateError> {
+        let endpoint = path.to_string();
+        let name = pascal_case(path);
+        let description = operation.get("description").and_then(Value::as_str).unwrap_or_default();
+        let (summary, meta) = description.split_once("**Credential required**").unwrap_or((description, ""));
+        // Misskey は説明のないエンドポイントにこの文を出力する。
+        let summary = summary.replace("No description provided.", "");
+        let credential = meta.starts_with(": *Yes*") || operation.get("security").is_some();
+        let permission = meta.split_once("**Permission**: *").and_then(|(_, a)| a.split_once('*')).map(|(a, _)| a);
+        let permission = match permission.map(|a| (a, Permission::from_str(a))) {
+            Some((_, Ok(permission))) => Some(format!("{:?}", permission)),
+            Some((unknown, Err(_))) => {
+                self.warnings.push(format!("{}: unknown permission `{}` is omitted", endpoint, unknown));
+                None
+            },
+            None => None,
+        };
+        let permission_list = permission.iter().map(|a| format!("Permission::{}", a)).collect::<Vec<_>>().join(", ");
+        writeln!(permissions, "    EndpointInfo {{ endpoint: \"{}\", requires_credential: {}, permissions: &[{}] }},", endpoint, credential, permission_list).unwrap();
+
+        let body = &operation["requestBody"]["content"]["application/json"]["schema"];
+        if body.get("type").and_then(Value::as_str) != Some("object") {
+            self.warnings.push(format!("{}: request body is not an object and the request is skipped", endpoint));
+            return Ok(());
+        }
+        let responses = &operation["responses"];
+        let (response, can_be_empty) = match responses.get("200") {
+            Some(ok) => {
+                let response = self.rust_type(&ok["content"]["application/json"]["schema"], &format!("{}Response", name), "", "", Side::Response)?;
+                self.import_response(&response);
+                (response, false)
+            },
+            None => ("()".to_string(), true),
+        };
+
+        let errors = responses.as_object().into_iter().flatten().flat_map(|(_, a)| a["content"]["application/json"]["examples"].as_object().into_iter().flatten());
+        let mut error_variants = Vec::<String>::new();
+        let mut error_body = String::new();
+        for (key, example) in errors {
+            let error = &example["value"]["error"];
+            let (Some(code), Some(id)) = (error["code"].as_str(), error["id"].as_str()) else { continue };
+            if !matches!(ServerErrorType::from_str(code), Ok(ServerErrorType::Other(_))) || error_variants.contains(&pascal_case(key)) {
+                continue;
+            }
+            error_body += &doc_comment(error["message"].as_str(), "        ");
+            writeln!(error_body, "        {} = (\"{}\", \"{}\"),", pascal_case(key), code, id).unwrap();
+            error_variants.push(pascal_case(key));
+        }
+        if !error_variants.is_empty() {
+            self.requests.items.push(format!("endpoint_error! {{\n    /// `{}` 固有のエラー\n    pub enum {}Error {{\n{}    }}\n}}\n", name, name, error_body));
+        }
+
+        let mut attributes = vec![format!("endpoint = \"{}\"", endpoint), format!("response = {}", response)];
+        if !error_variants.is_empty() {
+            attributes.push(format!("error = {}Error", name));
+        }
+        if can_be_empty {
+            attributes.push("can_be_empty = true".to_string());
+        }
+        if credential {
+            attributes.push("credential = true".to_string());
+        }
+        if let Some(permission) = &permission {
+            attributes.push(format!("permissions({})", permission));
+        }
+        let required = body.get("required").and_then(Value::as_array).map(|a| a.iter().filter_map(Value::as_str).collect::<HashSet<_>>()).unwrap_or_default();
+        let mut fields = String::new();
+        for (property, property_schema) in body.get("properties").and_then(Value::as_object).into_iter().flatten() {
+            let field = snake_case(property);
+            let ty = self.rust_type(property_schema, &format!("{}{}", name, pascal_case(property)), &name, property, Side::Request)?;
+            let required = required.contains(property.as_str());
+            let ty = match required || ty.starts_with("Option<") {
+                true => ty,
+                false => format!("Option<{}>", ty),
+            };
+            fields += &doc_comment(property_schema.get("description").and_then(Value::as_str), "    ");
+            match required {
+                true => fields += "    #[misskey_client(required, into)]\n",
+                false => fields += "    #[misskey_client(into)]\n",
+            }
+            let mut serde = rename(&field, property).into_iter().collect::<Vec<_>>();
+            if ty.starts_with("Option<") {
+                serde.push("skip_serializing_if = \"Option::is_none\"".to_string());
+            }
+            match serde.is_empty() {
+                true => writeln!(fields, "    {}: {},", field, ty).unwrap(),
+                false => writeln!(fields, "    #[serde({})] {}: {},", serde.join(", "), field, ty).unwrap(),
+            }
+        }
+        let doc = doc_comment(Some(&summary), "");
+        let item = match fields.is_empty() {
+            true => format!("{}#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]\n#[misskey_client({})]\npub struct {};\n", doc, attributes.join(", "), name),
+            false => {
+                attributes.push("builder".to_string());
+                format!("{}#[derive(Clone, Debug, Serialize, ConstParamJsonRequest)]\n#[misskey_client({})]\n#[serde(rename_all = \"camelCase\")]\npub struct {} {{\n{}}}\n", doc, attributes.join(", "), name, fields)
+            },
+        };
+        self.requests.items.push(item);
+        Ok(())
+    }
+}
+
+/// 定義の前に置く `use` を組み立てる。
+fn imports(output: &Output, side: Side) -> String {
+    let mut std = Vec::new();
+    let mut krate = Vec::new();
+    let mut result = String::new();
+    if output.map {
+        std.push("collections::BTreeMap");
+    }
+    if output.date_time {
+        result += "use chrono::{DateTime, Utc};\n";
+    }
+    if side == Side::Response {
+        result += "use derive_getters::Getters;\n";
+    }
+    result += match side {
+        Side::Request => "use misskey_client_macroes::ConstParamJsonRequest;\n",
+        Side::Response => "use misskey_client_macroes::Schema;\n",
+    };
+    result += match side {
+        Side::Request => "use serde_derive::Serialize;\n",
+        Side::Response => "use serde_derive::{Deserialize, Serialize};\n",
+    };
+    if output.value {
+        result += "use serde_json::Value;\n";
+    }
+    let ids = output.ids.iter().copied().collect::<Vec<_>>();
+    match (ids.as_slice(), output.string_enum) {
+        ([], false) => {},
+        ([], true) => krate.push("common::string_enum".to_string()),
+        ([id], false) => krate.push(format!("common::{}", id)),
+        (ids, string_enum) => krate.push(format!("common::{{{}{}}}", if string_enum { "string_enum, " } else { "" }, ids.join(", "))),
+    }
+    if side == Side::Request && output.items.iter().any(|a| a.starts_with("endpoint_error!")) {
+        krate.push("errors::endpoint_error".to_string());
+    }
+    let mut imports = String::new();
+    if !std.is_empty() {
+        imports += &format!("use std::{};\n\n", std.join(", "));
+    }
+    imports += &result;
+    if !krate.is_empty() {
+        imports += &match krate.as_slice() {
+            [single] => format!("\nuse crate::{};\n", single),
+            krate => format!("\nuse crate::{{{}}};\n", krate.join(", ")),
+        };
+    }
+    if !output.responses.is_empty() {
+        let responses = output.responses.iter().cloned().collect::<Vec<_>>();
+        imports += &match responses.as_slice() {
+            [single] => format!("\nuse super::responses::{};\n", single),
+            responses => format!("\nuse super::responses::{{{}}};\n", responses.join(", ")),
+        };
+    }
+    imports
+}
+
+fn render(output: &Output, side: Side) -> String {
+    format!("{}\n{}\n{}", HEADER, imports(output, side), output.items.join("\n"))
+}
+
+/// `endpoints.txt` の形式の一覧を読み込む。空行と `#` 以降は無視する。
+pub fn parse_endpoints(text: &str) -> BTreeSet<String> {
+    text.lines().map(|a| a.split_once('#').map_or(a, |a| a.0).trim()).filter(|a| !a.is_empty()).map(str::to_string).collect()
+}
+
+/// OpenAPI 定義から型を生成する。<br />
+/// `endpoints` を指定した場合は、一覧に含まれるエンドポイントのみを生成する。
+pub fn generate(spec: &Value, endpoints: Option<&BTreeSet<String>>) -> Result<Generated, GenerateError> {
+    let empty = Map::new();
+    let schemas = spec["components"]["schemas"].as_object().unwrap_or(&empty);
+    let paths = spec["paths"].as_object().ok_or_else(|| GenerateError("missing paths".to_string()))?;
+    let mut generator = Generator { schemas, requests: Output::default(), responses: Output::default(), stack: Vec::new(), warnings: Vec::new() };
+    if let Some(endpoints) = endpoints {
+        generator.warnings.extend(endpoints.iter().filter(|a| !paths.contains_key(*a)).map(|a| format!("{}: not found in the spec", a)));
+    }
+    // `find` は二分探索するため、`Map` の実装 (`preserve_order` の有無) によらず昇順に並べる。
+    let mut paths = paths.iter().filter(|a| endpoints.is_none_or(|b| b.contains(a.0))).collect::<Vec<_>>();
+    paths.sort_by_key(|a| a.0);
+    let mut endpoints = String::new();
+    for (path, item) in paths {
+        let Some(operation) = item.get("post") else {
+            generator.warnings.push(format!("{}: only POST endpoints are supported", path));
+            continue;
+        };
+        generator.endpoint(path, operation, &mut endpoints)?;
+    }
+    let permissions = format!(
+        "{}\n//! エンドポイントごとの認証情報の要否と必要な権限\n\nuse crate::miauth::Permission;\n\n\
+        /// エンドポイントの認証に関する情報\n#[derive(Clone, Copy, Debug)]\npub struct EndpointInfo {{\n    /// 先頭にスラッシュが付き、`/api` を含まないエンドポイント\n    pub endpoint: &'static str,\n    pub requires_credential: bool,\n    pub permissions: &'static [Permission],\n}}\n\n\
+        /// エンドポイントの一覧。`endpoint` の昇順に並んでいる。\npub const ENDPOINTS: &[EndpointInfo] = &[\n{}];\n\n\
+        /// エンドポイントの情報を返す。\npub fn find(endpoint: &str) -> Option<&'static EndpointInfo> {{\n    ENDPOINTS.binary_search_by(|a| a.endpoint.cmp(endpoint)).ok().map(|i| &ENDPOINTS[i])\n}}\n",
+        HEADER, endpoints,
+    );
+    Ok(Generated {
+        version: spec["info"]["version"].as_str().map(str::to_string),
+        requests: render(&generator.requests, Side::Request),
+        responses: render(&generator.responses, Side::Response),
+        permissions,
+        warnings: generator.warnings,
+    })
+}
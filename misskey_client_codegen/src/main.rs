@@ -0,0 +1,65 @@
+//! `misskey_client_codegen [--spec <api.json>] [--endpoints <endpoints.txt> | --all] [--out <directory>] [--check]`
+//!
+//! 既定では同梱の `api.json` (一部のエンドポイントのみの抜粋) から `endpoints.txt` に記載したエンドポイントを生成し、misskey_client の `src/generated` に出力する。<br />
+//! `--check` を指定すると出力せず、生成結果が既存のファイルと一致しない場合に失敗する。
+
+use std::{fs, path::PathBuf, process::ExitCode};
+
+fn main() -> ExitCode {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let mut spec = root.join("api.json");
+    let mut endpoints = Some(root.join("endpoints.txt"));
+    let mut out = root.join("../misskey_client/src/generated");
+    let mut check = false;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--check" => check = true,
+            "--all" => endpoints = None,
+            "--spec" | "--endpoints" | "--out" if let Some(path) = args.next() => match arg.as_str() {
+                "--spec" => spec = path.into(),
+                "--endpoints" => endpoints = Some(path.into()),
+                _ => out = path.into(),
+            },
+            _ => {
+                eprintln!("usage: misskey_client_codegen [--spec <api.json>] [--endpoints <endpoints.txt> | --all] [--out <directory>] [--check]");
+                return ExitCode::FAILURE;
+            },
+        }
+    }
+
+    let endpoints = match endpoints.map(|a| fs::read_to_string(&a).map_err(|e| format!("{}: {}", a.display(), e))).transpose() {
+        Ok(endpoints) => endpoints.map(|a| misskey_client_codegen::parse_endpoints(&a)),
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        },
+    };
+    let generated = match fs::read_to_string(&spec).map_err(|e| e.to_string()).and_then(|a| serde_json::from_str(&a).map_err(|e| e.to_string())).and_then(|a| misskey_client_codegen::generate(&a, endpoints.as_ref()).map_err(|e| e.to_string())) {
+        Ok(generated) => generated,
+        Err(e) => {
+            eprintln!("{}: {}", spec.display(), e);
+            return ExitCode::FAILURE;
+        },
+    };
+    for warning in &generated.warnings {
+        eprintln!("warning: {}", warning);
+    }
+    let mut outdated = false;
+    for (name, content) in generated.files() {
+        let path = out.join(name);
+        if check {
+            if fs::read_to_string(&path).ok().as_deref() != Some(content.as_str()) {
+                eprintln!("{} is outdated", path.display());
+                outdated = true;
+            }
+        } else if let Err(e) = fs::create_dir_all(&out).and_then(|_| fs::write(&path, content)) {
+            eprintln!("{}: {}", path.display(), e);
+            return ExitCode::FAILURE;
+        }
+    }
+    match outdated {
+        true => ExitCode::FAILURE,
+        false => ExitCode::SUCCESS,
+    }
+}
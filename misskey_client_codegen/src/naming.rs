@@ -0,0 +1,104 @@
+//! OpenAPI 定義の名前から Rust の識別子への変換
+
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate", "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in",
+    "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof", "unsafe",
+    "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// 区切り文字と大文字の境界で単語に分割する。
+fn words(name: &str) -> Vec<String> {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_ascii_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        let prev = i.checked_sub(1).map(|i| chars[i]);
+        let next = chars.get(i + 1);
+        let boundary = c.is_ascii_uppercase() && match prev {
+            Some(prev) => prev.is_ascii_lowercase() || prev.is_ascii_digit() || (prev.is_ascii_uppercase() && next.is_some_and(char::is_ascii_lowercase)),
+            None => false,
+        };
+        if boundary && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// `notes/reactions/create` や `likeOnly` を `NotesReactionsCreate` や `LikeOnly` に変換する。<br />
+/// 数字から始まる場合や変換後が空になる場合は先頭に `V` を付ける。
+pub fn pascal_case(name: &str) -> String {
+    let result = words(name).iter().map(|a| {
+        let mut chars = a.chars();
+        chars.next().map(|b| b.to_ascii_uppercase().to_string() + &chars.as_str().to_ascii_lowercase()).unwrap_or_default()
+    }).collect::<String>();
+    match result.chars().next() {
+        Some(a) if a.is_ascii_alphabetic() => result,
+        _ => format!("V{}", result),
+    }
+}
+
+/// `avatarBlurhash` を `avatar_blurhash` に変換する。予約語には末尾に `_` を付ける。
+pub fn snake_case(name: &str) -> String {
+    let result = words(name).iter().map(|a| a.to_ascii_lowercase()).collect::<Vec<_>>().join("_");
+    match result.chars().next() {
+        None => "field_".to_string(),
+        Some(a) if a.is_ascii_digit() => format!("_{}", result),
+        _ if KEYWORDS.contains(&result.as_str()) => format!("{}_", result),
+        _ => result,
+    }
+}
+
+/// serde の `rename_all = "camelCase"` と同じ規則で、スネークケースのフィールド名を変換する。
+pub fn serde_camel_case(name: &str) -> String {
+    let mut result = String::new();
+    let mut capitalize = false;
+    for c in name.chars() {
+        if c == '_' {
+            capitalize = !result.is_empty();
+        } else if capitalize {
+            result.push(c.to_ascii_uppercase());
+            capitalize = false;
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// `fileIds` から `fileId` のように、配列の要素を表す名前を作る。
+pub fn singular(name: &str) -> &str {
+    match name.strip_suffix('s') {
+        Some(a) if !a.ends_with('s') && !a.is_empty() => a,
+        _ => name,
+    }
+}
+
+/// プロパティ名から対応する ID の型を推測する。
+pub fn id_type(owner: &str, property: &str) -> Option<&'static str> {
+    let target = match property {
+        "id" => owner,
+        _ => property.strip_suffix("Id")?,
+    };
+    Some(match words(target).last()?.to_ascii_lowercase().as_str() {
+        "note" | "reply" | "renote" => "NoteId",
+        "user" | "lite" | "detailed" | "owner" | "invitee" => "UserId",
+        "channel" => "ChannelId",
+        "file" => "DriveFileId",
+        "folder" => "DriveFolderId",
+        "notification" => "NotificationId",
+        "role" => "RoleId",
+        "page" => "PageId",
+        _ => return None,
+    })
+}
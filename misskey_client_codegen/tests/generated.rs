@@ -0,0 +1,82 @@
+use std::{fs, path::Path};
+
+use misskey_client::{generated::{permissions, requests::{NotesCreate, NotesCreateVisibility, NotesShow}, responses::{Note, NoteVisibility}}, miauth::Permission, schema::from_str_strict, ConstParamJsonRequest};
+use serde_json::json;
+
+#[test]
+fn generated_files_are_up_to_date() {
+    let spec = serde_json::from_str::<serde_json::Value>(include_str!("../api.json")).unwrap();
+    let endpoints = misskey_client_codegen::parse_endpoints(include_str!("../endpoints.txt"));
+    let generated = misskey_client_codegen::generate(&spec, Some(&endpoints)).unwrap();
+    assert!(generated.warnings.is_empty(), "{:?}", generated.warnings);
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../misskey_client/src/generated");
+    for (name, content) in generated.files() {
+        let current = fs::read_to_string(root.join(name)).unwrap();
+        assert!(current == content, "{} is outdated. Run `cargo run -p misskey_client_codegen` to regenerate it.", name);
+    }
+}
+
+#[test]
+fn unsupported_endpoints_are_reported() {
+    let spec = json!({
+        "paths": {
+            "/notes/show": { "get": {} },
+            "/notes/create": { "post": { "description": "**Credential required**: *Yes* / **Permission**: *write:something-new*", "requestBody": { "content": { "application/json": { "schema": { "anyOf": [] } } } } } },
+        },
+    });
+    let generated = misskey_client_codegen::generate(&spec, None).unwrap();
+    assert_eq!(generated.warnings, [
+        "/notes/create: unknown permission `write:something-new` is omitted",
+        "/notes/create: request body is not an object and the request is skipped",
+        "/notes/show: only POST endpoints are supported",
+    ]);
+    assert!(generated.permissions.contains("EndpointInfo { endpoint: \"/notes/create\", requires_credential: true, permissions: &[] }"));
+
+    // 一覧にないエンドポイントは生成せず、定義にないものは警告する
+    let endpoints = misskey_client_codegen::parse_endpoints("# comment\n/notes/create # inline\n\n/notes/unknown\n");
+    let generated = misskey_client_codegen::generate(&spec, Some(&endpoints)).unwrap();
+    assert_eq!(generated.warnings, [
+        "/notes/unknown: not found in the spec",
+        "/notes/create: unknown permission `write:something-new` is omitted",
+        "/notes/create: request body is not an object and the request is skipped",
+    ]);
+    assert!(!generated.permissions.contains("/notes/show"));
+}
+
+#[test]
+fn generated_types() {
+    assert_eq!(serde_json::to_value(NotesShow::new("9srd69w5a1")).unwrap(), json!({ "noteId": "9srd69w5a1" }));
+    let note = NotesCreate::new().text("hello").visibility(NotesCreateVisibility::Home).local_only(true);
+    assert_eq!(serde_json::to_value(note).unwrap(), json!({ "text": "hello", "visibility": "home", "localOnly": true }));
+    assert_eq!(NotesCreate::PERMISSIONS, [Permission::WriteNotes]);
+
+    let info = permissions::find("/notes/reactions/create").unwrap();
+    assert!(info.requires_credential);
+    assert_eq!(info.permissions, [Permission::WriteReactions]);
+    assert!(!permissions::find("/notes/show").unwrap().requires_credential);
+    assert!(permissions::find("/notes/unknown").is_none());
+    assert!(permissions::ENDPOINTS.is_sorted_by_key(|a| a.endpoint));
+
+    let value = json!({
+        "id": "9srd69w5a1",
+        "createdAt": "2024-05-01T00:00:00.000Z",
+        "text": "hello",
+        "userId": "9srd69w5a0",
+        "user": { "id": "9srd69w5a0", "name": null, "username": "alice", "host": null, "avatarUrl": null, "avatarBlurhash": null, "emojis": {}, "onlineStatus": "online" },
+        "renote": null,
+        "visibility": "public",
+        "reactionAcceptance": null,
+        "reactionEmojis": {},
+        "reactions": { "👍": 1 },
+        "reactionCount": 1,
+        "renoteCount": 0,
+        "repliesCount": 0,
+    });
+    // 必須でないプロパティは存在しなくても差異として扱わない
+    let note = from_str_strict::<Note>(&value.to_string()).unwrap();
+    assert_eq!(note.id(), "9srd69w5a1");
+    assert_eq!(note.visibility(), &NoteVisibility::Public);
+    assert_eq!(note.reactions()["👍"], 1.0);
+    assert_eq!(note.user().username(), "alice");
+    assert!(note.renote().is_none());
+}
@@ -0,0 +1,239 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{meta::ParseNestedMeta, parse_quote, spanned::Spanned, Attribute, Data, DeriveInput, Expr, Fields, GenericArgument, Ident, PathArguments, Token, Type, Visibility};
+
+const FIELD_ERR_MESSAGE: &str = "Field attributes must be following form: `misskey_client(required, into, skip, default = expr, setter = name, add = name, extend = name)`";
+
+/// `builder(new = name, vis = visibility)` の内容
+pub(crate) struct BuilderOptions {
+    new: Ident,
+    vis: Visibility,
+}
+
+impl BuilderOptions {
+    pub(crate) fn parse(meta: &ParseNestedMeta) -> syn::Result<Self> {
+        let mut new: Option<Ident> = None;
+        let mut vis: Option<Visibility> = None;
+        if meta.input.peek(syn::token::Paren) {
+            meta.parse_nested_meta(|inner| {
+                if inner.path.is_ident("new") {
+                    if new.is_some() {
+                        return Err(syn::Error::new(inner.path.span(), "Duplicated definition of new."));
+                    }
+                    new = Some(inner.value()?.parse()?);
+                } else if inner.path.is_ident("vis") {
+                    if vis.is_some() {
+                        return Err(syn::Error::new(inner.path.span(), "Duplicated definition of vis."));
+                    }
+                    vis = Some(inner.value()?.parse()?);
+                } else {
+                    return Err(syn::Error::new(inner.path.span(), "Builder options must be following form: `builder(new = name, vis = visibility)`"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(Self {
+            new: new.unwrap_or_else(|| format_ident!("new")),
+            vis: vis.unwrap_or_else(|| parse_quote! {pub}),
+        })
+    }
+}
+
+/// フィールドに付けられた `misskey_client(...)` の内容
+#[derive(Default)]
+struct FieldOptions {
+    required: bool,
+    into: bool,
+    skip: bool,
+    default: Option<Expr>,
+    setter: Option<Ident>,
+    add: Option<Ident>,
+    extend: Option<Ident>,
+}
+
+impl FieldOptions {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut options = Self::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("misskey_client")) {
+            attr.parse_nested_meta(|meta| {
+                let duplicated = || syn::Error::new(meta.path.span(), format!("Duplicated definition of {}.", meta.path.get_ident().map(Ident::to_string).unwrap_or_default()));
+                let flag = |flag: &mut bool| match std::mem::replace(flag, true) {
+                    true => Err(duplicated()),
+                    false => Ok(()),
+                };
+                if meta.path.is_ident("required") {
+                    flag(&mut options.required)?;
+                } else if meta.path.is_ident("into") {
+                    flag(&mut options.into)?;
+                } else if meta.path.is_ident("skip") {
+                    flag(&mut options.skip)?;
+                } else if meta.path.is_ident("default") && meta.input.peek(Token![=]) {
+                    if options.default.replace(meta.value()?.parse()?).is_some() {
+                        return Err(duplicated());
+                    }
+                } else if meta.path.is_ident("setter") && meta.input.peek(Token![=]) {
+                    if options.setter.replace(meta.value()?.parse()?).is_some() {
+                        return Err(duplicated());
+                    }
+                } else if meta.path.is_ident("add") && meta.input.peek(Token![=]) {
+                    if options.add.replace(meta.value()?.parse()?).is_some() {
+                        return Err(duplicated());
+                    }
+                } else if meta.path.is_ident("extend") && meta.input.peek(Token![=]) {
+                    if options.extend.replace(meta.value()?.parse()?).is_some() {
+                        return Err(duplicated());
+                    }
+                } else {
+                    return Err(syn::Error::new(meta.path.span(), FIELD_ERR_MESSAGE));
+                }
+                Ok(())
+            })?;
+        }
+        if let (true, Some(default)) = (options.required, &options.default) {
+            return Err(syn::Error::new(default.span(), "A required field cannot have a default value."));
+        }
+        Ok(options)
+    }
+}
+
+enum FieldKind<'a> {
+    /// `Option<T>`。セッターは `Some` で包んだ値をセットする。
+    Option(&'a Type),
+    /// `Vec<T>` などのコレクション。`add_*` と `extend_*` を生成する。
+    Collection(&'a Type),
+    Plain,
+}
+
+impl<'a> FieldKind<'a> {
+    fn of(ty: &'a Type) -> Self {
+        let Type::Path(path) = ty else { return Self::Plain };
+        let Some(segment) = path.path.segments.last() else { return Self::Plain };
+        let PathArguments::AngleBracketed(arguments) = &segment.arguments else { return Self::Plain };
+        let mut types = arguments.args.iter().filter_map(|a| match a {
+            GenericArgument::Type(a) => Some(a),
+            _ => None,
+        });
+        let (Some(inner), None) = (types.next(), types.next()) else { return Self::Plain };
+        match segment.ident.to_string().as_str() {
+            "Option" => Self::Option(inner),
+            "Vec" | "VecDeque" | "HashSet" | "BTreeSet" => Self::Collection(inner),
+            _ => Self::Plain,
+        }
+    }
+}
+
+/// `include_types` から `include_type` のように、コレクションの要素を表す名前を作る。
+fn singular(name: &str) -> &str {
+    match name.strip_suffix('s') {
+        Some(a) if !a.ends_with('s') && !a.is_empty() => a,
+        _ => name,
+    }
+}
+
+/// フィールドに `misskey_client(...)` が付けられていないことを確認する。
+pub(crate) fn reject_field_attributes(ast: &DeriveInput) -> syn::Result<()> {
+    let Data::Struct(data) = &ast.data else { return Ok(()) };
+    match data.fields.iter().flat_map(|a| &a.attrs).find(|a| a.path().is_ident("misskey_client")) {
+        Some(attr) => Err(syn::Error::new(attr.span(), "Field attributes require `misskey_client(builder)`.")),
+        None => Ok(()),
+    }
+}
+
+pub(crate) fn expand(ast: &DeriveInput, options: &BuilderOptions) -> syn::Result<TokenStream> {
+    let Data::Struct(data) = &ast.data else {
+        return Err(syn::Error::new(ast.span(), "Builder can only be derived for structs."));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new(ast.span(), "Builder requires named fields."));
+    };
+    let mut parameters = Vec::new();
+    let mut initializers = Vec::new();
+    let mut setters = Vec::new();
+    for field in &fields.named {
+        let options = FieldOptions::parse(&field.attrs)?;
+        let ident = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        let docs = field.attrs.iter().filter(|a| a.path().is_ident("doc")).collect::<Vec<_>>();
+        let param = |ty: &Type| match options.into {
+            true => quote! {impl ::core::convert::Into<#ty>},
+            false => quote! {#ty},
+        };
+        let value = |ident: &Ident| match options.into {
+            true => quote! {::core::convert::Into::into(#ident)},
+            false => quote! {#ident},
+        };
+        if options.required {
+            let param = param(ty);
+            parameters.push(quote! {#ident: #param});
+            initializers.push(value(ident));
+            continue;
+        }
+        initializers.push(match &options.default {
+            Some(default) => quote! {#default},
+            None => quote! {::core::default::Default::default()},
+        });
+        if options.skip {
+            continue;
+        }
+        match FieldKind::of(ty) {
+            FieldKind::Option(inner) => {
+                let setter = options.setter.clone().unwrap_or_else(|| ident.clone());
+                let (param, value) = (param(inner), value(ident));
+                setters.push(quote! {
+                    #(#docs)*
+                    pub fn #setter(self, #ident: #param) -> Self {
+                        Self { #ident: ::core::option::Option::Some(#value), .. self }
+                    }
+                });
+            },
+            FieldKind::Collection(inner) => {
+                let name = ident.to_string();
+                let add = options.add.clone().unwrap_or_else(|| format_ident!("add_{}", singular(&name)));
+                let extend = options.extend.clone().unwrap_or_else(|| format_ident!("extend_{}", name));
+                let (param, value) = (param(inner), value(ident));
+                let items = match options.into {
+                    true => quote! {#ident.into_iter().map(::core::convert::Into::into)},
+                    false => quote! {#ident},
+                };
+                setters.push(quote! {
+                    #(#docs)*
+                    pub fn #add(mut self, #ident: #param) -> Self {
+                        self.#ident.extend(::core::iter::once(#value));
+                        self
+                    }
+
+                    #(#docs)*
+                    pub fn #extend(mut self, #ident: impl ::core::iter::IntoIterator<Item = #param>) -> Self {
+                        self.#ident.extend(#items);
+                        self
+                    }
+                });
+            },
+            FieldKind::Plain => {
+                let setter = options.setter.clone().unwrap_or_else(|| ident.clone());
+                let (param, value) = (param(ty), value(ident));
+                setters.push(quote! {
+                    #(#docs)*
+                    pub fn #setter(self, #ident: #param) -> Self {
+                        Self { #ident: #value, .. self }
+                    }
+                });
+            },
+        }
+    }
+    let idents = fields.named.iter().map(|a| a.ident.as_ref().unwrap());
+    let (impl_generics, type_generics, where_clause) = ast.generics.split_for_impl();
+    let name = &ast.ident;
+    let BuilderOptions { new, vis } = options;
+    // フィールドが 1 つの場合、`.. self` が不要になる。
+    Ok(quote! {
+        #[allow(clippy::needless_update)]
+        impl #impl_generics #name #type_generics #where_clause {
+            #vis fn #new(#(#parameters),*) -> Self {
+                Self { #(#idents: #initializers),* }
+            }
+
+            #(#setters)*
+        }
+    })
+}
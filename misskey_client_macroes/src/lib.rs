@@ -1,60 +1,125 @@
+mod builder;
+mod schema;
+
 use proc_macro::TokenStream;
-use proc_macro2::Literal;
 use quote::quote;
-use syn::{LitBool, Token, Type};
+use syn::{ExprPath, Ident, LitBool, LitStr, Token, Type};
 use syn::spanned::Spanned;
 
 #[proc_macro_derive(ConstParamJsonRequest, attributes(misskey_client))]
 pub fn derive_const_param_json_request(input: TokenStream) -> TokenStream {
-    const ERR_MESSAGE: &str = r#"Attributes must be following form: `misskey_client(endpoint = "endpoint", response = Response, can_be_empty = bool)`"#;
+    const ERR_MESSAGE: &str = r#"Attributes must be following form: `misskey_client(endpoint = "endpoint", response = Response, error = Error, can_be_empty = bool, credential = bool, permissions(Permission, ...), min_version = "major.minor.patch", validate = path, builder(new = name, vis = visibility))`"#;
     let ast = syn::parse::<syn::DeriveInput>(input).unwrap();
     let mut response: Option<Type> = None;
-    let mut endpoint: Option<Literal> = None;
+    let mut error: Option<Type> = None;
+    let mut endpoint: Option<LitStr> = None;
     let mut can_be_empty: Option<LitBool> = None;
+    let mut credential: Option<LitBool> = None;
+    let mut permissions: Option<Vec<Ident>> = None;
+    let mut min_version: Option<[u32; 3]> = None;
+    let mut validate: Option<ExprPath> = None;
+    let mut builder: Option<builder::BuilderOptions> = None;
     let origin = match proc_macro_crate::crate_name("misskey_client").unwrap() {
         proc_macro_crate::FoundCrate::Itself => quote! {crate},
         proc_macro_crate::FoundCrate::Name(_) => quote! {misskey_client},
     };
     for attr in &ast.attrs {
-        if attr.path().is_ident("misskey_client") {
-            if let Err(e) = attr.parse_nested_meta(|meta| {
-                if meta.path.is_ident("endpoint") && meta.input.peek(Token![=]) {
-                    match meta.value().map(|a| a.parse::<Literal>()).flatten() {
-                        Ok(a) => {
-                            if endpoint.is_some() {
-                                return Err(syn::Error::new(meta.path.span(), "Duplicated definition of endpoint."));
-                            }
-                            endpoint = Some(a)
-                        },
-                        Err(_) => return Err(syn::Error::new(meta.value().map(|a| a.span()).unwrap_or(meta.input.span()), ERR_MESSAGE)),
-                    }
-                } else if meta.path.is_ident("response") && meta.input.peek(Token![=]) {
-                    match meta.value().map(|a| a.parse::<Type>()).flatten() {
-                        Ok(a) => {
-                            if response.is_some() {
-                                return Err(syn::Error::new(meta.path.span(), "Duplicated definition of response."));
-                            }
-                            response = Some(a)
-                        },
-                        Err(_) => return Err(syn::Error::new(meta.value().map(|a| a.span()).unwrap_or(meta.input.span()), ERR_MESSAGE)),
-                    }
-                } else if meta.path.is_ident("can_be_empty") && meta.input.peek(Token![=]) {
-                    match meta.value().map(|a| a.parse::<LitBool>()).flatten() {
-                        Ok(a) => {
-                            if can_be_empty.is_some() {
-                                return Err(syn::Error::new(meta.path.span(), "Duplicated definition of can_be_empty."));
-                            }
-                            can_be_empty = Some(a)
-                        },
-                        Err(e) => return Err(syn::Error::new(meta.value().map(|a| a.span()).unwrap_or(meta.input.span()), e.to_string())),
-                    }
-                } else {
-                    return Err(syn::Error::new(meta.path.span(), ERR_MESSAGE));
+        if attr.path().is_ident("misskey_client") && let Err(e) = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("endpoint") && meta.input.peek(Token![=]) {
+                match meta.value().and_then(|a| a.parse::<LitStr>()) {
+                    Ok(a) => {
+                        if endpoint.is_some() {
+                            return Err(syn::Error::new(meta.path.span(), "Duplicated definition of endpoint."));
+                        }
+                        validate_endpoint(&a)?;
+                        endpoint = Some(a)
+                    },
+                    Err(e) => return Err(syn::Error::new(e.span(), "Endpoint must be a string literal.")),
+                }
+            } else if meta.path.is_ident("response") && meta.input.peek(Token![=]) {
+                match meta.value().and_then(|a| a.parse::<Type>()) {
+                    Ok(a) => {
+                        if response.is_some() {
+                            return Err(syn::Error::new(meta.path.span(), "Duplicated definition of response."));
+                        }
+                        response = Some(a)
+                    },
+                    Err(_) => return Err(syn::Error::new(meta.value().map(|a| a.span()).unwrap_or(meta.input.span()), ERR_MESSAGE)),
+                }
+            } else if meta.path.is_ident("error") && meta.input.peek(Token![=]) {
+                match meta.value().and_then(|a| a.parse::<Type>()) {
+                    Ok(a) => {
+                        if error.is_some() {
+                            return Err(syn::Error::new(meta.path.span(), "Duplicated definition of error."));
+                        }
+                        error = Some(a)
+                    },
+                    Err(_) => return Err(syn::Error::new(meta.value().map(|a| a.span()).unwrap_or(meta.input.span()), ERR_MESSAGE)),
+                }
+            } else if meta.path.is_ident("can_be_empty") && meta.input.peek(Token![=]) {
+                match meta.value().and_then(|a| a.parse::<LitBool>()) {
+                    Ok(a) => {
+                        if can_be_empty.is_some() {
+                            return Err(syn::Error::new(meta.path.span(), "Duplicated definition of can_be_empty."));
+                        }
+                        can_be_empty = Some(a)
+                    },
+                    Err(e) => return Err(syn::Error::new(meta.value().map(|a| a.span()).unwrap_or(meta.input.span()), e.to_string())),
+                }
+            } else if meta.path.is_ident("credential") && meta.input.peek(Token![=]) {
+                match meta.value().and_then(|a| a.parse::<LitBool>()) {
+                    Ok(a) => {
+                        if credential.is_some() {
+                            return Err(syn::Error::new(meta.path.span(), "Duplicated definition of credential."));
+                        }
+                        credential = Some(a)
+                    },
+                    Err(e) => return Err(syn::Error::new(meta.value().map(|a| a.span()).unwrap_or(meta.input.span()), e.to_string())),
+                }
+            } else if meta.path.is_ident("permissions") {
+                if permissions.is_some() {
+                    return Err(syn::Error::new(meta.path.span(), "Duplicated definition of permissions."));
+                }
+                let mut list = Vec::new();
+                meta.parse_nested_meta(|inner| match inner.path.get_ident() {
+                    Some(ident) => {
+                        list.push(ident.clone());
+                        Ok(())
+                    },
+                    None => Err(syn::Error::new(inner.path.span(), "Permission must be a variant name of `Permission`.")),
+                })?;
+                permissions = Some(list);
+            } else if meta.path.is_ident("min_version") && meta.input.peek(Token![=]) {
+                match meta.value().and_then(|a| a.parse::<LitStr>()) {
+                    Ok(a) => {
+                        if min_version.is_some() {
+                            return Err(syn::Error::new(meta.path.span(), "Duplicated definition of min_version."));
+                        }
+                        min_version = Some(parse_version(&a)?)
+                    },
+                    Err(e) => return Err(syn::Error::new(e.span(), "Minimum version must be a string literal.")),
+                }
+            } else if meta.path.is_ident("validate") && meta.input.peek(Token![=]) {
+                match meta.value().and_then(|a| a.parse::<ExprPath>()) {
+                    Ok(a) => {
+                        if validate.is_some() {
+                            return Err(syn::Error::new(meta.path.span(), "Duplicated definition of validate."));
+                        }
+                        validate = Some(a)
+                    },
+                    Err(e) => return Err(syn::Error::new(e.span(), "Validate must be a path to a function.")),
                 }
-                Ok(())
-            }) {
-                return e.into_compile_error().into();
+            } else if meta.path.is_ident("builder") {
+                if builder.is_some() {
+                    return Err(syn::Error::new(meta.path.span(), "Duplicated definition of builder."));
+                }
+                builder = Some(builder::BuilderOptions::parse(&meta)?);
+            } else {
+                return Err(syn::Error::new(meta.path.span(), ERR_MESSAGE));
             }
+            Ok(())
+        }) {
+            return e.into_compile_error().into();
         }
     }
     let Some(response) = response else {
@@ -63,21 +128,93 @@ pub fn derive_const_param_json_request(input: TokenStream) -> TokenStream {
     let Some(endpoint) = endpoint else {
         return syn::Error::new(ast.span(), "Missing endpoint.").into_compile_error().into();
     };
-    let generics = &ast.generics;
+    let builder = match builder {
+        Some(options) => builder::expand(&ast, &options),
+        None => builder::reject_field_attributes(&ast).map(|_| quote! {}),
+    };
+    let builder = match builder {
+        Ok(a) => a,
+        Err(e) => return e.into_compile_error().into(),
+    };
+    let error = match error {
+        Some(error) => quote! {#error},
+        None => quote! {#origin::errors::NoEndpointError},
+    };
+    let (impl_generics, type_generics, where_clause) = ast.generics.split_for_impl();
     let name = &ast.ident;
-    match can_be_empty {
-        Some(can_be_empty) => quote! {
-            impl #generics #origin::ConstParamJsonRequest for #name #generics {
-                type Response = #response;
-                const ENDPOINT: &'static str = #endpoint;
-                const CAN_BE_EMPTY: bool = #can_be_empty;
-            }
-        },
-        None => quote! {
-            impl #generics #origin::ConstParamJsonRequest for #name #generics {
-                type Response = #response;
-                const ENDPOINT: &'static str = #endpoint;
-            }
-        },
+    let can_be_empty = can_be_empty.map(|a| quote! {
+        const CAN_BE_EMPTY: bool = #a;
+    });
+    let credential = credential.map(|a| quote! {
+        const REQUIRES_CREDENTIAL: bool = #a;
+    });
+    let permissions = permissions.map(|a| quote! {
+        const PERMISSIONS: &'static [#origin::miauth::Permission] = &[#(#origin::miauth::Permission::#a),*];
+    });
+    let min_version = min_version.map(|[major, minor, patch]| quote! {
+        const MIN_VERSION: ::core::option::Option<#origin::common::ServerVersion> = ::core::option::Option::Some(#origin::common::ServerVersion::new(#major, #minor, #patch));
+    });
+    let validate = validate.map(|a| quote! {
+        fn validate(&self) -> ::core::result::Result<(), #origin::errors::InvalidRequestReason> {
+            #a(self)
+        }
+    });
+    quote! {
+        impl #impl_generics #origin::ConstParamJsonRequest for #name #type_generics #where_clause {
+            type Response = #response;
+            type Error = #error;
+            const ENDPOINT: &'static str = #endpoint;
+            #can_be_empty
+            #credential
+            #permissions
+            #min_version
+            #validate
+        }
+
+        #builder
     }.into()
 }
+
+/// `from_str_strict` が JSON と比較する `Schema` を、`#[serde(...)]` の属性から実装する。
+#[proc_macro_derive(Schema)]
+pub fn derive_schema(input: TokenStream) -> TokenStream {
+    let ast = syn::parse::<syn::DeriveInput>(input).unwrap();
+    let origin = match proc_macro_crate::crate_name("misskey_client").unwrap() {
+        proc_macro_crate::FoundCrate::Itself => quote! {crate},
+        proc_macro_crate::FoundCrate::Name(_) => quote! {misskey_client},
+    };
+    match schema::expand(&ast, &origin) {
+        Ok(a) => a.into(),
+        Err(e) => e.into_compile_error().into(),
+    }
+}
+
+/// エンドポイントが `/` で始まり、`/api` を含まない形式であることを確認する。
+fn validate_endpoint(endpoint: &LitStr) -> syn::Result<()> {
+    let value = endpoint.value();
+    let message = if !value.starts_with('/') {
+        "Endpoint must start with `/`."
+    } else if value == "/api" || value.starts_with("/api/") {
+        "Endpoint must not include the `/api` prefix."
+    } else if value.len() == 1 || value.ends_with('/') || value.contains("//") {
+        "Endpoint must not have empty path segments."
+    } else if value.contains(|a: char| a.is_whitespace() || a == '?' || a == '#') {
+        "Endpoint must not contain whitespaces, queries or fragments."
+    } else {
+        return Ok(());
+    };
+    Err(syn::Error::new(endpoint.span(), message))
+}
+
+/// `2024.2.0` のような `major.minor.patch` 形式のバージョンを解釈する。
+fn parse_version(version: &LitStr) -> syn::Result<[u32; 3]> {
+    let value = version.value();
+    let parts = value.split('.').map(|a| match a.bytes().all(|a| a.is_ascii_digit()) {
+        true => a.parse::<u32>().ok(),
+        false => None,
+    }).collect::<Option<Vec<_>>>();
+    match parts.as_deref() {
+        Some(&[major, minor, patch]) => Ok([major, minor, patch]),
+        _ => Err(syn::Error::new(version.span(), "Minimum version must be following form: `major.minor.patch`")),
+    }
+}
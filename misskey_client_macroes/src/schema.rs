@@ -0,0 +1,190 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Attribute, Data, DeriveInput, Fields, LitStr, Token, Type};
+use syn::spanned::Spanned;
+
+/// `#[serde(...)]` のうち `Schema` の生成に使う属性
+#[derive(Default)]
+struct SerdeAttributes {
+    rename: Option<String>,
+    rename_all: Option<String>,
+    rename_all_fields: Option<String>,
+    tag: Option<String>,
+    aliases: Vec<String>,
+    flatten: bool,
+    skip: bool,
+    untagged: bool,
+    skip_serializing_if: bool,
+}
+
+impl SerdeAttributes {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut parsed = Self::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("serde")) {
+            attr.parse_nested_meta(|meta| {
+                let value = match meta.input.peek(Token![=]) {
+                    true => Some(meta.value()?.parse::<LitStr>()?.value()),
+                    false => None,
+                };
+                let Some(ident) = meta.path.get_ident() else {
+                    return Ok(());
+                };
+                match (ident.to_string().as_str(), value) {
+                    ("rename", Some(a)) => parsed.rename = Some(a),
+                    ("rename_all", Some(a)) => parsed.rename_all = Some(a),
+                    ("rename_all_fields", Some(a)) => parsed.rename_all_fields = Some(a),
+                    ("tag", Some(a)) => parsed.tag = Some(a),
+                    ("alias", Some(a)) => parsed.aliases.push(a),
+                    ("flatten", None) => parsed.flatten = true,
+                    ("skip" | "skip_deserializing", None) => parsed.skip = true,
+                    ("untagged", None) => parsed.untagged = true,
+                    ("skip_serializing_if", Some(_)) => parsed.skip_serializing_if = true,
+                    _ => {},
+                }
+                Ok(())
+            })?;
+        }
+        Ok(parsed)
+    }
+}
+
+/// `rename_all` の規則で名前を変換する。
+fn rename(name: &str, rule: Option<&str>, span: proc_macro2::Span) -> syn::Result<String> {
+    let name = name.trim_start_matches("r#");
+    match rule {
+        None => Ok(name.to_string()),
+        Some("camelCase") if name.contains('_') || name.starts_with(char::is_lowercase) => {
+            let mut parts = name.split('_');
+            let first = parts.next().unwrap_or_default().to_string();
+            Ok(parts.fold(first, |mut a, b| {
+                let mut chars = b.chars();
+                if let Some(c) = chars.next() {
+                    a.extend(c.to_uppercase());
+                    a.push_str(chars.as_str());
+                }
+                a
+            }))
+        },
+        Some("camelCase") => {
+            let mut chars = name.chars();
+            Ok(chars.next().map(|a| a.to_lowercase().chain(chars).collect()).unwrap_or_default())
+        },
+        Some(rule) => Err(syn::Error::new(span, format!("Unsupported rename rule `{}`.", rule))),
+    }
+}
+
+/// `ExtraFields` 型のフィールドか否か
+fn is_extra_fields(ty: &Type) -> bool {
+    matches!(ty, Type::Path(a) if a.path.segments.last().is_some_and(|a| a.ident == "ExtraFields"))
+}
+
+/// 名前付きのフィールドを確認する文と、`#[serde(flatten)]` したフィールドの確認の結果を表す式
+fn expand_fields(fields: &Fields, rule: Option<&str>, origin: &TokenStream) -> syn::Result<(Vec<TokenStream>, Vec<TokenStream>)> {
+    let (mut checks, mut flattened) = (Vec::new(), Vec::new());
+    for field in fields {
+        let attrs = SerdeAttributes::parse(&field.attrs)?;
+        let ty = &field.ty;
+        let cfg = field.attrs.iter().filter(|a| a.path().is_ident("cfg"));
+        if attrs.skip || (attrs.flatten && is_extra_fields(ty)) {
+            continue;
+        }
+        if attrs.flatten {
+            flattened.push(quote! {
+                #(#cfg)* <#ty as #origin::schema::Schema>::check_fields(object, path, known, drifts)
+            });
+            continue;
+        }
+        let Some(ident) = &field.ident else {
+            return Err(syn::Error::new(field.span(), "Schema can only be derived for named fields."));
+        };
+        let name = match attrs.rename {
+            Some(a) => a,
+            None => rename(&ident.to_string(), rule, ident.span())?,
+        };
+        let aliases = &attrs.aliases;
+        let optional = attrs.skip_serializing_if;
+        checks.push(quote! {
+            #(#cfg)* #origin::schema::check_field::<#ty>(object, path, &[#name #(, #aliases)*], #optional, known, drifts);
+        });
+    }
+    Ok((checks, flattened))
+}
+
+/// `check_fields` の戻り値。`#[serde(flatten)]` したフィールドのいずれかが任意のキーを受け入れる場合は `false`
+fn result(flattened: &[TokenStream]) -> TokenStream {
+    match flattened.is_empty() {
+        true => quote! {true},
+        false => quote! {#(#flattened)&*},
+    }
+}
+
+pub(crate) fn expand(ast: &DeriveInput, origin: &TokenStream) -> syn::Result<TokenStream> {
+    let attrs = SerdeAttributes::parse(&ast.attrs)?;
+    let body = match &ast.data {
+        Data::Struct(data) => {
+            let (checks, flattened) = expand_fields(&data.fields, attrs.rename_all.as_deref(), origin)?;
+            let result = result(&flattened);
+            quote! {
+                #(#checks)*
+                #result
+            }
+        },
+        Data::Enum(data) => {
+            let Some(tag) = &attrs.tag else {
+                return Err(syn::Error::new(ast.ident.span(), "Schema can only be derived for internally tagged enums."));
+            };
+            let mut arms = Vec::new();
+            for variant in &data.variants {
+                let variant_attrs = SerdeAttributes::parse(&variant.attrs)?;
+                if variant_attrs.untagged || variant_attrs.skip {
+                    continue;
+                }
+                let name = match variant_attrs.rename {
+                    Some(a) => a,
+                    None => rename(&variant.ident.to_string(), attrs.rename_all.as_deref(), variant.ident.span())?,
+                };
+                let (checks, flattened) = match &variant.fields {
+                    Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                        let ty = &fields.unnamed[0].ty;
+                        (Vec::new(), vec![quote! {<#ty as #origin::schema::Schema>::check_fields(object, path, known, drifts)}])
+                    },
+                    Fields::Unnamed(_) => return Err(syn::Error::new(variant.span(), "Tuple variants are not supported in internally tagged enums.")),
+                    fields => expand_fields(fields, attrs.rename_all_fields.as_deref(), origin)?,
+                };
+                let result = result(&flattened);
+                arms.push(quote! {
+                    #name => {
+                        #(#checks)*
+                        #result
+                    },
+                });
+            }
+            quote! {
+                let ::core::option::Option::Some(tag) = object.get(#tag).and_then(#origin::schema::Value::as_str) else {
+                    return false;
+                };
+                known.push(#tag);
+                match tag {
+                    #(#arms)*
+                    _ => false,
+                }
+            }
+        },
+        Data::Union(_) => return Err(syn::Error::new(ast.ident.span(), "Schema cannot be derived for unions.")),
+    };
+    let (impl_generics, type_generics, where_clause) = ast.generics.split_for_impl();
+    let name = &ast.ident;
+    Ok(quote! {
+        impl #impl_generics #origin::schema::Schema for #name #type_generics #where_clause {
+            #[allow(unused_variables)]
+            fn check_fields(
+                object: &#origin::schema::Map<::std::string::String, #origin::schema::Value>,
+                path: &str,
+                known: &mut ::std::vec::Vec<&'static str>,
+                drifts: &mut ::std::vec::Vec<#origin::schema::SchemaDrift>,
+            ) -> bool {
+                #body
+            }
+        }
+    })
+}
@@ -0,0 +1,8 @@
+use misskey_client_macroes::ConstParamJsonRequest;
+use serde_derive::Serialize;
+
+#[derive(Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = 1, response = ())]
+struct Request;
+
+fn main() {}
@@ -0,0 +1,11 @@
+use misskey_client_macroes::ConstParamJsonRequest;
+use serde_derive::Serialize;
+
+#[derive(Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/i", response = ())]
+struct Request {
+    #[misskey_client(required)]
+    id: String,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use misskey_client_macroes::Schema;
+use serde_derive::Deserialize;
+
+#[derive(Deserialize, Schema)]
+#[serde(untagged)]
+enum Untagged {
+    Text(String),
+    Number(u64),
+}
+
+fn main() {}
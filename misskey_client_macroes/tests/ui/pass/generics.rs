@@ -0,0 +1,64 @@
+use std::fmt::Debug;
+
+use misskey_client::{common::ServerVersion, errors::InvalidRequestReason, responses::notes::NoteInfo, ConstParamJsonRequest};
+use misskey_client_macroes::ConstParamJsonRequest;
+use serde::Serialize;
+use serde_derive::Serialize;
+
+#[derive(Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/show", response = NoteInfo)]
+struct Lifetime<'a> {
+    note_id: &'a str,
+}
+
+#[derive(Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/show", response = T::Response, builder)]
+struct Bounded<T: ConstParamJsonRequest + Debug, U = ()>
+where
+    U: Serialize + Default,
+    for<'de> T::Response: serde::Deserialize<'de>,
+{
+    #[misskey_client(required)]
+    inner: T,
+    extra: U,
+}
+
+#[derive(Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/users/notes", response = Vec<NoteInfo>, builder)]
+struct ConstGeneric<const N: usize> {
+    #[misskey_client(default = N)]
+    limit: usize,
+}
+
+#[derive(Debug, Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/i", response = (), min_version = "2023.12.0")]
+struct Unit;
+
+#[derive(Serialize, ConstParamJsonRequest)]
+#[misskey_client(endpoint = "/notes/create", response = (), validate = Validated::check)]
+struct Validated {
+    text: String,
+}
+
+impl Validated {
+    fn check(&self) -> Result<(), InvalidRequestReason> {
+        match self.text.is_empty() {
+            true => Err(InvalidRequestReason::Empty { param: "text" }),
+            false => Ok(()),
+        }
+    }
+}
+
+fn main() {
+    assert_eq!(Lifetime::ENDPOINT, "/notes/show");
+    let bounded = Bounded::<Unit, u8>::new(Unit).extra(1);
+    assert_eq!(bounded.extra, 1);
+    assert_eq!(ConstGeneric::<30>::new().limit, 30);
+    assert_eq!(ConstGeneric::<30>::new().limit(5).limit, 5);
+    assert_eq!(<ConstGeneric<1>>::ENDPOINT, "/users/notes");
+    assert_eq!(Unit::MIN_VERSION, Some(ServerVersion::new(2023, 12, 0)));
+    assert_eq!(Lifetime::MIN_VERSION, None);
+    assert_eq!(Validated { text: String::new() }.validate(), Err(InvalidRequestReason::Empty { param: "text" }));
+    assert_eq!(Validated { text: "a".to_string() }.validate(), Ok(()));
+    assert_eq!(Unit.validate(), Ok(()));
+}
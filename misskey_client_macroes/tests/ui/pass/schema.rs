@@ -0,0 +1,51 @@
+use misskey_client::schema::{from_str_strict, SchemaDrift, StrictError};
+use misskey_client_macroes::Schema;
+use serde_derive::Deserialize;
+
+#[derive(Deserialize, Schema)]
+#[serde(rename_all = "camelCase")]
+struct Outer {
+    user_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reply_id: Option<String>,
+    #[serde(rename = "type", alias = "kind")]
+    outer_type: String,
+    #[serde(flatten)]
+    inner: Inner,
+    #[serde(skip)]
+    #[allow(dead_code)]
+    skipped: u8,
+    detail: Detail,
+}
+
+#[derive(Deserialize, Schema)]
+#[serde(rename_all = "camelCase")]
+struct Inner {
+    note_count: usize,
+}
+
+#[derive(Deserialize, Schema)]
+#[serde(rename_all = "camelCase", rename_all_fields = "camelCase", tag = "type")]
+enum Detail {
+    Follow {
+        follower_id: String,
+    },
+    Test,
+}
+
+fn drifts(json: &str) -> Vec<SchemaDrift> {
+    match from_str_strict::<Outer>(json) {
+        Ok(_) => Vec::new(),
+        Err(StrictError::Drift(a)) => a,
+        Err(a) => panic!("{}", a),
+    }
+}
+
+fn main() {
+    assert_eq!(drifts(r#"{"userId": "a", "kind": "b", "noteCount": 1, "detail": {"type": "test"}}"#), []);
+    assert_eq!(drifts(r#"{"userId": "a", "type": "b", "noteCount": 1, "detail": {"type": "follow", "followerId": "c"}, "replyId": "d"}"#), []);
+    assert_eq!(drifts(r#"{"userId": "a", "type": "b", "noteCount": 1, "detail": {"type": "follow", "followerId": "c", "new": 1}, "new": 2}"#), [
+        SchemaDrift::UnknownField { path: "/detail/new".to_string() },
+        SchemaDrift::UnknownField { path: "/new".to_string() },
+    ]);
+}
@@ -0,0 +1,104 @@
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+/// モックサーバーに注入する異常系の応答
+#[derive(Clone, Debug)]
+pub enum Fault {
+    /// 指定したエラーを返す。
+    Error(MockError),
+    /// 指定した時間だけ待ってから通常通り応答する。
+    Delay(Duration),
+    /// 指定したステータスコードと本文をそのまま返す。
+    MalformedBody {
+        status: u16,
+        body: String,
+    },
+}
+
+impl Fault {
+    /// `RATE_LIMIT_EXCEEDED` を返す。
+    pub fn rate_limit_exceeded() -> Self {
+        Self::Error(MockError::rate_limit_exceeded())
+    }
+
+    /// JSON として解釈できない本文を `200` で返す。
+    pub fn malformed_body() -> Self {
+        Self::MalformedBody { status: 200, body: r#"{"createdNote": {"id": "#.to_string() }
+    }
+}
+
+impl From<MockError> for Fault {
+    fn from(value: MockError) -> Self {
+        Self::Error(value)
+    }
+}
+
+/// Misskey サーバーが返すエラー応答
+#[derive(Clone, Debug)]
+pub struct MockError {
+    pub status: u16,
+    pub code: String,
+    pub id: String,
+    pub message: String,
+    pub info: Option<Value>,
+}
+
+impl MockError {
+    pub fn new(status: u16, code: impl Into<String>, id: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            code: code.into(),
+            id: id.into(),
+            message: message.into(),
+            info: None,
+        }
+    }
+
+    pub fn info(self, info: Value) -> Self {
+        Self { info: Some(info), .. self }
+    }
+
+    pub fn rate_limit_exceeded() -> Self {
+        Self::new(429, "RATE_LIMIT_EXCEEDED", "d5826d14-3982-4d2e-8011-b9e9f02499ef", "Rate limit exceeded. Please try again later.")
+            .info(json!({ "resetMs": 1000, "fullResetMs": 1000 }))
+    }
+
+    pub fn credential_required() -> Self {
+        Self::new(401, "CREDENTIAL_REQUIRED", "1384574d-a912-4b81-8601-c7b1c4085df1", "Credential required.")
+    }
+
+    pub fn authentication_failed() -> Self {
+        Self::new(401, "AUTHENTICATION_FAILED", "b0a7f5f8-dc2f-4171-b91f-de88ad238e14", "Authentication failed. Please ensure your token is correct.")
+    }
+
+    pub fn permission_denied() -> Self {
+        Self::new(403, "PERMISSION_DENIED", "1370e5b7-d4eb-4566-bb1d-7748ee6a1838", "Your app does not have the necessary permissions to use this endpoint.")
+    }
+
+    pub fn invalid_param(param: &str, reason: &str) -> Self {
+        Self::new(400, "INVALID_PARAM", "3d81ceae-475f-4600-b2a8-2bc116157532", "Invalid param.")
+            .info(json!({ "param": param, "reason": reason }))
+    }
+
+    pub fn no_such_endpoint() -> Self {
+        Self::new(404, "NO_SUCH_ENDPOINT", "9f7d4fb0-9bd4-4a1f-8a50-c0d0c1cb5e5a", "No such endpoint.")
+    }
+
+    pub fn internal_error() -> Self {
+        Self::new(500, "INTERNAL_ERROR", "5d37dbcb-891e-41ca-a3d6-e690c97775ac", "Internal error occurred. Please contact us if the error persists.")
+    }
+
+    pub(crate) fn to_json(&self) -> Value {
+        let mut error = json!({
+            "message": self.message,
+            "code": self.code,
+            "id": self.id,
+            "kind": if self.status >= 500 { "server" } else { "client" },
+        });
+        if let Some(info) = &self.info {
+            error["info"] = info.clone();
+        }
+        json!({ "error": error })
+    }
+}
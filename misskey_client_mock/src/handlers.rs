@@ -0,0 +1,729 @@
+use chrono::{DateTime, Duration, Utc};
+use serde_json::{json, Map, Value};
+
+use crate::{fault::MockError, state::{MockFavorite, MockNote, MockPoll, MockReaction, MockState}};
+
+/// ハンドラーの応答。`None` のときは `204 No Content` を返す。
+pub(crate) type HandlerResult = Result<Option<Value>, MockError>;
+
+/// `/api/endpoints` が返すエンドポイントの一覧
+const ENDPOINTS: &[&str] = &[
+    "channels/my-favorites", "charts/active-users", "charts/ap-request", "charts/drive", "charts/federation", "endpoints", "i", "i/favorites", "i/notifications", "meta", "notes/children",
+    "notes/conversation", "notes/create", "notes/delete", "notes/favorites/create", "notes/favorites/delete", "notes/featured", "notes/global-timeline", "notes/hybrid-timeline",
+    "notes/local-timeline", "notes/mentions", "notes/polls/recommendation", "notes/polls/vote", "notes/reactions", "notes/reactions/create", "notes/reactions/delete", "notes/renotes",
+    "notes/replies", "notes/search", "notes/show", "notes/state", "notes/thread-muting/create", "notes/thread-muting/delete", "notes/timeline", "notes/translate", "notes/unrenote",
+    "users/notes", "users/relation", "users/show",
+];
+
+/// Misskey 本体にはなく、フォークでのみ提供するエンドポイント
+const FORK_ENDPOINTS: &[&str] = &["notes/update"];
+
+/// 設定したソフトウェアでエンドポイントを提供するか否か
+fn is_available(state: &MockState, endpoint: &str) -> bool {
+    !state.meta.removed_endpoints.contains(endpoint) && (state.meta.software != "misskey" || !FORK_ENDPOINTS.contains(&endpoint))
+}
+
+pub(crate) fn handle(state: &mut MockState, endpoint: &str, body: &Value) -> HandlerResult {
+    if !is_available(state, endpoint) {
+        return Err(MockError::no_such_endpoint());
+    }
+    state.viewer = body.get("i").and_then(Value::as_str).and_then(|a| state.tokens.get(a)).map(|a| a.user_id.clone());
+    if let Some(session) = endpoint.strip_prefix("miauth/").and_then(|a| a.strip_suffix("/check")) {
+        return miauth_check(state, session);
+    }
+    match endpoint {
+        ".well-known/nodeinfo" => Ok(Some(json!({
+            "links": [
+                { "rel": "http://nodeinfo.diaspora.software/ns/schema/2.0", "href": format!("http://{}/nodeinfo/2.0", state.authority) },
+                { "rel": "http://nodeinfo.diaspora.software/ns/schema/2.1", "href": format!("http://{}/nodeinfo/2.1", state.authority) },
+            ],
+        }))),
+        "nodeinfo/2.0" | "nodeinfo/2.1" => nodeinfo(state, &endpoint["nodeinfo/".len()..]),
+        "meta" => meta(state, body),
+        "endpoints" => {
+            let mut endpoints = ENDPOINTS.iter().chain(FORK_ENDPOINTS).filter(|a| is_available(state, a)).collect::<Vec<_>>();
+            endpoints.sort();
+            Ok(Some(endpoints.into_iter().map(|a| json!(a)).collect()))
+        },
+        "i" => i(state, body),
+        "i/notifications" => i_notifications(state, body),
+        "i/favorites" => {
+            let user_id = authenticate(state, body, "read:favorites")?;
+            let favorites = paginate(state.favorites.iter().filter(|(_, a)| a.user_id == user_id), body, 10, 100)?;
+            Ok(Some(favorites.into_iter().map(|a| state.pack_favorite(a)).collect()))
+        },
+        "notes/create" => notes_create(state, body),
+        "notes/delete" => notes_delete(state, body),
+        "notes/search" => notes_search(state, body),
+        "notes/show" => {
+            let note = find_note(state, body, "24fcbfc6-2e37-42b6-8388-c29b3861a08d")?;
+            Ok(Some(state.pack_note(note, 1)))
+        },
+        "notes/children" => {
+            let note_id = str_param(body, "noteId")?.unwrap_or_default();
+            let notes = state.notes.iter()
+                .filter(|(_, a)| a.reply_id.as_deref() == Some(note_id) || (a.renote_id.as_deref() == Some(note_id) && a.text.is_some()));
+            let notes = paginate(notes, body, 10, 100)?;
+            Ok(Some(notes.into_iter().map(|a| state.pack_note(a, 1)).collect()))
+        },
+        "notes/conversation" => notes_conversation(state, body),
+        "notes/replies" => {
+            let note_id = str_param(body, "noteId")?.unwrap_or_default();
+            let notes = paginate(state.notes.iter().filter(|(_, a)| a.reply_id.as_deref() == Some(note_id)), body, 10, 100)?;
+            Ok(Some(notes.into_iter().map(|a| state.pack_note(a, 1)).collect()))
+        },
+        "notes/renotes" => {
+            let note = find_note(state, body, "12908022-2e21-46cd-ba6a-3edaf6093f46")?;
+            let user_id = str_param(body, "userId")?;
+            let notes = state.notes.iter()
+                .filter(|(_, a)| a.renote_id.as_ref() == Some(&note.id))
+                .filter(|(_, a)| user_id.is_none_or(|b| a.user_id == b));
+            let notes = paginate(notes, body, 10, 100)?;
+            Ok(Some(notes.into_iter().map(|a| state.pack_note(a, 1)).collect()))
+        },
+        "notes/reactions" => {
+            let note = find_note(state, body, "263fff3d-d0e1-4af4-bea7-8408059b451a")?;
+            let reaction = str_param(body, "type")?;
+            let reactions = state.reactions.iter()
+                .filter(|(_, a)| a.note_id == note.id)
+                .filter(|(_, a)| reaction.is_none_or(|b| a.reaction == b));
+            let reactions = paginate(reactions, body, 10, 100)?;
+            Ok(Some(reactions.into_iter().map(|a| state.pack_reaction(a)).collect()))
+        },
+        "notes/update" => notes_update(state, body),
+        "notes/unrenote" => {
+            let user_id = authenticate(state, body, "write:notes")?;
+            let note_id = find_note(state, body, "efd4a259-2442-496b-8dd7-b255aa1a160f")?.id.clone();
+            state.notes.retain(|_, a| !(a.user_id == user_id && a.renote_id.as_ref() == Some(&note_id) && a.text.is_none() && a.file_ids.is_empty() && a.poll.is_none()));
+            let notes = &state.notes;
+            state.favorites.retain(|_, a| notes.contains_key(&a.note_id));
+            Ok(None)
+        },
+        "notes/translate" => notes_translate(state, body),
+        "notes/featured" => {
+            let until = str_param(body, "untilId")?;
+            let channel_id = str_param(body, "channelId")?;
+            let limit = limit_param(body, 10, 100)?;
+            let notes = state.notes.values().rev()
+                .filter(|a| a.visibility == "public" && a.channel_id.as_deref() == channel_id)
+                .filter(|a| state.reactions.values().any(|b| b.note_id == a.id))
+                .filter(|a| until.is_none_or(|b| a.id.as_str() < b))
+                .take(limit);
+            Ok(Some(notes.map(|a| state.pack_note(a, 1)).collect()))
+        },
+        "notes/mentions" => {
+            let user_id = authenticate(state, body, "read:account")?;
+            let username = state.users[&user_id].username.clone();
+            let following = bool_param(body, "following", false)?;
+            let visibility = str_param(body, "visibility")?;
+            let notes = state.notes.iter()
+                .filter(|(_, a)| {
+                    let mentioned = a.text.iter().flat_map(|b| b.split_whitespace()).any(|b| b.strip_prefix('@').is_some_and(|c| c.eq_ignore_ascii_case(&username)));
+                    let replied = a.reply_id.as_ref().and_then(|b| state.notes.get(b)).is_some_and(|b| b.user_id == user_id);
+                    a.user_id != user_id && (mentioned || replied)
+                })
+                .filter(|(_, a)| !following || state.follows.contains(&(user_id.clone(), a.user_id.clone())))
+                .filter(|(_, a)| visibility.is_none_or(|b| a.visibility == b));
+            let notes = paginate(notes, body, 10, 100)?;
+            Ok(Some(notes.into_iter().map(|a| state.pack_note(a, 1)).collect()))
+        },
+        "notes/polls/vote" => notes_polls_vote(state, body),
+        "notes/polls/recommendation" => {
+            let user_id = authenticate(state, body, "read:account")?;
+            let limit = limit_param(body, 10, 100)?;
+            let offset = body.get("offset").and_then(Value::as_u64).unwrap_or(0) as usize;
+            let exclude_channels = bool_param(body, "excludeChannels", false)?;
+            let now = Utc::now();
+            let notes = state.notes.values().rev()
+                .filter(|a| a.user_id != user_id && a.visibility == "public")
+                .filter(|a| a.poll.as_ref().is_some_and(|b| b.expires_at.is_none_or(|c| c > now)))
+                .filter(|a| !state.poll_votes.iter().any(|b| b.0 == a.id && b.1 == user_id))
+                .filter(|a| !exclude_channels || a.channel_id.is_none())
+                .skip(offset)
+                .take(limit);
+            Ok(Some(notes.map(|a| state.pack_note(a, 1)).collect()))
+        },
+        "notes/reactions/create" => notes_reactions_create(state, body),
+        "notes/reactions/delete" => {
+            let user_id = authenticate(state, body, "write:reactions")?;
+            let note_id = find_note(state, body, "764d9fce-f9f2-4a0e-92b1-6ceac9a7ad37")?.id.clone();
+            let Some(id) = state.reactions.values().find(|a| a.note_id == note_id && a.user_id == user_id).map(|a| a.id.clone()) else {
+                return Err(MockError::new(400, "NOT_REACTED", "92f4426d-4196-4125-aa5b-02943e2ec8fc", "You are not reacting to that note."));
+            };
+            state.reactions.remove(&id);
+            Ok(None)
+        },
+        "notes/state" => {
+            let user_id = authenticate(state, body, "read:favorites")?;
+            let note_id = str_param(body, "noteId")?.unwrap_or_default();
+            let is_favorited = state.favorites.values().any(|a| a.note_id == note_id && a.user_id == user_id);
+            let is_muted_thread = state.notes.get(note_id).is_some_and(|a| state.thread_mutings.contains(&(user_id.clone(), state.thread_id(a).to_string())));
+            Ok(Some(json!({ "isFavorited": is_favorited, "isMutedThread": is_muted_thread })))
+        },
+        "notes/favorites/create" => {
+            let user_id = authenticate(state, body, "write:favorites")?;
+            let note_id = find_note(state, body, "6dd26674-e060-4816-909a-45ba3f4da458")?.id.clone();
+            if state.favorites.values().any(|a| a.note_id == note_id && a.user_id == user_id) {
+                return Err(MockError::new(400, "ALREADY_FAVORITED", "a402c12b-34dd-41d2-97d8-4d2ffd96a1a6", "The note has already been marked as a favorite."));
+            }
+            let id = state.gen_id();
+            let created_at = state.last_id_date();
+            state.favorites.insert(id.clone(), MockFavorite { id, created_at, note_id, user_id });
+            Ok(None)
+        },
+        "notes/favorites/delete" => {
+            let user_id = authenticate(state, body, "write:favorites")?;
+            let note_id = find_note(state, body, "80848a2c-398f-4343-baa9-df1d57696c56")?.id.clone();
+            let Some(id) = state.favorites.values().find(|a| a.note_id == note_id && a.user_id == user_id).map(|a| a.id.clone()) else {
+                return Err(MockError::new(400, "NOT_FAVORITED", "b625fc69-635e-45e9-86f4-dbefbef35af5", "You have not marked that note a favorite."));
+            };
+            state.favorites.remove(&id);
+            Ok(None)
+        },
+        "notes/thread-muting/create" => {
+            let user_id = authenticate(state, body, "write:account")?;
+            let thread_id = state.thread_id(find_note(state, body, "5ff67ada-ed3b-2e71-8e87-a1a421e177d2")?).to_string();
+            state.thread_mutings.insert((user_id, thread_id));
+            Ok(None)
+        },
+        "notes/thread-muting/delete" => {
+            let user_id = authenticate(state, body, "write:account")?;
+            let thread_id = state.thread_id(find_note(state, body, "bddd57ac-ceb3-b29d-4334-86ea5fae481a")?).to_string();
+            state.thread_mutings.remove(&(user_id, thread_id));
+            Ok(None)
+        },
+        "notes/timeline" => {
+            let user_id = authenticate(state, body, "read:account")?;
+            timeline(state, body, |state, a| a.user_id == user_id || state.follows.contains(&(user_id.clone(), a.user_id.clone())), false)
+        },
+        "notes/local-timeline" => {
+            reject_with_replies_and_files(body, "dd9c8400-1cb5-4eef-8a31-200c5f933793")?;
+            timeline(state, body, |_, a| a.visibility == "public" && a.channel_id.is_none(), true)
+        },
+        "notes/hybrid-timeline" => {
+            let user_id = authenticate(state, body, "read:account")?;
+            reject_with_replies_and_files(body, "dfaa3eb7-8002-4cb7-bcc4-1095df46656f")?;
+            timeline(state, body, |state, a| (a.visibility == "public" && a.channel_id.is_none()) || a.user_id == user_id || state.follows.contains(&(user_id.clone(), a.user_id.clone())), true)
+        },
+        "notes/global-timeline" => timeline(state, body, |_, a| a.visibility == "public" && a.channel_id.is_none(), false),
+        "users/show" => users_show(state, body),
+        "users/notes" => users_notes(state, body),
+        "users/relation" => users_relation(state, body),
+        "channels/my-favorites" => {
+            authenticate(state, body, "read:channels")?;
+            Ok(Some(json!([])))
+        },
+        "charts/active-users" => chart(body, &["readWrite", "read", "write", "registeredWithinWeek", "registeredWithinMonth", "registeredWithinYear", "registeredOutsideWeek", "registeredOutsideMonth", "registeredOutsideYear"]),
+        "charts/ap-request" => chart(body, &["deliverFailed", "deliverSucceeded", "inboxReceived"]),
+        "charts/drive" => {
+            let detail = chart(body, &["incCount", "incSize", "decCount", "decSize"])?;
+            Ok(Some(json!({ "local": detail, "remote": detail })))
+        },
+        "charts/federation" => chart(body, &["deliveredInstances", "inboxInstances", "stalled", "sub", "pub", "pubsub", "subActive", "pubActive"]),
+        _ => Err(MockError::no_such_endpoint()),
+    }
+}
+
+/// トークンを検証し、ログイン中のユーザー ID を返す。
+fn authenticate(state: &MockState, body: &Value, permission: &str) -> Result<String, MockError> {
+    let Some(token) = body.get("i").and_then(Value::as_str) else {
+        return Err(MockError::credential_required());
+    };
+    let Some(token) = state.tokens.get(token) else {
+        return Err(MockError::authentication_failed());
+    };
+    if token.permissions.as_ref().is_some_and(|a| !a.contains(permission)) {
+        return Err(MockError::permission_denied());
+    }
+    Ok(token.user_id.clone())
+}
+
+fn str_param<'a>(body: &'a Value, name: &str) -> Result<Option<&'a str>, MockError> {
+    match body.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(a)) => Ok(Some(a)),
+        Some(_) => Err(MockError::invalid_param(&format!("/{}", name), "must be string")),
+    }
+}
+
+fn bool_param(body: &Value, name: &str, default: bool) -> Result<bool, MockError> {
+    match body.get(name) {
+        None | Some(Value::Null) => Ok(default),
+        Some(Value::Bool(a)) => Ok(*a),
+        Some(_) => Err(MockError::invalid_param(&format!("/{}", name), "must be boolean")),
+    }
+}
+
+fn limit_param(body: &Value, default: usize, max: usize) -> Result<usize, MockError> {
+    match body.get("limit") {
+        None | Some(Value::Null) => Ok(default),
+        Some(Value::Number(a)) => match a.as_u64() {
+            Some(a) if (1..=max as u64).contains(&a) => Ok(a as usize),
+            _ => Err(MockError::invalid_param("/limit", &format!("must be >= 1 and <= {}", max))),
+        },
+        Some(_) => Err(MockError::invalid_param("/limit", "must be integer")),
+    }
+}
+
+/// `sinceId` と `untilId` によるページングを行う。<br />
+/// Misskey と同様に、`sinceId` のみを指定したときは古い順、それ以外は新しい順に返す。
+fn paginate<'a, T>(items: impl DoubleEndedIterator<Item = (&'a String, T)>, body: &Value, default_limit: usize, max_limit: usize) -> Result<Vec<T>, MockError> {
+    let limit = limit_param(body, default_limit, max_limit)?;
+    let since = str_param(body, "sinceId")?;
+    let until = str_param(body, "untilId")?;
+    let items = items.filter(|(id, _)| since.is_none_or(|a| id.as_str() > a) && until.is_none_or(|a| id.as_str() < a));
+    Ok(if since.is_some() && until.is_none() {
+        items.take(limit).map(|a| a.1).collect()
+    } else {
+        items.rev().take(limit).map(|a| a.1).collect()
+    })
+}
+
+fn i(state: &mut MockState, body: &Value) -> HandlerResult {
+    let user_id = authenticate(state, body, "read:account")?;
+    Ok(Some(state.pack_detailed_user(&state.users[&user_id])))
+}
+
+fn i_notifications(state: &mut MockState, body: &Value) -> HandlerResult {
+    let user_id = authenticate(state, body, "read:notifications")?;
+    let types = |name: &str| body.get(name)
+        .and_then(Value::as_array)
+        .map(|a| a.iter().filter_map(Value::as_str).map(str::to_string).collect::<Vec<_>>())
+        .unwrap_or_default();
+    let (include, exclude) = (types("includeTypes"), types("excludeTypes"));
+    let notifications = state.notifications.iter()
+        .filter(|(_, a)| a.owner_id == user_id)
+        .filter(|(_, a)| include.is_empty() || include.contains(&a.notification_type))
+        .filter(|(_, a)| !exclude.contains(&a.notification_type));
+    let notifications = paginate(notifications, body, 10, 100)?;
+    Ok(Some(notifications.into_iter().map(|a| state.pack_notification(a)).collect()))
+}
+
+fn notes_create(state: &mut MockState, body: &Value) -> HandlerResult {
+    let user_id = authenticate(state, body, "write:notes")?;
+    let text = str_param(body, "text")?.filter(|a| !a.is_empty()).map(str::to_string);
+    let renote_id = str_param(body, "renoteId")?.map(str::to_string);
+    let reply_id = str_param(body, "replyId")?.map(str::to_string);
+    // `mediaIds` は `fileIds` の古い名前
+    let file_ids = match body.get("fileIds") {
+        None | Some(Value::Null) => ids_param(body, "mediaIds")?,
+        Some(_) => ids_param(body, "fileIds")?,
+    };
+    let poll = poll_param(body)?;
+    if text.is_none() && renote_id.is_none() && file_ids.is_empty() && poll.is_none() {
+        return Err(MockError::invalid_param("/text", "must NOT have fewer than 1 characters"));
+    }
+    if file_ids.iter().any(|a| state.files.get(a).is_none_or(|a| a.user_id != user_id)) {
+        return Err(MockError::new(400, "NO_SUCH_FILE", "b6992544-63e7-67f0-fa7f-32444b1b5306", "Some files are not found."));
+    }
+    if poll.as_ref().and_then(|a| a.expires_at).is_some_and(|a| a <= Utc::now()) {
+        return Err(MockError::new(400, "CANNOT_CREATE_ALREADY_EXPIRED_POLL", "04da457d-b083-4055-9082-955525eda5a5", "Poll is already expired."));
+    }
+    if let Some(reply_id) = &reply_id && !state.notes.contains_key(reply_id) {
+        return Err(MockError::new(400, "NO_SUCH_REPLY_TARGET", "749ee0f6-d3da-459a-bf02-282e2da4292c", "No such reply target."));
+    }
+    if let Some(renote_id) = &renote_id && !state.notes.contains_key(renote_id) {
+        return Err(MockError::new(400, "NO_SUCH_RENOTE_TARGET", "b5c90186-4ab0-49c8-9bba-a1f76c282ba4", "No such renote target."));
+    }
+    let visibility = str_param(body, "visibility")?.unwrap_or("public").to_string();
+    if !["public", "home", "followers", "specified"].contains(&visibility.as_str()) {
+        return Err(MockError::invalid_param("/visibility", "must be equal to one of the allowed values"));
+    }
+    let note = MockNote {
+        id: state.gen_id(),
+        created_at: state.last_id_date(),
+        user_id: user_id.clone(),
+        text,
+        cw: str_param(body, "cw")?.map(str::to_string),
+        visibility,
+        visible_user_ids: body.get("visibleUserIds")
+            .and_then(Value::as_array)
+            .map(|a| a.iter().filter_map(Value::as_str).map(str::to_string).collect())
+            .unwrap_or_default(),
+        local_only: bool_param(body, "localOnly", false)?,
+        reaction_acceptance: str_param(body, "reactionAcceptance")?.map(str::to_string),
+        reply_id,
+        renote_id,
+        channel_id: str_param(body, "channelId")?.map(str::to_string),
+        file_ids,
+        poll,
+        updated_at: None,
+        edit_history: Vec::new(),
+    };
+    notify_related(state, &note);
+    let id = note.id.clone();
+    state.notes.insert(id.clone(), note);
+    if bool_param(body, "noCreatedNote", false)? {
+        return Ok(None);
+    }
+    Ok(Some(json!({ "createdNote": state.pack_note(&state.notes[&id], 1) })))
+}
+
+fn notes_update(state: &mut MockState, body: &Value) -> HandlerResult {
+    let user_id = authenticate(state, body, "write:notes")?;
+    // 他のユーザーのノートは存在しないものとして扱う。
+    let note = find_note(state, body, "a6584e14-6e01-4ad3-b566-851e7bf0d474")?;
+    if note.user_id != user_id {
+        return Err(MockError::new(400, "NO_SUCH_NOTE", "a6584e14-6e01-4ad3-b566-851e7bf0d474", "No such note."));
+    }
+    let id = note.id.clone();
+    let text = str_param(body, "text")?.map(str::to_string);
+    let cw = str_param(body, "cw")?.map(str::to_string);
+    let file_ids = ids_param(body, "fileIds")?;
+    if text.as_ref().is_some_and(|a| a.is_empty()) || (text.is_none() && file_ids.is_empty()) {
+        return Err(MockError::invalid_param("/text", "must NOT have fewer than 1 characters"));
+    }
+    if file_ids.iter().any(|a| state.files.get(a).is_none_or(|a| a.user_id != user_id)) {
+        return Err(MockError::new(400, "NO_SUCH_FILE", "b6992544-63e7-67f0-fa7f-32444b1b5306", "Some files are not found."));
+    }
+    if let Some(note) = state.notes.get_mut(&id) {
+        let now = Utc::now();
+        note.edit_history.push((now, note.text.clone()));
+        note.updated_at = Some(now);
+        note.text = text;
+        note.cw = cw;
+        if !file_ids.is_empty() {
+            note.file_ids = file_ids;
+        }
+    }
+    Ok(None)
+}
+
+/// 重複のない 1 個以上 16 個以下の ID の配列。指定されていない場合は空になる。
+fn ids_param(body: &Value, name: &str) -> Result<Vec<String>, MockError> {
+    let ids = match body.get(name) {
+        None | Some(Value::Null) => return Ok(Vec::new()),
+        Some(Value::Array(a)) => a.iter().map(|a| a.as_str().map(str::to_string)).collect::<Option<Vec<_>>>(),
+        Some(_) => None,
+    };
+    let Some(ids) = ids else {
+        return Err(MockError::invalid_param(&format!("/{}", name), "must be array of string"));
+    };
+    if !(1..=16).contains(&ids.len()) {
+        return Err(MockError::invalid_param(&format!("/{}", name), "must NOT have fewer than 1 items or more than 16 items"));
+    }
+    if ids.iter().enumerate().any(|(i, a)| ids[..i].contains(a)) {
+        return Err(MockError::invalid_param(&format!("/{}", name), "must NOT have duplicate items"));
+    }
+    Ok(ids)
+}
+
+fn poll_param(body: &Value) -> Result<Option<MockPoll>, MockError> {
+    let poll = match body.get("poll") {
+        None | Some(Value::Null) => return Ok(None),
+        Some(a) => a,
+    };
+    let choices = poll.get("choices")
+        .and_then(Value::as_array)
+        .and_then(|a| a.iter().map(|a| a.as_str().filter(|a| (1..=50).contains(&a.encode_utf16().count())).map(str::to_string)).collect::<Option<Vec<_>>>())
+        .filter(|a| (2..=10).contains(&a.len()) && !a.iter().enumerate().any(|(i, b)| a[..i].contains(b)));
+    let Some(choices) = choices else {
+        return Err(MockError::invalid_param("/poll/choices", "must have 2 to 10 unique choices of 1 to 50 characters"));
+    };
+    let expires_at = match (date_param(poll, "expiresAt")?, date_param(poll, "expiredAfter")?) {
+        (Some(a), _) => DateTime::from_timestamp_millis(a),
+        (None, Some(a)) if a >= 1 => Some(Utc::now() + Duration::milliseconds(a)),
+        (None, Some(_)) => return Err(MockError::invalid_param("/poll/expiredAfter", "must be >= 1")),
+        (None, None) => None,
+    };
+    Ok(Some(MockPoll { choices, multiple: bool_param(poll, "multiple", false)?, expires_at }))
+}
+
+/// 作成されたノートに関連する通知を生成する。
+fn notify_related(state: &mut MockState, note: &MockNote) {
+    let owner = |id: &Option<String>| id.as_ref().and_then(|a| state.notes.get(a)).map(|a| a.user_id.clone());
+    let mut targets = Vec::new();
+    if let Some(owner) = owner(&note.reply_id) {
+        targets.push((owner, "reply"));
+    }
+    if let Some(owner) = owner(&note.renote_id) {
+        targets.push((owner, if note.text.is_some() { "quote" } else { "renote" }));
+    }
+    for word in note.text.iter().flat_map(|a| a.split_whitespace()) {
+        if let Some(user) = word.strip_prefix('@').and_then(|a| state.find_user_by_name(a)) {
+            targets.push((user.id.clone(), "mention"));
+        }
+    }
+    // スレッドをミュートしたユーザーには、リプライとメンションを通知しない。
+    let thread_id = state.thread_id(note).to_string();
+    let mut notified = Vec::new();
+    for (owner, notification_type) in targets {
+        let muted = matches!(notification_type, "reply" | "mention") && state.thread_mutings.contains(&(owner.clone(), thread_id.clone()));
+        if owner != note.user_id && !notified.contains(&owner) && !muted {
+            state.notify(&owner, notification_type, Some(&note.user_id), Some(&note.id), Map::new());
+            notified.push(owner);
+        }
+    }
+}
+
+fn notes_delete(state: &mut MockState, body: &Value) -> HandlerResult {
+    let user_id = authenticate(state, body, "write:notes")?;
+    let note_id = str_param(body, "noteId")?.unwrap_or_default();
+    let Some(note) = state.notes.get(note_id) else {
+        return Err(MockError::new(400, "NO_SUCH_NOTE", "490be23f-8c1f-4796-819f-94cb4f9d1630", "No such note."));
+    };
+    if note.user_id != user_id {
+        return Err(MockError::new(400, "ACCESS_DENIED", "fe8d7103-0ea8-4ec3-814d-f8b401dc69e9", "Access denied."));
+    }
+    state.notes.remove(note_id);
+    state.favorites.retain(|_, a| a.note_id != note_id);
+    Ok(None)
+}
+
+fn notes_search(state: &mut MockState, body: &Value) -> HandlerResult {
+    let query = str_param(body, "query")?.unwrap_or_default();
+    let user_id = str_param(body, "userId")?;
+    let channel_id = str_param(body, "channelId")?;
+    let notes = state.notes.iter()
+        .filter(|(_, a)| a.text.as_deref().is_some_and(|a| a.contains(query)))
+        .filter(|(_, a)| user_id.is_none_or(|b| a.user_id == b))
+        .filter(|(_, a)| channel_id.is_none_or(|b| a.channel_id.as_deref() == Some(b)));
+    let notes = paginate(notes, body, 10, 100)?;
+    Ok(Some(notes.into_iter().map(|a| state.pack_note(a, 1)).collect()))
+}
+
+/// `sinceDate` や `expiresAt` のような日時をミリ秒の UNIX 時間として解釈する。
+fn date_param(body: &Value, name: &str) -> Result<Option<i64>, MockError> {
+    match body.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Number(a)) if a.is_i64() => Ok(a.as_i64()),
+        Some(_) => Err(MockError::invalid_param(&format!("/{}", name), "must be integer")),
+    }
+}
+
+fn reject_with_replies_and_files(body: &Value, id: &str) -> Result<(), MockError> {
+    if bool_param(body, "withReplies", false)? && bool_param(body, "withFiles", false)? {
+        return Err(MockError::new(400, "BOTH_WITH_REPLIES_AND_WITH_FILES", id, "Specifying both withReplies and withFiles is not supported"));
+    }
+    Ok(())
+}
+
+/// `visible` を満たすノートを、タイムライン共通のパラメーターで絞り込んで返す。<br />
+/// `replies` が `false` のときは `withReplies` を受け付けず、リプライを常に含める。
+fn timeline(state: &mut MockState, body: &Value, visible: impl Fn(&MockState, &MockNote) -> bool, replies: bool) -> HandlerResult {
+    let with_files = bool_param(body, "withFiles", false)?;
+    let with_renotes = bool_param(body, "withRenotes", true)?;
+    let with_replies = !replies || bool_param(body, "withReplies", false)?;
+    let (since, until) = (date_param(body, "sinceDate")?, date_param(body, "untilDate")?);
+    let notes = state.notes.iter()
+        .filter(|(_, a)| visible(state, a))
+        .filter(|(_, a)| !with_files || !a.file_ids.is_empty())
+        .filter(|(_, a)| with_renotes || a.renote_id.is_none() || a.text.is_some())
+        .filter(|(_, a)| with_replies || a.reply_id.is_none())
+        .filter(|(_, a)| since.is_none_or(|b| a.created_at.timestamp_millis() > b) && until.is_none_or(|b| a.created_at.timestamp_millis() < b));
+    let notes = paginate(notes, body, 10, 100)?;
+    Ok(Some(notes.into_iter().map(|a| state.pack_note(a, 1)).collect()))
+}
+
+/// `noteId` のノートを取得する。存在しない場合は `id` の `NO_SUCH_NOTE` を返す。
+fn find_note<'a>(state: &'a MockState, body: &Value, id: &str) -> Result<&'a MockNote, MockError> {
+    let note_id = str_param(body, "noteId")?.unwrap_or_default();
+    state.notes.get(note_id).ok_or_else(|| MockError::new(400, "NO_SUCH_NOTE", id, "No such note."))
+}
+
+fn notes_translate(state: &mut MockState, body: &Value) -> HandlerResult {
+    let user_id = authenticate(state, body, "read:account")?;
+    let note = find_note(state, body, "bea9b03f-36e0-49c5-a4db-627a029f8971")?;
+    let visible = match note.visibility.as_str() {
+        "specified" => note.user_id == user_id || note.visible_user_ids.contains(&user_id),
+        "followers" => note.user_id == user_id || state.follows.contains(&(user_id.clone(), note.user_id.clone())),
+        _ => true,
+    };
+    if !visible {
+        return Err(MockError::new(400, "CANNOT_TRANSLATE_INVISIBLE_NOTE", "ea29f2ca-c368-43b3-aaf1-5ac3e74bbe5d", "Cannot translate invisible note."));
+    }
+    let Some(text) = &note.text else {
+        return Ok(None);
+    };
+    if !state.meta.translator_available {
+        return Err(MockError::new(400, "UNAVAILABLE", "50a70314-2d8a-431b-b433-efa5cc56444c", "Translate of notes unavailable."));
+    }
+    let target_lang = str_param(body, "targetLang")?.unwrap_or_default();
+    Ok(Some(json!({
+        "sourceLang": if text.is_ascii() { "en" } else { "ja" },
+        "text": format!("[{}] {}", target_lang, text),
+    })))
+}
+
+fn notes_polls_vote(state: &mut MockState, body: &Value) -> HandlerResult {
+    let user_id = authenticate(state, body, "write:votes")?;
+    let note = find_note(state, body, "ecafbd2e-c283-4d6d-aecb-1a0a33b75396")?;
+    let Some(poll) = &note.poll else {
+        return Err(MockError::new(400, "NO_POLL", "5f979967-52d9-4314-a911-1c673727f92f", "The note does not attach a poll."));
+    };
+    let choice = match body.get("choice") {
+        Some(Value::Number(a)) if a.is_u64() => a.as_u64().unwrap_or_default() as usize,
+        _ => return Err(MockError::invalid_param("/choice", "must be integer")),
+    };
+    if choice >= poll.choices.len() {
+        return Err(MockError::new(400, "INVALID_CHOICE", "e0cc9a04-f2e8-41e4-a5f1-4127293260cc", "Choice ID is invalid."));
+    }
+    if poll.expires_at.is_some_and(|a| a <= Utc::now()) {
+        return Err(MockError::new(400, "ALREADY_EXPIRED", "1022a357-b085-4054-9083-8f8de358337e", "The poll is already expired."));
+    }
+    let voted = state.poll_votes.iter().filter(|a| a.0 == note.id && a.1 == user_id).map(|a| a.2).collect::<Vec<_>>();
+    if voted.contains(&choice) || (!poll.multiple && !voted.is_empty()) {
+        return Err(MockError::new(400, "ALREADY_VOTED", "0963fc77-efac-419b-9424-b391608dc6d8", "You have already voted."));
+    }
+    let note_id = note.id.clone();
+    state.poll_votes.insert((note_id, user_id, choice));
+    Ok(None)
+}
+
+fn notes_reactions_create(state: &mut MockState, body: &Value) -> HandlerResult {
+    let user_id = authenticate(state, body, "write:reactions")?;
+    let note = find_note(state, body, "033d0620-5bfe-4027-965d-980b0c85a3ea")?.clone();
+    if note.renote_id.is_some() && note.text.is_none() {
+        return Err(MockError::new(400, "CANNOT_REACT_TO_RENOTE", "eaccdc08-ddef-43fe-908f-d108faad57f5", "You cannot react to Renote."));
+    }
+    if state.reactions.values().any(|a| a.note_id == note.id && a.user_id == user_id) {
+        return Err(MockError::new(400, "ALREADY_REACTED", "71efcf98-86d6-4e2b-b2ad-9d032369366b", "You are already reacting to that note."));
+    }
+    let Some(reaction) = str_param(body, "reaction")?.filter(|a| !a.is_empty()) else {
+        return Err(MockError::invalid_param("/reaction", "must have required property 'reaction'"));
+    };
+    // Misskey と同様に、ローカルのカスタム絵文字を `:name@.:` にそろえ、受け付けないリアクションをいいねに置き換える。
+    let reaction = match reaction.strip_prefix(':').and_then(|a| a.strip_suffix(':')) {
+        Some(name) if !name.contains('@') => format!(":{}@.:", name),
+        Some(name) if !name.ends_with("@.") => "❤".to_string(),
+        _ => reaction.to_string(),
+    };
+    let reaction = match note.reaction_acceptance.as_deref() {
+        Some("likeOnly") => "❤".to_string(),
+        _ => reaction,
+    };
+    let id = state.gen_id();
+    let created_at = state.last_id_date();
+    state.reactions.insert(id.clone(), MockReaction { id, created_at, note_id: note.id.clone(), user_id: user_id.clone(), reaction: reaction.clone() });
+    if note.user_id != user_id {
+        state.notify(&note.user_id, "reaction", Some(&user_id), Some(&note.id), [("reaction".to_string(), json!(reaction))].into_iter().collect());
+    }
+    Ok(None)
+}
+
+fn notes_conversation(state: &mut MockState, body: &Value) -> HandlerResult {
+    let note = find_note(state, body, "e1035875-9551-45ec-afa8-1ded1fcb53c8")?;
+    let limit = limit_param(body, 10, 100)?;
+    let offset = body.get("offset").and_then(Value::as_u64).unwrap_or(0) as usize;
+    let mut conversation = Vec::new();
+    let mut current = note.reply_id.as_ref().and_then(|a| state.notes.get(a));
+    while let Some(note) = current {
+        conversation.push(note);
+        current = note.reply_id.as_ref().and_then(|a| state.notes.get(a));
+    }
+    Ok(Some(conversation.into_iter().skip(offset).take(limit).map(|a| state.pack_note(a, 1)).collect()))
+}
+
+fn users_show(state: &mut MockState, body: &Value) -> HandlerResult {
+    let user = match (str_param(body, "userId")?, str_param(body, "username")?) {
+        (Some(id), _) => state.users.get(id),
+        (None, Some(username)) => state.find_user_by_name(username),
+        (None, None) => return Err(MockError::invalid_param("/", "must match a schema in anyOf")),
+    };
+    match user {
+        Some(user) => Ok(Some(state.pack_detailed_user(user))),
+        None => Err(MockError::new(400, "NO_SUCH_USER", "4362f8dc-731f-4ad8-a694-be5a88922a24", "No such user.")),
+    }
+}
+
+fn users_notes(state: &mut MockState, body: &Value) -> HandlerResult {
+    let Some(user_id) = str_param(body, "userId")? else {
+        return Err(MockError::invalid_param("/userId", "must have required property 'userId'"));
+    };
+    if !state.users.contains_key(user_id) {
+        return Err(MockError::new(400, "NO_SUCH_USER", "27e494ba-2ac2-48e8-893b-10d4d8c2387b", "No such user."));
+    }
+    let with_replies = bool_param(body, "withReplies", false)?;
+    let with_renotes = bool_param(body, "withRenotes", true)?;
+    let notes = state.notes.iter()
+        .filter(|(_, a)| a.user_id == user_id)
+        .filter(|(_, a)| with_replies || a.reply_id.is_none())
+        .filter(|(_, a)| with_renotes || a.renote_id.is_none() || a.text.is_some());
+    let notes = paginate(notes, body, 10, 100)?;
+    Ok(Some(notes.into_iter().map(|a| state.pack_note(a, 1)).collect()))
+}
+
+fn users_relation(state: &mut MockState, body: &Value) -> HandlerResult {
+    let me = authenticate(state, body, "read:account")?;
+    let relation = |id: &str| json!({
+        "id": id,
+        "isFollowing": state.follows.contains(&(me.clone(), id.to_string())),
+        "isFollowed": state.follows.contains(&(id.to_string(), me.clone())),
+        "hasPendingFollowRequestFromYou": false,
+        "hasPendingFollowRequestToYou": false,
+        "isBlocking": false,
+        "isBlocked": false,
+        "isMuted": false,
+        "isRenoteMuted": false,
+    });
+    match body.get("userId") {
+        Some(Value::String(id)) => Ok(Some(relation(id))),
+        Some(Value::Array(ids)) => Ok(Some(ids.iter().filter_map(Value::as_str).map(relation).collect())),
+        _ => Err(MockError::invalid_param("/userId", "must match a schema in anyOf")),
+    }
+}
+
+fn meta(state: &mut MockState, body: &Value) -> HandlerResult {
+    let mut meta = json!({
+        "maintainerName": null,
+        "maintainerEmail": null,
+        "version": state.meta.version,
+        "name": "Mock",
+        "uri": format!("http://{}", state.authority),
+        "description": null,
+        "langs": [],
+        "disableRegistration": false,
+        "emailRequiredForSignup": false,
+        "enableHcaptcha": false,
+        "enableRecaptcha": false,
+        "enableTurnstile": false,
+        "maxNoteTextLength": 3000,
+        "enableEmail": false,
+        "enableServiceWorker": false,
+        "translatorAvailable": state.meta.translator_available,
+    });
+    if let Some(version) = &state.meta.based_misskey_version {
+        meta["basedMisskeyVersion"] = json!(version);
+    }
+    if bool_param(body, "detail", true)? {
+        meta["features"] = json!({
+            "registration": true,
+            "emailRequiredForSignup": false,
+            "localTimeline": true,
+            "globalTimeline": true,
+            "hcaptcha": false,
+            "recaptcha": false,
+            "turnstile": false,
+            "objectStorage": false,
+            "serviceWorker": false,
+            "miauth": true,
+        });
+    }
+    Ok(Some(meta))
+}
+
+fn nodeinfo(state: &mut MockState, version: &str) -> HandlerResult {
+    Ok(Some(json!({
+        "version": version,
+        "software": { "name": state.meta.software, "version": state.meta.version },
+        "protocols": ["activitypub"],
+        "services": { "inbound": [], "outbound": ["atom1.0", "rss2.0"] },
+        "openRegistrations": true,
+        "usage": { "users": { "total": state.users.len() }, "localPosts": state.notes.len() },
+        "metadata": { "nodeName": "Mock" },
+    })))
+}
+
+fn miauth_check(state: &mut MockState, session: &str) -> HandlerResult {
+    let token = state.miauth_sessions.get(session).cloned().flatten();
+    let Some(token) = token else {
+        return Ok(Some(json!({ "ok": false })));
+    };
+    let user = &state.users[&state.tokens[&token].user_id];
+    Ok(Some(json!({ "ok": true, "token": token, "user": state.pack_detailed_user(user) })))
+}
+
+fn chart(body: &Value, fields: &[&str]) -> HandlerResult {
+    let limit = limit_param(body, 30, 500)?;
+    Ok(Some(fields.iter().map(|a| (a.to_string(), json!(vec![0; limit]))).collect::<Map<_, _>>().into()))
+}
@@ -0,0 +1,60 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+
+/// 受信した HTTP リクエスト
+pub(crate) struct RawRequest {
+    pub(crate) path: String,
+    pub(crate) body: Vec<u8>,
+}
+
+/// 接続からリクエストを 1 件読み取る。接続が閉じられた場合は `None` を返す。
+pub(crate) fn read_request<T>(reader: &mut BufReader<T>) -> io::Result<Option<RawRequest>> where T: Read {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    let mut parts = line.split_whitespace();
+    let _method = parts.next();
+    let path = parts.next().unwrap_or("/").to_string();
+    let mut length = 0;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let header = line.trim();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((key, value)) = header.split_once(':')
+            && key.trim().eq_ignore_ascii_case("content-length") {
+            length = value.trim().parse().unwrap_or(0);
+        }
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    Ok(Some(RawRequest { path, body }))
+}
+
+pub(crate) fn write_response<T>(stream: &mut T, status: u16, body: &str, request_id: usize) -> io::Result<()> where T: Write {
+    let mut response = format!("HTTP/1.1 {} {}\r\nx-request-id: {}\r\n", status, reason(status), request_id);
+    if !body.is_empty() {
+        response.push_str("content-type: application/json; charset=utf-8\r\n");
+    }
+    response.push_str(&format!("content-length: {}\r\nconnection: keep-alive\r\n\r\n{}", body.len(), body));
+    stream.write_all(response.as_bytes())?;
+    stream.flush()
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        204 => "No Content",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        _ => "Unknown",
+    }
+}
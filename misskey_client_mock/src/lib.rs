@@ -0,0 +1,248 @@
+//! `misskey_client` の結合テスト用に、Misskey API を模倣する HTTP サーバーを提供するクレート
+//!
+//! 状態はすべてメモリ上に保持され、サーバーを破棄すると失われる。
+
+mod fault;
+mod handlers;
+mod http;
+mod state;
+
+use std::{collections::HashSet, io::{self, BufReader}, net::{SocketAddr, TcpListener, TcpStream}, sync::{atomic::{AtomicBool, Ordering}, Arc, Mutex, MutexGuard}, thread::{self, JoinHandle}};
+
+use serde_json::{Map, Value};
+
+pub use fault::{Fault, MockError};
+pub use state::RecordedRequest;
+
+use crate::state::{MockFile, MockReaction, MockState, MockToken, MockUser};
+
+/// ローカルで動作する Misskey のモックサーバー
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<MockState>>,
+    shutdown: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+/// モックサーバーに登録したユーザー
+#[derive(Clone, Debug)]
+pub struct MockAccount {
+    pub id: String,
+    pub username: String,
+    /// すべての権限を持つアクセストークン
+    pub token: String,
+}
+
+impl MockServer {
+    /// `127.0.0.1` の空いているポートでサーバーを起動する。
+    pub fn start() -> io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let mut state = MockState::default();
+        state.authority = addr.to_string();
+        let state = Arc::new(Mutex::new(state));
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let handle = {
+            let state = state.clone();
+            let shutdown = shutdown.clone();
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if shutdown.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    let Ok(stream) = stream else { continue };
+                    let state = state.clone();
+                    thread::spawn(move || {
+                        let _ = serve(stream, state);
+                    });
+                }
+            })
+        };
+        Ok(Self { addr, state, shutdown, handle: Some(handle) })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// `MisskeyHttpClient::new` に渡すオーソリティ
+    pub fn authority(&self) -> String {
+        self.addr.to_string()
+    }
+
+    /// サーバーへの新しい接続を開く。
+    pub fn connect(&self) -> io::Result<TcpStream> {
+        TcpStream::connect(self.addr)
+    }
+
+    /// ユーザーを登録する。
+    pub fn add_user(&self, username: &str) -> MockAccount {
+        let mut state = self.state();
+        let id = state.gen_id();
+        let created_at = state.last_id_date();
+        let token = state.gen_token();
+        state.users.insert(id.clone(), MockUser {
+            id: id.clone(),
+            username: username.to_string(),
+            name: None,
+            is_bot: false,
+            created_at,
+        });
+        state.tokens.insert(token.clone(), MockToken { user_id: id.clone(), permissions: None });
+        MockAccount { id, username: username.to_string(), token }
+    }
+
+    /// 指定した権限のみを持つアクセストークンを発行する。
+    pub fn issue_token(&self, user_id: &str, permissions: &[&str]) -> String {
+        let mut state = self.state();
+        let token = state.gen_token();
+        state.tokens.insert(token.clone(), MockToken {
+            user_id: user_id.to_string(),
+            permissions: Some(permissions.iter().map(|a| a.to_string()).collect::<HashSet<_>>()),
+        });
+        token
+    }
+
+    /// `follower` が `followee` をフォローした状態にする。
+    pub fn follow(&self, follower: &str, followee: &str) {
+        let mut state = self.state();
+        state.follows.insert((follower.to_string(), followee.to_string()));
+        state.notify(followee, "follow", Some(follower), None, Map::new());
+    }
+
+    /// `user_id` のユーザーがノートにリアクションした状態にし、リアクションの ID を返す。
+    pub fn react(&self, user_id: &str, note_id: &str, reaction: &str) -> String {
+        let mut state = self.state();
+        let id = state.gen_id();
+        let created_at = state.last_id_date();
+        state.reactions.insert(id.clone(), MockReaction {
+            id: id.clone(),
+            created_at,
+            note_id: note_id.to_string(),
+            user_id: user_id.to_string(),
+            reaction: reaction.to_string(),
+        });
+        id
+    }
+
+    /// ユーザーのドライブに画像ファイルを追加し、その ID を返す。
+    pub fn upload_file(&self, user_id: &str, name: &str) -> String {
+        let mut state = self.state();
+        let id = state.gen_id();
+        let created_at = state.last_id_date();
+        state.files.insert(id.clone(), MockFile {
+            id: id.clone(),
+            created_at,
+            user_id: user_id.to_string(),
+            name: name.to_string(),
+        });
+        id
+    }
+
+    /// 任意の通知を追加し、その ID を返す。<br />
+    /// `extra` のキーは応答の JSON にそのまま追加される。
+    pub fn push_notification(&self, owner_id: &str, notification_type: &str, user_id: Option<&str>, note_id: Option<&str>, extra: Map<String, Value>) -> String {
+        self.state().notify(owner_id, notification_type, user_id, note_id, extra)
+    }
+
+    /// MiAuth のセッションを承認し、発行したアクセストークンを返す。<br />
+    /// `session` には `MiAuth::get_uri` のパスに含まれる UUID を指定する。
+    pub fn approve_miauth(&self, session: &str, user_id: &str, permissions: &[&str]) -> String {
+        let token = self.issue_token(user_id, permissions);
+        self.state().miauth_sessions.insert(session.to_string(), Some(token.clone()));
+        token
+    }
+
+    /// `/api/meta` と NodeInfo が返すソフトウェア名とバージョンを設定する。既定では `misskey` の `2025.4.0`
+    pub fn set_software(&self, name: &str, version: &str) {
+        let mut state = self.state();
+        state.meta.software = name.to_string();
+        state.meta.version = version.to_string();
+    }
+
+    /// フォークが `/api/meta` で報告する、基にした Misskey のバージョンを設定する。
+    pub fn set_based_misskey_version(&self, version: &str) {
+        self.state().meta.based_misskey_version = Some(version.to_string());
+    }
+
+    /// `notes/translate` を有効にする。本文の前に `[翻訳先の言語]` を付けたものを翻訳として返す。
+    pub fn enable_translator(&self) {
+        self.state().meta.translator_available = true;
+    }
+
+    /// エンドポイント (`/api` を除く) を削除し、`/api/endpoints` に含めず `NO_SUCH_ENDPOINT` を返すようにする。
+    pub fn remove_endpoint(&self, endpoint: &str) {
+        self.state().meta.removed_endpoints.insert(endpoint.trim_start_matches('/').to_string());
+    }
+
+    /// 次に受信したリクエストに対して異常系の応答を注入する。
+    pub fn inject(&self, fault: impl Into<Fault>) {
+        self.state().faults.push_back((None, fault.into()));
+    }
+
+    /// 指定したエンドポイント (`/api` を除く) への次のリクエストに対して異常系の応答を注入する。
+    pub fn inject_for(&self, endpoint: &str, fault: impl Into<Fault>) {
+        self.state().faults.push_back((Some(endpoint.trim_start_matches('/').to_string()), fault.into()));
+    }
+
+    /// これまでに受信したリクエストの一覧
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state().requests.clone()
+    }
+
+    /// 現在のノートの件数
+    pub fn notes_count(&self) -> usize {
+        self.state().notes.len()
+    }
+
+    fn state(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|a| a.into_inner())
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        let _ = TcpStream::connect(self.addr);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn serve(stream: TcpStream, state: Arc<Mutex<MockState>>) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    while let Some(request) = http::read_request(&mut reader)? {
+        let endpoint = request.path.strip_prefix("/api/").unwrap_or(request.path.trim_start_matches('/')).to_string();
+        let body = serde_json::from_slice::<Value>(&request.body).unwrap_or(Value::Null);
+        let (fault, request_id) = {
+            let mut state = state.lock().unwrap_or_else(|a| a.into_inner());
+            state.requests.push(RecordedRequest { endpoint: endpoint.clone(), body: body.clone() });
+            (state.take_fault(&endpoint), state.requests.len())
+        };
+        let result = match fault {
+            Some(Fault::Error(error)) => Err(error),
+            Some(Fault::MalformedBody { status, body }) => {
+                http::write_response(&mut writer, status, &body, request_id)?;
+                continue;
+            },
+            Some(Fault::Delay(duration)) => {
+                thread::sleep(duration);
+                process(&state, &endpoint, &body)
+            },
+            None => process(&state, &endpoint, &body),
+        };
+        match result {
+            Ok(Some(value)) => http::write_response(&mut writer, 200, &value.to_string(), request_id)?,
+            Ok(None) => http::write_response(&mut writer, 204, "", request_id)?,
+            Err(error) => http::write_response(&mut writer, error.status, &error.to_json().to_string(), request_id)?,
+        }
+    }
+    Ok(())
+}
+
+fn process(state: &Mutex<MockState>, endpoint: &str, body: &Value) -> handlers::HandlerResult {
+    let mut state = state.lock().unwrap_or_else(|a| a.into_inner());
+    handlers::handle(&mut state, endpoint, body)
+}
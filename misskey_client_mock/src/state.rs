@@ -0,0 +1,391 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+use chrono::{DateTime, Utc};
+use serde_json::{json, Map, Value};
+
+use crate::fault::Fault;
+
+/// aid の基準時刻 (2000-01-01T00:00:00Z) のミリ秒表現
+const AID_EPOCH: i64 = 946_684_800_000;
+
+#[derive(Clone, Debug)]
+pub(crate) struct MockUser {
+    pub(crate) id: String,
+    pub(crate) username: String,
+    pub(crate) name: Option<String>,
+    pub(crate) is_bot: bool,
+    pub(crate) created_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct MockNote {
+    pub(crate) id: String,
+    pub(crate) created_at: DateTime<Utc>,
+    pub(crate) user_id: String,
+    pub(crate) text: Option<String>,
+    pub(crate) cw: Option<String>,
+    pub(crate) visibility: String,
+    pub(crate) visible_user_ids: Vec<String>,
+    pub(crate) local_only: bool,
+    pub(crate) reaction_acceptance: Option<String>,
+    pub(crate) reply_id: Option<String>,
+    pub(crate) renote_id: Option<String>,
+    pub(crate) channel_id: Option<String>,
+    pub(crate) file_ids: Vec<String>,
+    pub(crate) poll: Option<MockPoll>,
+    pub(crate) updated_at: Option<DateTime<Utc>>,
+    /// 編集した日時と編集前の本文
+    pub(crate) edit_history: Vec<(DateTime<Utc>, Option<String>)>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct MockPoll {
+    pub(crate) choices: Vec<String>,
+    pub(crate) multiple: bool,
+    pub(crate) expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct MockFavorite {
+    pub(crate) id: String,
+    pub(crate) created_at: DateTime<Utc>,
+    pub(crate) note_id: String,
+    pub(crate) user_id: String,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct MockFile {
+    pub(crate) id: String,
+    pub(crate) created_at: DateTime<Utc>,
+    pub(crate) user_id: String,
+    pub(crate) name: String,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct MockReaction {
+    pub(crate) id: String,
+    pub(crate) created_at: DateTime<Utc>,
+    pub(crate) note_id: String,
+    pub(crate) user_id: String,
+    pub(crate) reaction: String,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct MockNotification {
+    pub(crate) id: String,
+    pub(crate) created_at: DateTime<Utc>,
+    pub(crate) owner_id: String,
+    pub(crate) notification_type: String,
+    pub(crate) user_id: Option<String>,
+    pub(crate) note_id: Option<String>,
+    pub(crate) extra: Map<String, Value>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct MockToken {
+    pub(crate) user_id: String,
+    /// `None` のときはすべての権限を持つ。
+    pub(crate) permissions: Option<HashSet<String>>,
+}
+
+/// モックサーバーが受信したリクエスト
+#[derive(Clone, Debug)]
+pub struct RecordedRequest {
+    /// `/api` を除いたエンドポイント
+    pub endpoint: String,
+    /// リクエストの本文。JSON でない場合は `Value::Null`
+    pub body: Value,
+}
+
+/// `/api/meta` と NodeInfo が返すサーバーの情報
+#[derive(Debug)]
+pub(crate) struct MockMeta {
+    pub(crate) software: String,
+    pub(crate) version: String,
+    pub(crate) based_misskey_version: Option<String>,
+    /// `notes/translate` を使用できるか否か
+    pub(crate) translator_available: bool,
+    /// `NO_SUCH_ENDPOINT` を返し、`/api/endpoints` にも含めないエンドポイント
+    pub(crate) removed_endpoints: HashSet<String>,
+}
+
+impl Default for MockMeta {
+    fn default() -> Self {
+        Self {
+            software: "misskey".to_string(),
+            version: "2025.4.0".to_string(),
+            based_misskey_version: None,
+            translator_available: false,
+            removed_endpoints: HashSet::new(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct MockState {
+    pub(crate) users: BTreeMap<String, MockUser>,
+    pub(crate) notes: BTreeMap<String, MockNote>,
+    pub(crate) reactions: BTreeMap<String, MockReaction>,
+    pub(crate) files: BTreeMap<String, MockFile>,
+    pub(crate) favorites: BTreeMap<String, MockFavorite>,
+    /// スレッドをミュートした (ユーザー ID, スレッドの最初のノートの ID)
+    pub(crate) thread_mutings: HashSet<(String, String)>,
+    /// 投票の (ノート ID, ユーザー ID, 選択肢の位置)
+    pub(crate) poll_votes: BTreeSet<(String, String, usize)>,
+    pub(crate) notifications: BTreeMap<String, MockNotification>,
+    pub(crate) tokens: HashMap<String, MockToken>,
+    /// MiAuth のセッション ID と、承認済みであれば発行したトークン
+    pub(crate) miauth_sessions: HashMap<String, Option<String>>,
+    pub(crate) follows: HashSet<(String, String)>,
+    pub(crate) faults: VecDeque<(Option<String>, Fault)>,
+    pub(crate) requests: Vec<RecordedRequest>,
+    pub(crate) meta: MockMeta,
+    /// NodeInfo のリンクに使用するオーソリティ
+    pub(crate) authority: String,
+    /// 処理中のリクエストのトークンのユーザー。`isVoted` のような閲覧者ごとの値に使用する。
+    pub(crate) viewer: Option<String>,
+    last_time: i64,
+    counter: u32,
+}
+
+impl MockState {
+    /// 直前に生成した ID に含まれる時刻
+    pub(crate) fn last_id_date(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp_millis(self.last_time).unwrap_or_default()
+    }
+
+    /// aid 形式の ID を生成する。生成順に辞書順で並ぶ。
+    pub(crate) fn gen_id(&mut self) -> String {
+        let now = Utc::now().timestamp_millis().max(self.last_time);
+        if now == self.last_time {
+            self.counter += 1;
+        } else {
+            self.counter = 0;
+        }
+        self.last_time = now;
+        format!("{}{}", radix36(now - AID_EPOCH, 8), radix36(self.counter as i64 % 1296, 2))
+    }
+
+    pub(crate) fn gen_token(&mut self) -> String {
+        format!("mock{}", self.gen_id())
+    }
+
+    pub(crate) fn take_fault(&mut self, endpoint: &str) -> Option<Fault> {
+        let index = self.faults.iter().position(|(target, _)| target.as_deref().is_none_or(|a| a == endpoint))?;
+        self.faults.remove(index).map(|a| a.1)
+    }
+
+    pub(crate) fn find_user_by_name(&self, username: &str) -> Option<&MockUser> {
+        self.users.values().find(|a| a.username.eq_ignore_ascii_case(username))
+    }
+
+    /// リプライをたどった、スレッドの最初のノートの ID
+    pub(crate) fn thread_id<'a>(&'a self, note: &'a MockNote) -> &'a str {
+        match note.reply_id.as_ref().and_then(|a| self.notes.get(a)) {
+            Some(reply) => self.thread_id(reply),
+            None => note.reply_id.as_deref().unwrap_or(&note.id),
+        }
+    }
+
+    pub(crate) fn notify(&mut self, owner_id: &str, notification_type: &str, user_id: Option<&str>, note_id: Option<&str>, extra: Map<String, Value>) -> String {
+        let id = self.gen_id();
+        self.notifications.insert(id.clone(), MockNotification {
+            id: id.clone(),
+            created_at: self.last_id_date(),
+            owner_id: owner_id.to_string(),
+            notification_type: notification_type.to_string(),
+            user_id: user_id.map(str::to_string),
+            note_id: note_id.map(str::to_string),
+            extra,
+        });
+        id
+    }
+
+    pub(crate) fn pack_lite_user(&self, user: &MockUser) -> Value {
+        json!({
+            "id": user.id,
+            "name": user.name,
+            "username": user.username,
+            "host": null,
+            "avatarUrl": format!("https://misskey.test/identicon/{}", user.id),
+            "avatarBlurhash": null,
+            "avatarDecorations": [],
+            "isBot": user.is_bot,
+            "isCat": false,
+            "emojis": {},
+            "onlineStatus": "unknown",
+            "badgeRoles": [],
+        })
+    }
+
+    pub(crate) fn pack_detailed_user(&self, user: &MockUser) -> Value {
+        let mut packed = self.pack_lite_user(user);
+        let detail = json!({
+            "url": null,
+            "uri": null,
+            "movedTo": null,
+            "alsoKnownAs": null,
+            "createdAt": user.created_at,
+            "updatedAt": null,
+            "lastFetchedAt": null,
+            "bannerUrl": null,
+            "bannerBlurhash": null,
+            "isLocked": false,
+            "isSilenced": false,
+            "isSuspended": false,
+            "description": null,
+            "location": null,
+            "birthday": null,
+            "lang": null,
+            "fields": [],
+            "verifiedLinks": [],
+            "followersCount": self.follows.iter().filter(|a| a.1 == user.id).count(),
+            "followingCount": self.follows.iter().filter(|a| a.0 == user.id).count(),
+            "notesCount": self.notes.values().filter(|a| a.user_id == user.id).count(),
+            "pinnedNoteIds": [],
+            "pinnedNotes": [],
+            "pinnedPageId": null,
+            "pinnedPage": null,
+            "publicReactions": true,
+            "followingVisibility": "public",
+            "followersVisibility": "public",
+            "roles": [],
+            "memo": null,
+        });
+        if let (Some(packed), Value::Object(detail)) = (packed.as_object_mut(), detail) {
+            packed.extend(detail);
+        }
+        packed
+    }
+
+    pub(crate) fn pack_note(&self, note: &MockNote, depth: usize) -> Value {
+        let user = &self.users[&note.user_id];
+        let mut reactions = BTreeMap::<&str, usize>::new();
+        for reaction in self.reactions.values().filter(|a| a.note_id == note.id) {
+            *reactions.entry(&reaction.reaction).or_default() += 1;
+        }
+        let child = |id: &Option<String>| id.as_ref()
+            .and_then(|a| self.notes.get(a))
+            .filter(|_| depth > 0)
+            .map(|a| self.pack_note(a, depth - 1));
+        let mut packed = json!({
+            "id": note.id,
+            "createdAt": note.created_at,
+            "userId": note.user_id,
+            "user": self.pack_lite_user(user),
+            "text": note.text,
+            "cw": note.cw,
+            "visibility": note.visibility,
+            "localOnly": note.local_only,
+            "reactionAcceptance": note.reaction_acceptance,
+            "renoteCount": self.notes.values().filter(|a| a.renote_id.as_ref() == Some(&note.id)).count(),
+            "repliesCount": self.notes.values().filter(|a| a.reply_id.as_ref() == Some(&note.id)).count(),
+            "reactionCount": reactions.values().sum::<usize>(),
+            "reactions": reactions,
+            "reactionEmojis": {},
+            "emojis": {},
+            "fileIds": note.file_ids,
+            "files": note.file_ids.iter().filter_map(|a| self.files.get(a)).map(|a| self.pack_file(a)).collect::<Vec<_>>(),
+            "replyId": note.reply_id,
+            "renoteId": note.renote_id,
+            "channelId": note.channel_id,
+            "clippedCount": 0,
+        });
+        if let Some(updated_at) = note.updated_at {
+            packed["updatedAt"] = json!(updated_at);
+        }
+        // 編集の履歴は CherryPick のみが公開する。
+        if self.meta.software == "cherrypick" {
+            packed["updatedAtHistory"] = note.edit_history.iter().map(|a| json!(a.0)).collect();
+            packed["noteEditHistory"] = note.edit_history.iter().map(|a| json!(a.1.as_deref().unwrap_or_default())).collect();
+        }
+        if note.visibility == "specified" {
+            packed["visibleUserIds"] = json!(note.visible_user_ids);
+        }
+        if let Some(poll) = &note.poll {
+            packed["poll"] = json!({
+                "multiple": poll.multiple,
+                "expiresAt": poll.expires_at,
+                "choices": poll.choices.iter().enumerate().map(|(i, a)| {
+                    let votes = self.poll_votes.iter().filter(|b| b.0 == note.id && b.2 == i);
+                    json!({ "text": a, "votes": votes.clone().count(), "isVoted": votes.into_iter().any(|b| Some(&b.1) == self.viewer.as_ref()) })
+                }).collect::<Vec<_>>(),
+            });
+        }
+        if let Some(reply) = child(&note.reply_id) {
+            packed["reply"] = reply;
+        }
+        if let Some(renote) = child(&note.renote_id) {
+            packed["renote"] = renote;
+        }
+        packed
+    }
+
+    pub(crate) fn pack_file(&self, file: &MockFile) -> Value {
+        json!({
+            "id": file.id,
+            "createdAt": file.created_at,
+            "name": file.name,
+            "type": "image/png",
+            "md5": "d41d8cd98f00b204e9800998ecf8427e",
+            "size": 0,
+            "isSensitive": false,
+            "blurhash": null,
+            "properties": {},
+            "url": format!("https://misskey.test/files/{}", file.id),
+            "thumbnailUrl": null,
+            "comment": null,
+            "folderId": null,
+            "folder": null,
+            "userId": file.user_id,
+            "user": null,
+        })
+    }
+
+    pub(crate) fn pack_favorite(&self, favorite: &MockFavorite) -> Value {
+        json!({
+            "id": favorite.id,
+            "createdAt": favorite.created_at,
+            "noteId": favorite.note_id,
+            "note": self.pack_note(&self.notes[&favorite.note_id], 1),
+        })
+    }
+
+    pub(crate) fn pack_reaction(&self, reaction: &MockReaction) -> Value {
+        json!({
+            "id": reaction.id,
+            "createdAt": reaction.created_at,
+            "user": self.pack_lite_user(&self.users[&reaction.user_id]),
+            "type": reaction.reaction,
+        })
+    }
+
+    pub(crate) fn pack_notification(&self, notification: &MockNotification) -> Value {
+        let mut packed = json!({
+            "id": notification.id,
+            "createdAt": notification.created_at,
+            "type": notification.notification_type,
+        });
+        if let Some(user) = notification.user_id.as_ref().and_then(|a| self.users.get(a)) {
+            packed["userId"] = json!(user.id);
+            packed["user"] = self.pack_lite_user(user);
+        }
+        if let Some(note) = notification.note_id.as_ref().and_then(|a| self.notes.get(a)) {
+            packed["note"] = self.pack_note(note, 1);
+        }
+        if let Some(packed) = packed.as_object_mut() {
+            packed.extend(notification.extra.clone());
+        }
+        packed
+    }
+}
+
+fn radix36(mut value: i64, width: usize) -> String {
+    const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    let mut buff = vec![b'0'; width];
+    for i in (0..width).rev() {
+        buff[i] = DIGITS[(value % 36) as usize];
+        value /= 36;
+    }
+    String::from_utf8(buff).unwrap()
+}
@@ -0,0 +1,775 @@
+use std::{error::Error, net::TcpStream, time::{Duration, Instant}};
+
+use chrono::{TimeZone, Utc};
+use misskey_client::{id_gen::IdGenMethod, errors::{InvalidRequestReason, MisskeyConnectionError, ServerError, ServerErrorType, UnsupportedReason}, miauth::{MiAuthStatus, Permission}, requests::{charts::{CommonChartRequestBody, GetActiveUsersChart, GetFederationChart}, i::{favorites::GetFavorites, notifications::GetNotifications, GetSelfData}, notes::{CreateFavorite, CreateFavoriteError, CreateNote, CreateNoteError, CreateReaction, CreateReactionError, DeleteFavorite, DeleteFavoriteError, DeleteNote, DeleteNoteError, DeleteReaction, DeleteReactionError, GetConversation, GetFeaturedNotes, GetGlobalTimeline, GetNote, GetNoteChildren, GetNoteError, GetNoteReactions, GetNoteState, GetRenotes, GetReplies, GetHybridTimeline, GetHybridTimelineError, GetLocalTimeline, GetMentions, GetRecommendedPolls, MuteThread, GetTimeline, Poll, SearchNote, TranslateNote, TranslateNoteError, Unrenote, UnrenoteError, UnmuteThread, UpdateNote, UpdateNoteError, VotePoll, VotePollError}, raw::RawRequest, users::{GetLiteUserInfo, GetNotes, GetNotesError}}, common::{ChartSpan, NoteId, NoteVisibility, NotificationType, Reaction, ReactionAcceptance, ServerVersion}, responses::{notes::{CreatedNoteInfo, NoteInfo}, notifications::NotificationDetail}, server::Software, MisskeyHttpClient};
+use misskey_client_mock::{Fault, MockAccount, MockError, MockServer};
+use serde_json::json;
+
+fn client(server: &MockServer, account: Option<&MockAccount>) -> MisskeyHttpClient<TcpStream> {
+    let client = MisskeyHttpClient::new(server.connect().unwrap(), server.authority()).unwrap();
+    match account {
+        Some(account) => client.login(&account.token),
+        None => client,
+    }
+}
+
+fn server_error(error: MisskeyConnectionError) -> ServerError {
+    match error {
+        MisskeyConnectionError::ServerResponseError(e) => *e,
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
+
+fn server_error_code(error: MisskeyConnectionError) -> String {
+    server_error(error).code().to_string()
+}
+
+#[test]
+fn get_self_data() {
+    let server = MockServer::start().unwrap();
+    let alice = server.add_user("alice");
+    let mut client = client(&server, Some(&alice));
+    let me = client.request(&GetSelfData).unwrap().into_body().unwrap();
+    assert_eq!(me.id(), &alice.id);
+    assert_eq!(me.username(), "alice");
+    assert_eq!(server.requests()[0].body["i"], alice.token.as_str());
+}
+
+#[test]
+fn credential_required() {
+    let server = MockServer::start().unwrap();
+    let mut client = client(&server, None);
+    let error = client.request(&GetSelfData).unwrap_err();
+    assert!(matches!(error, MisskeyConnectionError::CredentialRequired { endpoint } if endpoint == "/i"));
+    assert!(server.requests().is_empty());
+    assert!(client.request(&GetLiteUserInfo::from_name("alice")).is_err_and(|a| server_error_code(a) == "NO_SUCH_USER"));
+}
+
+#[test]
+fn permission_denied() {
+    let server = MockServer::start().unwrap();
+    let alice = server.add_user("alice");
+    let token = server.issue_token(&alice.id, &["read:account"]);
+    let mut client = client(&server, None).login(token);
+    assert!(client.request(&GetSelfData).is_ok());
+    let error = client.request(&CreateNote::note("hello")).unwrap_err();
+    assert_eq!(server_error_code(error), "PERMISSION_DENIED");
+}
+
+#[test]
+fn local_permission_check() {
+    let server = MockServer::start().unwrap();
+    let alice = server.add_user("alice");
+    let token = server.issue_token(&alice.id, &["read:account"]);
+    let mut client = client(&server, None).login_with_permissions(token, [Permission::ReadAccount]);
+    assert!(client.request(&GetSelfData).is_ok());
+    let error = client.request(&CreateNote::note("hello")).unwrap_err();
+    assert!(matches!(&error, MisskeyConnectionError::PermissionDenied { missing, .. } if missing == &[Permission::WriteNotes]));
+    assert_eq!(error.to_string(), "/notes/create requires permissions that are not granted: write:notes");
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[test]
+fn create_search_and_delete_note() {
+    let server = MockServer::start().unwrap();
+    let alice = server.add_user("alice");
+    let mut client = client(&server, Some(&alice));
+    let note = client.request(&CreateNote::note("hello world").cw("greeting")).unwrap().into_body().unwrap().created_note();
+    assert_eq!(note.text().as_deref(), Some("hello world"));
+    assert_eq!(note.cw().as_deref(), Some("greeting"));
+    assert_eq!(note.user().username(), "alice");
+
+    let found = client.request(&SearchNote::new("world")).unwrap().into_body().unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id(), note.id());
+
+    let deleted = client.request(&DeleteNote::new(note.id())).unwrap();
+    assert_eq!(deleted.status(), 204);
+    assert!(deleted.into_body().is_none());
+    assert_eq!(server.notes_count(), 0);
+
+    let error = client.request(&DeleteNote::new(note.id())).unwrap_err();
+    assert_eq!(server_error_code(error), "NO_SUCH_NOTE");
+}
+
+#[test]
+fn raw_request() {
+    let server = MockServer::start().unwrap();
+    let alice = server.add_user("alice");
+    let mut client = client(&server, Some(&alice));
+    let me = client.request(&RawRequest::without_body("i").credential(true)).unwrap().into_body().unwrap();
+    assert_eq!(me["username"], "alice");
+    assert_eq!(server.requests()[0].endpoint, "i");
+    assert_eq!(server.requests()[0].body["i"], alice.token.as_str());
+
+    let created = client.request(&RawRequest::new("/api/notes/create", json!({ "text": "raw" })).response::<CreatedNoteInfo>()).unwrap().into_body().unwrap().created_note();
+    assert_eq!(created.text().as_deref(), Some("raw"));
+
+    let deleted = client.request(&RawRequest::new("/notes/delete", json!({ "noteId": created.id() })).can_be_empty(true)).unwrap();
+    assert_eq!(deleted.status(), 204);
+    assert!(deleted.into_body().is_none());
+
+    let error = client.request(&RawRequest::new("/users/show", json!({ "username": "bob" }))).unwrap_err();
+    assert_eq!(server_error(error).error_type(), ServerErrorType::Other("NO_SUCH_USER".to_string()));
+
+    // オブジェクトにならない本文は送信前に拒否される
+    let before = server.requests().len();
+    let error = client.request(&RawRequest::new("/notes/show", json!(["a"]))).unwrap_err();
+    assert!(matches!(error, MisskeyConnectionError::InvalidRequest { reason: InvalidRequestReason::NotAnObject, .. }));
+    assert_eq!(server.requests().len(), before);
+
+    // Misskey のエラーでない本文でも、成功以外のステータスは成功として扱わない
+    server.inject(Fault::MalformedBody { status: 502, body: r#"{"message":"bad gateway"}"#.to_string() });
+    let error = client.request(&RawRequest::without_body("meta")).unwrap_err();
+    assert!(matches!(&error, MisskeyConnectionError::UnexpectedStatus { status, endpoint, raw_string } if *status == 502 && endpoint == "/meta" && raw_string.contains("bad gateway")));
+
+    let mut client = client.logout();
+    let error = client.request(&RawRequest::without_body("/i").credential(true)).unwrap_err();
+    assert!(matches!(error, MisskeyConnectionError::CredentialRequired { endpoint } if endpoint == "/i"));
+}
+
+#[test]
+fn no_created_note() {
+    let server = MockServer::start().unwrap();
+    let alice = server.add_user("alice");
+    let mut client = client(&server, Some(&alice));
+    let response = client.request(&CreateNote::note("silent").no_created_note(true)).unwrap();
+    assert_eq!(response.status(), 204);
+    assert!(response.into_body().is_none());
+    assert_eq!(server.notes_count(), 1);
+}
+
+#[test]
+fn show_user_and_notes() {
+    let server = MockServer::start().unwrap();
+    let alice = server.add_user("alice");
+    let mut client = client(&server, Some(&alice));
+    for i in 0..5 {
+        client.request(&CreateNote::note(format!("note {}", i))).unwrap();
+    }
+    let user = client.request(&GetLiteUserInfo::from_name("alice")).unwrap().into_body().unwrap();
+    assert_eq!(user.id(), &alice.id);
+
+    let notes = client.request(&GetNotes::new(&alice.id).limit(3)).unwrap().into_body().unwrap();
+    assert_eq!(notes.iter().map(|a| a.text().clone().unwrap()).collect::<Vec<_>>(), ["note 4", "note 3", "note 2"]);
+    let older = client.request(&GetNotes::new(&alice.id).until_id(notes[2].id().clone())).unwrap().into_body().unwrap();
+    assert_eq!(older.iter().map(|a| a.text().clone().unwrap()).collect::<Vec<_>>(), ["note 1", "note 0"]);
+
+    // 日時はミリ秒単位で送信される
+    let (since, until) = (Utc.timestamp_millis_opt(1_700_000_000_123).unwrap(), Utc.timestamp_millis_opt(1_800_000_000_456).unwrap());
+    client.request(&GetNotes::new(&alice.id).since_date(since).until_date(until)).unwrap();
+    let body = server.requests().pop().unwrap().body;
+    assert_eq!((&body["sinceDate"], &body["untilDate"]), (&json!(1_700_000_000_123_i64), &json!(1_800_000_000_456_i64)));
+
+    let error = client.request(&GetLiteUserInfo::from_name("bob")).unwrap_err();
+    assert_eq!(server_error_code(error), "NO_SUCH_USER");
+}
+
+#[test]
+fn reply_notification() {
+    let server = MockServer::start().unwrap();
+    let alice = server.add_user("alice");
+    let bob = server.add_user("bob");
+    let mut alice_client = client(&server, Some(&alice));
+    let mut bob_client = client(&server, Some(&bob));
+    let note = alice_client.request(&CreateNote::note("hi")).unwrap().into_body().unwrap().created_note();
+    bob_client.request(&CreateNote::note("hello").reply(note.id())).unwrap();
+    server.follow(&bob.id, &alice.id);
+
+    let notifications = alice_client.request(&GetNotifications::new()).unwrap().into_body().unwrap();
+    assert_eq!(notifications.len(), 2);
+    assert!(matches!(notifications[0].notification(), NotificationDetail::Follow { user_id, .. } if user_id == &bob.id));
+    assert!(matches!(notifications[1].notification(), NotificationDetail::Reply { note, .. } if note.text().as_deref() == Some("hello")));
+}
+
+#[test]
+fn typed_errors() {
+    let server = MockServer::start().unwrap();
+    let alice = server.add_user("alice");
+    let bob = server.add_user("bob");
+    let mut alice_client = client(&server, Some(&alice));
+    let mut bob_client = client(&server, Some(&bob));
+    let note = alice_client.request(&CreateNote::note("hi")).unwrap().into_body().unwrap().created_note();
+
+    let error = server_error(bob_client.request(&DeleteNote::new(note.id())).unwrap_err());
+    assert_eq!(error.error_type(), ServerErrorType::AccessDenied);
+    assert_eq!(error.error_type_for::<DeleteNote>(), ServerErrorType::Endpoint(DeleteNoteError::AccessDenied));
+
+    let error = server_error(alice_client.request(&DeleteNote::new("unknown")).unwrap_err());
+    assert_eq!(error.error_type_for::<DeleteNote>(), ServerErrorType::Endpoint(DeleteNoteError::NoSuchNote));
+    assert_eq!(error.error_type_for::<DeleteNote>().to_string(), "NO_SUCH_NOTE");
+
+    let error = server_error(alice_client.request(&GetNotes::new("unknown")).unwrap_err());
+    assert_eq!(error.error_type_for::<GetNotes>(), ServerErrorType::Endpoint(GetNotesError::NoSuchUser));
+
+    server.inject(Fault::rate_limit_exceeded());
+    let error = server_error(alice_client.request(&DeleteNote::new(note.id())).unwrap_err());
+    assert_eq!(error.error_type_for::<DeleteNote>(), ServerErrorType::RateLimitExceeded);
+
+    server.inject(MockError::credential_required());
+    let error = server_error(alice_client.request(&GetSelfData).unwrap_err());
+    assert_eq!(error.error_type(), ServerErrorType::CredentialRequired);
+}
+
+#[test]
+fn error_context() {
+    let server = MockServer::start().unwrap();
+    let alice = server.add_user("alice");
+    let mut client = client(&server, Some(&alice));
+    let error = client.request(&GetNotes::new(&alice.id).limit(1000)).unwrap_err();
+    assert!(error.source().is_some_and(|a| a.is::<ServerError>()));
+    assert!(error.to_string().contains("INVALID_PARAM"));
+
+    let error = server_error(error);
+    assert_eq!(error.error_type(), ServerErrorType::InvalidParam);
+    assert_eq!(error.status().map(|a| a.as_u16()), Some(400));
+    assert_eq!(error.endpoint().as_deref(), Some("/users/notes"));
+    assert_eq!(error.request_id().as_deref(), Some("1"));
+    let info = error.invalid_param_info().unwrap();
+    assert_eq!(info.param(), "/limit");
+    assert_eq!(info.reason(), "must be >= 1 and <= 100");
+    assert_eq!(error.to_string(), "INVALID_PARAM: Invalid param. (param: /limit, reason: must be >= 1 and <= 100) [endpoint: /users/notes, status: 400, request id: 1] (id: 3d81ceae-475f-4600-b2a8-2bc116157532)");
+
+    server.inject(Fault::malformed_body());
+    let error = client.request(&GetSelfData).unwrap_err();
+    assert!(error.source().is_some_and(|a| a.is::<serde_json::Error>()));
+}
+
+#[test]
+fn paginate_notes() {
+    let server = MockServer::start().unwrap();
+    let alice = server.add_user("alice");
+    let mut client = client(&server, Some(&alice));
+    for i in 0..25 {
+        client.request(&CreateNote::note(format!("note {}", i))).unwrap();
+    }
+    let texts = |notes: Vec<NoteInfo>| notes.into_iter().map(|a| a.text().clone().unwrap()).collect::<Vec<_>>();
+
+    let notes = client.paginate(GetNotes::new(&alice.id)).collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(texts(notes), (0..25).rev().map(|i| format!("note {}", i)).collect::<Vec<_>>());
+    assert_eq!(server.requests().len(), 25 + 4);
+
+    let notes = client.paginate(GetNotes::new(&alice.id).limit(1000)).max_items(12).collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(notes.len(), 12);
+    assert_eq!(server.requests().last().unwrap().body["limit"], 12);
+
+    let first = client.request(&GetNotes::new(&alice.id).limit(25)).unwrap().into_body().unwrap().pop().unwrap();
+    let bound = client.request(&GetNotes::new(&alice.id).limit(5)).unwrap().into_body().unwrap().pop().unwrap();
+    let notes = client.paginate(GetNotes::new(&alice.id).limit(7).until_id(bound.id().clone())).forward(first.id()).collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(texts(notes), (1..20).map(|i| format!("note {}", i)).collect::<Vec<_>>());
+
+    let notes = client.paginate(SearchNote::new("note 1").limit(3)).collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(notes.len(), 11);
+}
+
+#[test]
+fn paginate_notifications() {
+    let server = MockServer::start().unwrap();
+    let alice = server.add_user("alice");
+    let mut client = client(&server, Some(&alice));
+    for i in 0..15 {
+        let user = server.add_user(&format!("user{}", i));
+        server.follow(&user.id, &alice.id);
+    }
+    let notifications = client.paginate(GetNotifications::new().limit(4)).collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(notifications.len(), 15);
+    assert!(notifications.windows(2).all(|a| a[0].id() > a[1].id()));
+
+    server.inject_for("i/notifications", Fault::rate_limit_exceeded());
+    let mut paginator = client.paginate(GetNotifications::new());
+    assert!(paginator.next().unwrap().is_err());
+    assert!(paginator.next().is_none());
+}
+
+#[test]
+fn id_dates() {
+    let server = MockServer::start().unwrap();
+    let alice = server.add_user("alice");
+    let mut client = client(&server, Some(&alice));
+    let note = client.request(&CreateNote::note("hello")).unwrap().into_body().unwrap().created_note();
+    assert_eq!(note.id().created_at(), Some(*note.created_at()));
+    let until = NoteId::min_at(IdGenMethod::Aid, *note.created_at() + chrono::Duration::milliseconds(1));
+    let notes = client.request(&GetNotes::new(&alice.id).until_id(until)).unwrap().into_body().unwrap();
+    assert_eq!(notes.len(), 1);
+}
+
+#[test]
+fn charts() {
+    let server = MockServer::start().unwrap();
+    let mut client = client(&server, None);
+    let chart = client.request(&GetActiveUsersChart::new(CommonChartRequestBody::new(ChartSpan::Day).limit(7))).unwrap().into_body().unwrap();
+    assert_eq!(chart.read().len(), 7);
+    let chart = client.request(&GetFederationChart::new(CommonChartRequestBody::new(ChartSpan::Hour).limit(3))).unwrap().into_body().unwrap();
+    assert_eq!(chart.get_pub().len(), 3);
+}
+
+#[test]
+fn injected_rate_limit() {
+    let server = MockServer::start().unwrap();
+    let alice = server.add_user("alice");
+    let mut client = client(&server, Some(&alice));
+    server.inject_for("notes/create", Fault::rate_limit_exceeded());
+    assert!(client.request(&GetSelfData).is_ok());
+    let error = client.request(&CreateNote::note("hello")).unwrap_err();
+    assert_eq!(server_error_code(error), "RATE_LIMIT_EXCEEDED");
+    assert!(client.request(&CreateNote::note("hello")).is_ok());
+}
+
+#[test]
+fn injected_error() {
+    let server = MockServer::start().unwrap();
+    let alice = server.add_user("alice");
+    let mut client = client(&server, Some(&alice));
+    server.inject(MockError::internal_error());
+    let error = client.request(&GetSelfData).unwrap_err();
+    assert_eq!(server_error_code(error), "INTERNAL_ERROR");
+}
+
+#[test]
+fn injected_malformed_body() {
+    let server = MockServer::start().unwrap();
+    let alice = server.add_user("alice");
+    let mut client = client(&server, Some(&alice));
+    server.inject(Fault::malformed_body());
+    let error = client.request(&CreateNote::note("hello")).unwrap_err();
+    assert!(matches!(error, MisskeyConnectionError::SerdeError { .. }));
+    assert!(client.request(&GetSelfData).is_ok());
+}
+
+#[test]
+fn injected_delay() {
+    let server = MockServer::start().unwrap();
+    let alice = server.add_user("alice");
+    let mut client = client(&server, Some(&alice));
+    server.inject(Fault::Delay(Duration::from_millis(200)));
+    let start = Instant::now();
+    assert!(client.request(&GetSelfData).is_ok());
+    assert!(start.elapsed() >= Duration::from_millis(200));
+}
+
+#[test]
+fn miauth_flow() {
+    let server = MockServer::start().unwrap();
+    let alice = server.add_user("alice");
+    let miauth = client(&server, None).miauth("http").app_name("test".to_string()).requires(Permission::ReadAccount).build().unwrap();
+    let session = miauth.get_uri().path().trim_start_matches("/miauth/").to_string();
+
+    let MiAuthStatus::Pending(miauth) = miauth.check().unwrap() else {
+        panic!("session must be pending before approval");
+    };
+    server.approve_miauth(&session, &alice.id, &["read:account"]);
+    let MiAuthStatus::Succeed(mut client, user) = miauth.check().unwrap() else {
+        panic!("session must succeed after approval");
+    };
+    assert_eq!(user.username(), "alice");
+    assert_eq!(client.permissions().map(|a| a.len()), Some(1));
+    let me = client.request(&GetSelfData).unwrap().into_body().unwrap();
+    assert_eq!(me.id(), &alice.id);
+    let error = client.request(&CreateNote::note("hello")).unwrap_err();
+    assert!(matches!(error, MisskeyConnectionError::PermissionDenied { .. }));
+    assert!(server.requests().iter().any(|a| a.endpoint == format!("miauth/{}/check", session)));
+}
+
+#[test]
+fn request_builders() {
+    let server = MockServer::start().unwrap();
+    let alice = server.add_user("alice");
+    let mut client = client(&server, Some(&alice));
+    let note = client.request(&CreateNote::note("hello").cw("cw").local_only(true).no_extract_hashtags(true)).unwrap().into_body().unwrap().created_note();
+    client.request(&CreateNote::quote("quote", note.id())).unwrap();
+    client.request(&GetNotes::new(&alice.id).with_renotes(false).since_id(note.id())).unwrap();
+    client.request(&GetNotifications::new().include(NotificationType::Reply).include_all([NotificationType::Mention]).mark_as_read(false)).unwrap();
+
+    let requests = server.requests();
+    let body = &requests[0].body;
+    assert_eq!((&body["text"], &body["cw"], &body["localOnly"], &body["noExtractHashtags"]), (&json!("hello"), &json!("cw"), &json!(true), &json!(true)));
+    assert_eq!((&body["visibility"], &body["renoteId"], &body["noCreatedNote"]), (&json!("public"), &json!(null), &json!(false)));
+    assert_eq!((&requests[1].body["text"], &requests[1].body["renoteId"]), (&json!("quote"), &json!(note.id().as_str())));
+    let body = &requests[2].body;
+    assert_eq!((&body["userId"], &body["withRenotes"], &body["limit"], &body["sinceId"]), (&json!(alice.id.as_str()), &json!(false), &json!(10), &json!(note.id().as_str())));
+    let body = &requests[3].body;
+    let mut include = serde_json::from_value::<Vec<String>>(body["includeTypes"].clone()).unwrap();
+    include.sort();
+    assert_eq!((include, &body["markAsRead"], &body["limit"]), (vec!["mention".to_string(), "reply".to_string()], &json!(false), &json!(10)));
+}
+
+#[test]
+fn discover_server_info() {
+    let server = MockServer::start().unwrap();
+    let mut client = client(&server, None);
+    let info = client.discover().unwrap();
+    assert_eq!(info.software(), &Software::Misskey);
+    assert_eq!(info.version(), &Some(ServerVersion::new(2025, 4, 0)));
+    assert_eq!(info.misskey_version(), info.version());
+    assert!(info.features().is_some_and(|a| *a.local_timeline() && *a.miauth()));
+    assert_eq!(info.has_endpoint("notes/create"), Some(true));
+    assert_eq!(info.has_endpoint("/notes/update"), Some(false));
+    assert_eq!(info.nodeinfo().as_ref().map(|a| a.version().as_str()), Some("2.1"));
+    let endpoints = server.requests().into_iter().map(|a| a.endpoint).collect::<Vec<_>>();
+    assert_eq!(endpoints, ["meta", "endpoints", ".well-known/nodeinfo", "nodeinfo/2.1"]);
+    assert_eq!(server.requests()[0].body["detail"], true);
+
+    server.set_software("cherrypick", "4.14.0");
+    server.set_based_misskey_version("2024.11.0");
+    let info = client.discover().unwrap();
+    assert_eq!(info.software(), &Software::CherryPick);
+    assert_eq!(info.version(), &Some(ServerVersion::new(4, 14, 0)));
+    assert_eq!(info.misskey_version(), &Some(ServerVersion::new(2024, 11, 0)));
+    assert_eq!(info.at_least(&ServerVersion::new(2025, 1, 0)), Some(false));
+
+    // NodeInfo を取得できなくても `/api/meta` があれば続行する
+    server.inject_for(".well-known/nodeinfo", MockError::no_such_endpoint());
+    let info = client.discover().unwrap();
+    assert!(info.nodeinfo().is_none());
+    assert_eq!(info.software(), &Software::Misskey);
+}
+
+#[test]
+fn unsupported_on_server() {
+    let server = MockServer::start().unwrap();
+    server.remove_endpoint("notes/search");
+    let mut client = client(&server, None);
+    let error = client.request(&SearchNote::new("hello")).unwrap_err();
+    assert_eq!(server_error(error).error_type(), ServerErrorType::NoSuchEndpoint);
+
+    client.discover().unwrap();
+    let sent = server.requests().len();
+    let error = client.request(&SearchNote::new("hello")).unwrap_err();
+    assert!(matches!(&error, MisskeyConnectionError::Unsupported { endpoint, reason: UnsupportedReason::Endpoint } if endpoint == "/notes/search"));
+    assert_eq!(error.to_string(), "/notes/search is not supported on this server: the server does not provide the endpoint");
+    let error = client.request(&RawRequest::without_body("meta").min_version(ServerVersion::new(2099, 1, 0))).unwrap_err();
+    let MisskeyConnectionError::Unsupported { reason: UnsupportedReason::Version { required, actual }, .. } = error else { panic!("unexpected error: {:?}", error) };
+    assert_eq!((required, actual), (ServerVersion::new(2099, 1, 0), ServerVersion::new(2025, 4, 0)));
+    assert_eq!(server.requests().len(), sent);
+    assert!(client.request(&RawRequest::without_body("meta").min_version(ServerVersion::new(2024, 2, 0))).is_ok());
+
+    // 取得済みの情報はログインし直しても引き継がれる
+    let alice = server.add_user("alice");
+    let client = client.login(&alice.token);
+    assert!(client.server_info().is_some());
+}
+
+#[test]
+fn timelines() {
+    let server = MockServer::start().unwrap();
+    let (alice, bob, carol) = (server.add_user("alice"), server.add_user("bob"), server.add_user("carol"));
+    server.follow(&alice.id, &bob.id);
+    let (mut alice_client, mut bob_client, mut carol_client) = (client(&server, Some(&alice)), client(&server, Some(&bob)), client(&server, Some(&carol)));
+    let bob_note = bob_client.request(&CreateNote::note("bob").visibility(NoteVisibility::Home)).unwrap().into_body().unwrap().created_note();
+    std::thread::sleep(Duration::from_millis(5));
+    let carol_note = carol_client.request(&CreateNote::note("carol")).unwrap().into_body().unwrap().created_note();
+    alice_client.request(&CreateNote::note("reply").reply(carol_note.id())).unwrap();
+    carol_client.request(&CreateNote::renote(carol_note.id())).unwrap();
+    let texts = |notes: Vec<NoteInfo>| notes.into_iter().map(|a| a.text().clone().unwrap_or_default()).collect::<Vec<_>>();
+
+    assert_eq!(texts(alice_client.request(&GetTimeline::new()).unwrap().into_body().unwrap()), ["reply", "bob"]);
+    assert_eq!(texts(alice_client.request(&GetLocalTimeline::new()).unwrap().into_body().unwrap()), ["", "carol"]);
+    assert_eq!(texts(alice_client.request(&GetLocalTimeline::new().with_replies(true).with_renotes(false)).unwrap().into_body().unwrap()), ["reply", "carol"]);
+    assert_eq!(texts(alice_client.request(&GetHybridTimeline::new().with_replies(true)).unwrap().into_body().unwrap()), ["", "reply", "carol", "bob"]);
+    assert_eq!(texts(alice_client.request(&GetGlobalTimeline::new().with_renotes(false)).unwrap().into_body().unwrap()), ["reply", "carol"]);
+    assert!(alice_client.request(&GetLocalTimeline::new().with_files(true)).unwrap().into_body().unwrap().is_empty());
+
+    let error = server_error(alice_client.request(&GetHybridTimeline::new().with_replies(true).with_files(true)).unwrap_err());
+    assert_eq!(error.error_type_for::<GetHybridTimeline>(), ServerErrorType::Endpoint(GetHybridTimelineError::BothWithRepliesAndWithFiles));
+    assert!(matches!(client(&server, None).request(&GetTimeline::new()), Err(MisskeyConnectionError::CredentialRequired { .. })));
+
+    let since = *bob_note.created_at();
+    let notes = alice_client.paginate(GetHybridTimeline::new().limit(1).since_date(since)).collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(texts(notes), ["", "carol"]);
+    let body = server.requests().pop().unwrap().body;
+    assert_eq!((&body["sinceDate"], &body["allowPartial"]), (&json!(since.timestamp_millis()), &json!(false)));
+}
+
+#[test]
+fn read_notes() {
+    let server = MockServer::start().unwrap();
+    let (alice, bob) = (server.add_user("alice"), server.add_user("bob"));
+    let (mut alice_client, mut bob_client) = (client(&server, Some(&alice)), client(&server, Some(&bob)));
+    let created = |client: &mut MisskeyHttpClient<TcpStream>, request: CreateNote| client.request(&request).unwrap().into_body().unwrap().created_note();
+    let root = created(&mut alice_client, CreateNote::note("root"));
+    let reply = created(&mut bob_client, CreateNote::note("reply").reply(root.id()));
+    let nested = created(&mut alice_client, CreateNote::note("nested").reply(reply.id()));
+    created(&mut bob_client, CreateNote::quote("quote", root.id()));
+    created(&mut bob_client, CreateNote::renote(root.id()));
+    let texts = |notes: Vec<NoteInfo>| notes.into_iter().map(|a| a.text().clone().unwrap_or_default()).collect::<Vec<_>>();
+
+    let note = alice_client.request(&GetNote::new(root.id())).unwrap().into_body().unwrap();
+    assert_eq!((note.replies_count(), note.renote_count()), (&1, &2));
+    let error = server_error(alice_client.request(&GetNote::new("missing")).unwrap_err());
+    assert_eq!(error.error_type_for::<GetNote>(), ServerErrorType::Endpoint(GetNoteError::NoSuchNote));
+
+    assert_eq!(texts(alice_client.request(&GetNoteChildren::new(root.id())).unwrap().into_body().unwrap()), ["quote", "reply"]);
+    assert_eq!(texts(alice_client.request(&GetReplies::new(root.id())).unwrap().into_body().unwrap()), ["reply"]);
+    assert_eq!(texts(alice_client.request(&GetRenotes::new(root.id())).unwrap().into_body().unwrap()), ["", "quote"]);
+    assert!(alice_client.request(&GetRenotes::new(root.id()).user_id(&alice.id)).unwrap().into_body().unwrap().is_empty());
+    assert_eq!(texts(alice_client.request(&GetConversation::new(nested.id())).unwrap().into_body().unwrap()), ["reply", "root"]);
+    assert_eq!(texts(alice_client.request(&GetConversation::new(nested.id()).offset(1)).unwrap().into_body().unwrap()), ["root"]);
+
+    server.react(&bob.id, root.id(), "👍");
+    server.react(&alice.id, root.id(), ":blobcat@.:");
+    let note = alice_client.request(&GetNote::new(root.id())).unwrap().into_body().unwrap();
+    assert_eq!((note.reaction_count(), note.reactions().get(&Reaction::from("👍"))), (&2, Some(&1)));
+    let reactions = alice_client.paginate(GetNoteReactions::new(root.id()).limit(1)).collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(reactions.iter().map(|a| a.user().username().as_str()).collect::<Vec<_>>(), ["alice", "bob"]);
+    let older = alice_client.request(&GetNoteReactions::new(root.id()).until_id(reactions[0].id())).unwrap().into_body().unwrap();
+    assert_eq!(older.iter().map(|a| a.id()).collect::<Vec<_>>(), [reactions[1].id()]);
+    let reactions = alice_client.request(&GetNoteReactions::new(root.id()).reaction("👍")).unwrap().into_body().unwrap();
+    assert_eq!((reactions.len(), reactions[0].reaction()), (1, &Reaction::from("👍")));
+    assert_eq!(server.requests().pop().unwrap().body["type"], "👍");
+
+    let state = alice_client.request(&GetNoteState::new(root.id())).unwrap().into_body().unwrap();
+    assert!(!state.is_favorited() && !state.is_muted_thread());
+}
+
+#[test]
+fn reactions() {
+    let server = MockServer::start().unwrap();
+    let (alice, bob) = (server.add_user("alice"), server.add_user("bob"));
+    let (mut alice_client, mut bob_client) = (client(&server, Some(&alice)), client(&server, Some(&bob)));
+    let note = alice_client.request(&CreateNote::note("hello")).unwrap().into_body().unwrap().created_note();
+    let like_only = alice_client.request(&CreateNote::note("like only").reaction_acceptance(ReactionAcceptance::LikeOnly)).unwrap().into_body().unwrap().created_note();
+
+    bob_client.request(&CreateReaction::new(note.id(), Reaction::local("blobcat"))).unwrap();
+    assert_eq!(server.requests().pop().unwrap().body["reaction"], ":blobcat@.:");
+    let error = server_error(bob_client.request(&CreateReaction::new(note.id(), "👍")).unwrap_err());
+    assert_eq!(error.error_type_for::<CreateReaction>(), ServerErrorType::Endpoint(CreateReactionError::AlreadyReacted));
+    alice_client.request(&CreateReaction::new(note.id(), "👍")).unwrap();
+    let note = alice_client.request(&GetNote::new(note.id())).unwrap().into_body().unwrap();
+    assert_eq!(note.reactions().keys().cloned().collect::<Vec<_>>(), [Reaction::Unicode("👍".to_string()), Reaction::local("blobcat")]);
+
+    let notifications = alice_client.request(&GetNotifications::new()).unwrap().into_body().unwrap();
+    assert!(matches!(notifications[0].notification(), NotificationDetail::Reaction { reaction, .. } if reaction == &Reaction::local("blobcat")));
+
+    assert!(!like_only.accepts_reaction(&Reaction::from("👍")) && like_only.accepts_reaction(&Reaction::like()));
+    assert!(!note.accepts_reaction(&Reaction::Remote { name: "blobcat".to_string(), host: "misskey.io".to_string() }));
+    bob_client.request(&CreateReaction::new(like_only.id(), "👍")).unwrap();
+    let reactions = bob_client.request(&GetNoteReactions::new(like_only.id())).unwrap().into_body().unwrap();
+    assert!(reactions[0].reaction().is_like());
+
+    bob_client.request(&DeleteReaction::new(note.id())).unwrap();
+    let error = server_error(bob_client.request(&DeleteReaction::new(note.id())).unwrap_err());
+    assert_eq!(error.error_type_for::<DeleteReaction>(), ServerErrorType::Endpoint(DeleteReactionError::NotReacted));
+    assert!(matches!(client(&server, None).request(&DeleteReaction::new(note.id())), Err(MisskeyConnectionError::CredentialRequired { .. })));
+}
+
+#[test]
+fn attachments_and_polls() {
+    let server = MockServer::start().unwrap();
+    let (alice, bob) = (server.add_user("alice"), server.add_user("bob"));
+    let mut client = client(&server, Some(&alice));
+    let files = [server.upload_file(&alice.id, "a.png"), server.upload_file(&alice.id, "b.png")];
+
+    let note = client.request(&CreateNote::with_files(&files)).unwrap().into_body().unwrap().created_note();
+    assert_eq!(note.file_ids().iter().map(|a| a.as_str()).collect::<Vec<_>>(), files);
+    assert_eq!(note.files().iter().map(|a| a.name().as_str()).collect::<Vec<_>>(), ["a.png", "b.png"]);
+    assert_eq!((note.text(), &server.requests().pop().unwrap().body["text"]), (&None, &json!(null)));
+    assert_eq!(client.request(&GetLocalTimeline::new().with_files(true)).unwrap().into_body().unwrap().len(), 1);
+    let error = server_error(client.request(&CreateNote::note("stolen").file(server.upload_file(&bob.id, "c.png"))).unwrap_err());
+    assert_eq!(error.error_type_for::<CreateNote>(), ServerErrorType::Endpoint(CreateNoteError::NoSuchFile));
+
+    let poll = Poll::new(["yes", "no"]).multiple(true).expired_after(chrono::Duration::hours(1));
+    let note = client.request(&CreateNote::note("vote").poll(poll)).unwrap().into_body().unwrap().created_note();
+    let poll = note.poll().as_ref().unwrap();
+    assert!(*poll.can_choose_multiple() && poll.expires_at().is_some_and(|a| a > Utc::now()));
+    assert_eq!(poll.choices().iter().map(|a| a.text().as_str()).collect::<Vec<_>>(), ["yes", "no"]);
+    assert_eq!(server.requests().pop().unwrap().body["poll"], json!({ "choices": ["yes", "no"], "multiple": true, "expiredAfter": 3_600_000 }));
+    assert!(client.request(&CreateNote::with_poll(Poll::new(["a", "b"]))).unwrap().into_body().unwrap().created_note().poll().is_some());
+
+    let scheduled_at = Utc::now() + chrono::Duration::days(1);
+    client.request(&CreateNote::note("later").scheduled_at(scheduled_at)).unwrap();
+    assert_eq!(server.requests().pop().unwrap().body["scheduledAt"], scheduled_at.timestamp_millis());
+
+    let sent = server.requests().len();
+    let invalid = |client: &mut MisskeyHttpClient<TcpStream>, request: CreateNote| match client.request(&request).unwrap_err() {
+        MisskeyConnectionError::InvalidRequest { endpoint, reason } if endpoint == "/notes/create" => reason,
+        e => panic!("unexpected error: {:?}", e),
+    };
+    assert!(matches!(invalid(&mut client, CreateNote::with_files(Vec::<String>::new())), InvalidRequestReason::MissingAnyOf(_)));
+    assert_eq!(invalid(&mut client, CreateNote::note("")), InvalidRequestReason::Empty { param: "text" });
+    assert_eq!(invalid(&mut client, CreateNote::note("a").cw("a".repeat(101))), InvalidRequestReason::TooLong { param: "cw", max: 100, actual: 101 });
+    assert_eq!(invalid(&mut client, CreateNote::with_files((0..17).map(|a| a.to_string()))), InvalidRequestReason::ItemCount { param: "fileIds", min: 1, max: 16, actual: 17 });
+    assert_eq!(invalid(&mut client, CreateNote::with_files([&files[0], &files[0]])), InvalidRequestReason::DuplicatedItems { param: "fileIds" });
+    assert_eq!(invalid(&mut client, CreateNote::with_poll(Poll::new(["only"]))), InvalidRequestReason::ItemCount { param: "poll.choices", min: 2, max: 10, actual: 1 });
+    assert_eq!(invalid(&mut client, CreateNote::with_poll(Poll::new(["a", ""]))), InvalidRequestReason::Empty { param: "poll.choices" });
+    assert_eq!(invalid(&mut client, CreateNote::with_poll(Poll::new(["a", "b"]).expires_at(Utc::now()))), InvalidRequestReason::NotInFuture { param: "poll.expiresAt" });
+    assert_eq!(invalid(&mut client, CreateNote::note("a").scheduled_at(Utc::now())), InvalidRequestReason::NotInFuture { param: "scheduledAt" });
+    assert_eq!(server.requests().len(), sent);
+}
+
+#[test]
+fn polls() {
+    let server = MockServer::start().unwrap();
+    let (alice, bob) = (server.add_user("alice"), server.add_user("bob"));
+    let (mut alice_client, mut bob_client) = (client(&server, Some(&alice)), client(&server, Some(&bob)));
+    let created = |client: &mut MisskeyHttpClient<TcpStream>, request: CreateNote| client.request(&request).unwrap().into_body().unwrap().created_note();
+    let single = created(&mut alice_client, CreateNote::with_poll(Poll::new(["red", "green", "blue"])));
+    let multiple = created(&mut alice_client, CreateNote::with_poll(Poll::new(["a", "b"]).multiple(true)));
+    let plain = created(&mut alice_client, CreateNote::note("no poll"));
+    assert_eq!((single.poll_choice_index("green"), single.poll_choice_index("black"), plain.poll_choice_index("green")), (Some(1), None, None));
+
+    let recommended = bob_client.request(&GetRecommendedPolls::new()).unwrap().into_body().unwrap();
+    assert_eq!(recommended.iter().map(|a| a.id()).collect::<Vec<_>>(), [multiple.id(), single.id()]);
+    assert!(alice_client.request(&GetRecommendedPolls::new()).unwrap().into_body().unwrap().is_empty());
+
+    let invalid = |client: &mut MisskeyHttpClient<TcpStream>, request: VotePoll| match client.request(&request).unwrap_err() {
+        MisskeyConnectionError::InvalidRequest { endpoint, reason } if endpoint == "/notes/polls/vote" => reason,
+        e => panic!("unexpected error: {:?}", e),
+    };
+    bob_client.request(&VotePoll::for_choice(&single, "green").unwrap()).unwrap();
+    assert_eq!(server.requests().pop().unwrap().body["choice"], 1);
+    let single = bob_client.request(&GetNote::new(single.id())).unwrap().into_body().unwrap();
+    let poll = single.poll().as_ref().unwrap();
+    assert!(poll.has_voted() && *poll.choices()[1].is_voted() && poll.total_votes() == 1);
+    assert!(!alice_client.request(&GetNote::new(single.id())).unwrap().into_body().unwrap().poll().as_ref().unwrap().has_voted());
+    assert_eq!(invalid(&mut bob_client, VotePoll::for_note(&single, 0)), InvalidRequestReason::AlreadyVoted);
+    let error = server_error(bob_client.request(&VotePoll::new(single.id(), 0)).unwrap_err());
+    assert_eq!(error.error_type_for::<VotePoll>(), ServerErrorType::Endpoint(VotePollError::AlreadyVoted));
+
+    bob_client.request(&VotePoll::for_note(&multiple, 0)).unwrap();
+    let multiple = bob_client.request(&GetNote::new(multiple.id())).unwrap().into_body().unwrap();
+    assert_eq!(invalid(&mut bob_client, VotePoll::for_note(&multiple, 0)), InvalidRequestReason::AlreadyVoted);
+    bob_client.request(&VotePoll::for_note(&multiple, 1)).unwrap();
+    assert!(bob_client.request(&GetRecommendedPolls::new()).unwrap().into_body().unwrap().is_empty());
+
+    assert_eq!(invalid(&mut bob_client, VotePoll::for_note(&multiple, 2)), InvalidRequestReason::OutOfRange { param: "choice", max: 1, actual: 2 });
+    assert!(VotePoll::for_choice(&multiple, "c").is_none() && VotePoll::for_choice(&plain, "a").is_none());
+    assert_eq!(invalid(&mut bob_client, VotePoll::for_note(&plain, 0)), InvalidRequestReason::NoPoll);
+    let error = server_error(bob_client.request(&VotePoll::new(plain.id(), 0)).unwrap_err());
+    assert_eq!(error.error_type_for::<VotePoll>(), ServerErrorType::Endpoint(VotePollError::NoPoll));
+
+    let expiring = created(&mut alice_client, CreateNote::with_poll(Poll::new(["a", "b"]).expired_after(chrono::Duration::milliseconds(20))));
+    std::thread::sleep(Duration::from_millis(30));
+    assert!(expiring.poll().as_ref().unwrap().is_expired());
+    assert_eq!(invalid(&mut bob_client, VotePoll::for_note(&expiring, 0)), InvalidRequestReason::PollExpired);
+    let error = server_error(bob_client.request(&VotePoll::new(expiring.id(), 0)).unwrap_err());
+    assert_eq!(error.error_type_for::<VotePoll>(), ServerErrorType::Endpoint(VotePollError::AlreadyExpired));
+}
+
+#[test]
+fn edit_and_unrenote() {
+    let server = MockServer::start().unwrap();
+    let (alice, bob) = (server.add_user("alice"), server.add_user("bob"));
+    let (mut alice_client, mut bob_client) = (client(&server, Some(&alice)), client(&server, Some(&bob)));
+    let note = alice_client.request(&CreateNote::note("before")).unwrap().into_body().unwrap().created_note();
+    assert!(!note.is_edited() && note.updated_at().is_none());
+
+    // Misskey 本体には編集のエンドポイントがないため、送信前に拒否する
+    alice_client.discover().unwrap();
+    let error = alice_client.request(&UpdateNote::new(note.id()).text("after")).unwrap_err();
+    assert!(matches!(error, MisskeyConnectionError::Unsupported { reason: UnsupportedReason::Endpoint, .. }));
+
+    server.set_software("cherrypick", "4.11.0");
+    server.set_based_misskey_version("2024.3.1");
+    alice_client.discover().unwrap();
+    alice_client.request(&UpdateNote::new(note.id()).text("after").cw("spoiler")).unwrap();
+    assert_eq!(server.requests().pop().unwrap().body, json!({ "i": alice.token, "noteId": note.id(), "text": "after", "cw": "spoiler" }));
+    // 編集の履歴は CherryPick のみが公開する
+    let edited = alice_client.request(&GetNote::new(note.id())).unwrap().into_body().unwrap();
+    assert_eq!((edited.text().as_deref(), edited.cw().as_deref()), (Some("after"), Some("spoiler")));
+    assert!(edited.is_edited());
+    assert_eq!(edited.edit_history().map(|a| (*a.0, a.1)).collect::<Vec<_>>(), [(edited.updated_at().unwrap(), "before")]);
+    let error = server_error(bob_client.request(&UpdateNote::new(note.id()).text("hijacked")).unwrap_err());
+    assert_eq!(error.error_type_for::<UpdateNote>(), ServerErrorType::Endpoint(UpdateNoteError::NoSuchNote));
+    let error = alice_client.request(&UpdateNote::new(note.id())).unwrap_err();
+    assert!(matches!(error, MisskeyConnectionError::InvalidRequest { reason: InvalidRequestReason::MissingAnyOf(_), .. }));
+
+    bob_client.request(&CreateNote::renote(note.id())).unwrap();
+    bob_client.request(&CreateNote::renote(note.id())).unwrap();
+    bob_client.request(&CreateNote::quote("quote", note.id())).unwrap();
+    bob_client.request(&Unrenote::new(note.id())).unwrap();
+    let renotes = bob_client.request(&GetRenotes::new(note.id())).unwrap().into_body().unwrap();
+    assert_eq!(renotes.iter().map(|a| a.text().as_deref()).collect::<Vec<_>>(), [Some("quote")]);
+    let error = server_error(bob_client.request(&Unrenote::new("missing")).unwrap_err());
+    assert_eq!(error.error_type_for::<Unrenote>(), ServerErrorType::Endpoint(UnrenoteError::NoSuchNote));
+
+    // 基にした Misskey のバージョンではなく、エンドポイントの有無で判断する
+    server.set_based_misskey_version("2023.10.0");
+    alice_client.discover().unwrap();
+    alice_client.request(&UpdateNote::new(note.id()).text("again")).unwrap();
+    server.remove_endpoint("notes/update");
+    alice_client.discover().unwrap();
+    let error = alice_client.request(&UpdateNote::new(note.id()).text("again")).unwrap_err();
+    assert!(matches!(error, MisskeyConnectionError::Unsupported { reason: UnsupportedReason::Endpoint, .. }));
+    assert!(alice_client.request(&Unrenote::new(note.id())).is_ok());
+}
+
+#[test]
+fn favorites_and_thread_muting() {
+    let server = MockServer::start().unwrap();
+    let (alice, bob) = (server.add_user("alice"), server.add_user("bob"));
+    let (mut alice_client, mut bob_client) = (client(&server, Some(&alice)), client(&server, Some(&bob)));
+    let created = |client: &mut MisskeyHttpClient<TcpStream>, request: CreateNote| client.request(&request).unwrap().into_body().unwrap().created_note();
+    let notes = (0..3).map(|i| created(&mut bob_client, CreateNote::note(format!("note {}", i)))).collect::<Vec<_>>();
+
+    for note in &notes {
+        alice_client.request(&CreateFavorite::new(note)).unwrap();
+    }
+    let error = server_error(alice_client.request(&CreateFavorite::new(&notes[0])).unwrap_err());
+    assert_eq!(error.error_type_for::<CreateFavorite>(), ServerErrorType::Endpoint(CreateFavoriteError::AlreadyFavorited));
+    let favorites = alice_client.paginate(GetFavorites::new().limit(2)).collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(favorites.iter().map(|a| a.note_id()).collect::<Vec<_>>(), [notes[2].id(), notes[1].id(), notes[0].id()]);
+    assert!(favorites.iter().all(|a| a.note().id() == a.note_id() && a.id() != a.note_id().as_str()));
+    assert_eq!(NoteInfo::from(favorites[0].clone()).text().as_deref(), Some("note 2"));
+    assert!(*alice_client.request(&GetNoteState::new(&notes[1])).unwrap().into_body().unwrap().is_favorited());
+    assert!(bob_client.request(&GetFavorites::new()).unwrap().into_body().unwrap().is_empty());
+
+    alice_client.request(&DeleteFavorite::new(&notes[1])).unwrap();
+    assert!(!*alice_client.request(&GetNoteState::new(&notes[1])).unwrap().into_body().unwrap().is_favorited());
+    let error = server_error(alice_client.request(&DeleteFavorite::new(&notes[1])).unwrap_err());
+    assert_eq!(error.error_type_for::<DeleteFavorite>(), ServerErrorType::Endpoint(DeleteFavoriteError::NotFavorited));
+    bob_client.request(&DeleteNote::new(&notes[2])).unwrap();
+    assert_eq!(alice_client.request(&GetFavorites::new()).unwrap().into_body().unwrap().len(), 1);
+
+    // ミュートしたスレッドのリプライは、途中のノートを指定しても通知されない
+    let root = created(&mut bob_client, CreateNote::note("thread"));
+    let reply = created(&mut alice_client, CreateNote::note("reply").reply(root.id()));
+    bob_client.request(&MuteThread::new(&reply)).unwrap();
+    assert!(*bob_client.request(&GetNoteState::new(&root)).unwrap().into_body().unwrap().is_muted_thread());
+    let replies = |client: &mut MisskeyHttpClient<TcpStream>| client.request(&GetNotifications::new().include(NotificationType::Reply)).unwrap().into_body().unwrap().len();
+    let before = replies(&mut bob_client);
+    created(&mut alice_client, CreateNote::note("muted").reply(root.id()));
+    assert_eq!(replies(&mut bob_client), before);
+    bob_client.request(&UnmuteThread::new(&root)).unwrap();
+    assert!(!*bob_client.request(&GetNoteState::new(&root)).unwrap().into_body().unwrap().is_muted_thread());
+    created(&mut alice_client, CreateNote::note("unmuted").reply(root.id()));
+    assert_eq!(replies(&mut bob_client), before + 1);
+    assert!(matches!(alice_client.request(&MuteThread::new("missing")), Err(MisskeyConnectionError::ServerResponseError(_))));
+}
+
+#[test]
+fn translate_featured_and_mentions() {
+    let server = MockServer::start().unwrap();
+    let (alice, bob, carol) = (server.add_user("alice"), server.add_user("bob"), server.add_user("carol"));
+    let (mut alice_client, mut bob_client, mut carol_client) = (client(&server, Some(&alice)), client(&server, Some(&bob)), client(&server, Some(&carol)));
+    let created = |client: &mut MisskeyHttpClient<TcpStream>, request: CreateNote| client.request(&request).unwrap().into_body().unwrap().created_note();
+
+    let note = created(&mut bob_client, CreateNote::note("hello"));
+    let error = server_error(alice_client.request(&TranslateNote::new(note.id(), "ja-JP")).unwrap_err());
+    assert_eq!(error.error_type_for::<TranslateNote>(), ServerErrorType::Endpoint(TranslateNoteError::Unavailable));
+    server.enable_translator();
+    let translation = alice_client.request(&TranslateNote::new(note.id(), "ja-JP")).unwrap().into_body().unwrap();
+    assert_eq!((translation.source_lang().as_str(), translation.text().as_str()), ("en", "[ja-JP] hello"));
+    let followers_only = created(&mut bob_client, CreateNote::note("secret").visibility(NoteVisibility::Followers));
+    let error = server_error(alice_client.request(&TranslateNote::new(followers_only.id(), "ja-JP")).unwrap_err());
+    assert_eq!(error.error_type_for::<TranslateNote>(), ServerErrorType::Endpoint(TranslateNoteError::CannotTranslateInvisibleNote));
+    let error = server_error(alice_client.request(&TranslateNote::new("missing", "ja-JP")).unwrap_err());
+    assert_eq!(error.error_type_for::<TranslateNote>(), ServerErrorType::Endpoint(TranslateNoteError::NoSuchNote));
+
+    // リアクションのある公開ノートのみが話題になる
+    let quiet = created(&mut bob_client, CreateNote::note("quiet"));
+    alice_client.request(&CreateReaction::new(note.id(), "👍")).unwrap();
+    let featured = client(&server, None).request(&GetFeaturedNotes::new()).unwrap().into_body().unwrap();
+    assert_eq!(featured.iter().map(|a| a.id()).collect::<Vec<_>>(), [note.id()]);
+    assert!(featured.iter().all(|a| a.id() != quiet.id()));
+
+    let mention = created(&mut bob_client, CreateNote::note("hi @alice"));
+    let reply = created(&mut carol_client, CreateNote::note("reply").reply(created(&mut alice_client, CreateNote::note("root")).id()));
+    created(&mut carol_client, CreateNote::note("hi @alicia"));
+    let mentions = alice_client.paginate(GetMentions::new().limit(1)).collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(mentions.iter().map(|a| a.id()).collect::<Vec<_>>(), [reply.id(), mention.id()]);
+    server.follow(&alice.id, &bob.id);
+    let following = alice_client.request(&GetMentions::new().following(true)).unwrap().into_body().unwrap();
+    assert_eq!(following.iter().map(|a| a.id()).collect::<Vec<_>>(), [mention.id()]);
+    let direct = alice_client.request(&GetMentions::new().visibility(NoteVisibility::Specified)).unwrap().into_body().unwrap();
+    assert!(direct.is_empty());
+}
@@ -0,0 +1,255 @@
+use chrono::{TimeZone, Utc};
+use misskey_client::{common::{NoteVisibility, NotificationType, ReactionAcceptance, ServerVersion}, responses::{announcements::AnnouncementDisplay, meta::MetaInfo, nodeinfo::NodeInfo, notes::NoteInfo, notifications::{ExportedEntity, NotificationDetail, NotificationInfo, RoleCondFormula, RoleCondition}, pages::PageBlock, users::{Birthday, DetailedUserInfo, LiteUserInfo, NotificationReceiveConfig, OnlineStatus}}, schema::{from_str_strict, SchemaDrift, StrictError}};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+
+const USER_LITE: &str = include_str!("fixtures/user_lite.json");
+const NOTE: &str = include_str!("fixtures/note.json");
+const NOTIFICATIONS: &str = include_str!("fixtures/notifications.json");
+const NOTIFICATIONS_ALL: &str = include_str!("fixtures/notifications_all.json");
+const USER_DETAILED: &str = include_str!("fixtures/user_detailed.json");
+const ROLE_COND_FORMULA: &str = include_str!("fixtures/role_cond_formula.json");
+const META: &str = include_str!("fixtures/meta.json");
+const NODEINFO: &str = include_str!("fixtures/nodeinfo.json");
+
+/// `left` と `right` で値が異なる位置を列挙する。
+fn differences(left: &Value, right: &Value, path: &str, found: &mut Vec<String>) {
+    match (left, right) {
+        (Value::Object(left), Value::Object(right)) => {
+            for key in left.keys().chain(right.keys().filter(|a| !left.contains_key(*a))) {
+                let path = format!("{}/{}", path, key);
+                match (left.get(key), right.get(key)) {
+                    (Some(left), Some(right)) => differences(left, right, &path, found),
+                    (left, right) => found.push(format!("{}: {:?} != {:?}", path, left, right)),
+                }
+            }
+        },
+        (Value::Array(left), Value::Array(right)) if left.len() == right.len() => {
+            for (i, (left, right)) in left.iter().zip(right).enumerate() {
+                differences(left, right, &format!("{}/{}", path, i), found);
+            }
+        },
+        (left, right) if left != right => found.push(format!("{}: {} != {}", path, left, right)),
+        _ => {},
+    }
+}
+
+/// デシリアライズとシリアライズを繰り返しても同じ JSON になることを確認する。
+fn assert_round_trip<T>(fixture: &str) where T: DeserializeOwned + Serialize + Clone {
+    let original = serde_json::from_str::<Value>(fixture).unwrap();
+    let parsed = serde_json::from_value::<T>(original.clone()).unwrap();
+    let serialized = serde_json::to_value(parsed.clone()).unwrap();
+    let mut found = Vec::new();
+    differences(&serialized, &original, "", &mut found);
+    assert!(found.is_empty(), "{:#?}", found);
+    let reparsed = serde_json::from_value::<T>(serialized.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&reparsed).unwrap(), serialized);
+}
+
+#[test]
+fn round_trip() {
+    assert_round_trip::<LiteUserInfo>(USER_LITE);
+    assert_round_trip::<DetailedUserInfo>(USER_DETAILED);
+    assert_round_trip::<NoteInfo>(NOTE);
+    assert_round_trip::<Vec<NotificationInfo>>(NOTIFICATIONS);
+    assert_round_trip::<Vec<NotificationInfo>>(NOTIFICATIONS_ALL);
+    assert_round_trip::<RoleCondFormula>(ROLE_COND_FORMULA);
+    assert_round_trip::<MetaInfo>(META);
+    assert_round_trip::<NodeInfo>(NODEINFO);
+}
+
+#[test]
+fn unknown_online_status() {
+    let user = serde_json::from_str::<LiteUserInfo>(USER_LITE).unwrap();
+    assert_eq!(user.online_status(), &OnlineStatus::Other("away".to_string()));
+    assert_eq!(user.online_status().to_string(), "away");
+}
+
+#[test]
+fn unknown_note_visibility() {
+    let note = serde_json::from_str::<NoteInfo>(NOTE).unwrap();
+    assert_eq!(note.visibility(), &NoteVisibility::Other("circle".to_string()));
+    assert_eq!(note.reaction_acceptance(), &Some(ReactionAcceptance::NonSensitiveOnly));
+    assert_eq!(note.user().online_status(), &OnlineStatus::Online);
+}
+
+#[test]
+fn unknown_notification_type() {
+    let notifications = serde_json::from_str::<Vec<NotificationInfo>>(NOTIFICATIONS).unwrap();
+    assert_eq!(notifications.len(), 4);
+    assert!(matches!(notifications[0].notification(), NotificationDetail::Follow { user, .. } if user.online_status() == &OnlineStatus::Active));
+    assert!(matches!(notifications[1].notification(), NotificationDetail::AchievementEarned { achievement } if achievement == "notes1"));
+    match notifications[2].notification() {
+        NotificationDetail::Other { notification_type, body } => {
+            assert_eq!(notification_type, "somethingNew");
+            assert_eq!(body["payload"], json!({ "count": 3 }));
+        },
+        detail => panic!("unexpected notification: {:?}", detail),
+    }
+    assert!(matches!(notifications[3].notification(), NotificationDetail::Test));
+}
+
+#[test]
+fn notification_detail_types() {
+    let raw = serde_json::from_str::<Vec<serde_json::Value>>(NOTIFICATIONS_ALL).unwrap();
+    let notifications = serde_json::from_str::<Vec<NotificationInfo>>(NOTIFICATIONS_ALL).unwrap();
+    for (raw, notification) in raw.iter().zip(&notifications) {
+        let raw_type = raw["type"].as_str().unwrap();
+        let detail = notification.notification();
+        assert!(!matches!(detail, NotificationDetail::Other { .. }), "{} was not recognized", raw_type);
+        assert_eq!(detail.is_grouped(), raw_type.contains(':'));
+        assert_eq!(notification.notification_type().to_string(), raw_type.trim_end_matches(":grouped"));
+    }
+    let types = notifications.iter().filter(|a| !a.notification().is_grouped()).map(NotificationInfo::notification_type).collect::<Vec<_>>();
+    for notification_type in &types {
+        assert!(!matches!(notification_type, NotificationType::Other(_)));
+    }
+    assert_eq!(types.len(), 21);
+
+    assert!(notifications.iter().any(|a| matches!(a.notification(), NotificationDetail::ExportCompleted { exported_entity: ExportedEntity::Note, file_id } if file_id == "9srd6h0000")));
+    assert!(notifications.iter().any(|a| matches!(a.notification(), NotificationDetail::PollVote { choice: 1, .. })));
+    assert!(notifications.iter().any(|a| matches!(a.notification(), NotificationDetail::FollowRequestRejected { message: Some(message), .. } if message == "sorry")));
+    assert!(notifications.iter().any(|a| matches!(a.notification(), NotificationDetail::ChatRoomInvitationReceived { invitation } if invitation.invitee_id() == "9srd69w5a2")));
+    assert!(notifications.iter().any(|a| matches!(a.notification(), NotificationDetail::App { header: None, .. })));
+}
+
+#[test]
+fn malformed_known_notification() {
+    let mut notifications = serde_json::from_str::<Vec<serde_json::Value>>(NOTIFICATIONS).unwrap();
+    notifications[0].as_object_mut().unwrap().remove("user");
+    assert!(serde_json::from_value::<NotificationInfo>(notifications.swap_remove(0)).is_err());
+}
+
+#[test]
+fn detailed_user() {
+    let user = serde_json::from_str::<DetailedUserInfo>(USER_DETAILED).unwrap();
+    assert_eq!(user.birthday(), &Some(Birthday { year: 2000, month: 2, day: 29 }));
+    assert_eq!(user.also_known_as().as_deref(), Some(&["https://old.example/users/alice".to_string()][..]));
+    assert_eq!(user.moved_to().as_deref(), Some("https://old.example/users/alice"));
+    assert_eq!(user.last_fetched_at(), &Some(Utc.with_ymd_and_hms(2024, 5, 2, 0, 0, 0).unwrap()));
+    assert_eq!(user.verified_links(), &["https://example.com"]);
+    assert_eq!(user.email_notification_types().as_deref(), Some(&[NotificationType::Follow, NotificationType::Mention][..]));
+    assert_eq!(user.muting_notification_types().as_deref(), Some(&[NotificationType::App][..]));
+    assert!(user.emojis().is_empty());
+
+    let config = user.notification_receive_config().as_ref().unwrap();
+    assert!(matches!(config[&NotificationType::Follow], NotificationReceiveConfig::MutualFollow));
+    assert!(matches!(&config[&NotificationType::Mention], NotificationReceiveConfig::List { user_list_id } if user_list_id == "9srd6j0000"));
+    assert!(matches!(config[&NotificationType::Other("newKind".to_string())], NotificationReceiveConfig::Other(_)));
+
+    let page = user.pinned_page().as_ref().unwrap();
+    assert_eq!(page.id(), user.pinned_page_id().as_ref().unwrap());
+    assert!(matches!(&page.content()[0], PageBlock::Text { text, .. } if text == "hello"));
+    match &page.content()[1] {
+        PageBlock::Section { children, .. } => {
+            assert!(matches!(&children[0], PageBlock::Note { detailed: true, note: Some(note), .. } if note == "9srd6a0b00"));
+            assert!(matches!(&children[1], PageBlock::Image { file_id: None, .. }));
+        },
+        block => panic!("unexpected block: {:?}", block),
+    }
+    assert!(matches!(&page.content()[2], PageBlock::Other(block) if block["type"] == "canvas"));
+
+    let announcement = &user.unread_announcements().as_ref().unwrap()[0];
+    assert_eq!(announcement.display(), &AnnouncementDisplay::Banner);
+    assert!(announcement.need_confirmation_to_read());
+}
+
+#[test]
+fn meta_and_nodeinfo() {
+    let meta = serde_json::from_str::<MetaInfo>(META).unwrap();
+    assert!(meta.version().parse::<ServerVersion>().unwrap() < ServerVersion::new(2025, 4, 0));
+    assert!(meta.features().as_ref().is_some_and(|a| *a.local_timeline() && !*a.global_timeline()));
+    let nodeinfo = serde_json::from_str::<NodeInfo>(NODEINFO).unwrap();
+    assert_eq!(nodeinfo.software().name(), "sharkey");
+    assert_eq!(nodeinfo.usage().as_ref().and_then(|a| *a.users().active_month()), Some(120));
+}
+
+#[test]
+fn role_cond_formula() {
+    let formula = serde_json::from_str::<RoleCondFormula>(ROLE_COND_FORMULA).unwrap();
+    assert_eq!(formula.id(), "c0");
+    let RoleCondition::And { values } = formula.condition() else { panic!("unexpected formula: {:?}", formula) };
+    assert!(matches!(values[0].condition(), RoleCondition::IsLocal));
+    assert!(matches!(values[1].condition(), RoleCondition::Not { value } if matches!(value.condition(), RoleCondition::IsBot)));
+    let RoleCondition::Or { values: or } = values[2].condition() else { panic!("unexpected formula: {:?}", values[2]) };
+    assert!(matches!(or[0].condition(), RoleCondition::CreatedMoreThan { sec: 86400 }));
+    assert!(matches!(or[1].condition(), RoleCondition::RoleAssignedTo { role_id } if role_id == "9srd600000"));
+    assert!(matches!(values[3].condition(), RoleCondition::IsFromInstance { host, subdomains: true } if host == "example.com"));
+    assert!(matches!(values[4].condition(), RoleCondition::NotesMoreThanOrEq { value: 10 }));
+    assert!(matches!(values[5].condition(), RoleCondition::Other(body) if body["type"] == "someFutureCondition"));
+
+    let manual = serde_json::from_value::<RoleCondFormula>(json!({})).unwrap();
+    assert!(matches!(manual.condition(), RoleCondition::Other(body) if body.is_empty()));
+}
+
+fn drifts<T: std::fmt::Debug>(result: Result<T, StrictError>) -> Vec<SchemaDrift> {
+    match result {
+        Err(StrictError::Drift(drifts)) => drifts,
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
+
+#[test]
+fn extra_fields() {
+    let mut value = serde_json::from_str::<Value>(USER_LITE).unwrap();
+    value["newField"] = json!({ "nested": true });
+    let user = serde_json::from_value::<LiteUserInfo>(value.clone()).unwrap();
+    assert_eq!(user.extra().len(), 1);
+    assert_eq!(user.extra()["newField"], json!({ "nested": true }));
+    assert_eq!(serde_json::to_value(&user).unwrap()["newField"], value["newField"]);
+
+    let user = serde_json::from_str::<LiteUserInfo>(USER_LITE).unwrap();
+    assert!(user.extra().is_empty());
+    assert!(serde_json::to_value(&user).unwrap().get("extra").is_none());
+}
+
+#[test]
+fn strict_schema() {
+    from_str_strict::<Vec<NotificationInfo>>(NOTIFICATIONS_ALL).unwrap();
+
+    assert_eq!(drifts(from_str_strict::<NoteInfo>(NOTE)), [SchemaDrift::UnknownValue { type_name: "NoteVisibility", value: "circle".to_string() }]);
+    assert_eq!(drifts(from_str_strict::<RoleCondFormula>(ROLE_COND_FORMULA)), [SchemaDrift::UnknownValue { type_name: "RoleCondition", value: "someFutureCondition".to_string() }]);
+
+    let mut value = serde_json::from_str::<Value>(USER_LITE).unwrap();
+    value["onlineStatus"] = json!("online");
+    value["instance"] = json!({ "name": null, "softwareName": "misskey", "softwareVersion": null, "iconUrl": null, "faviconUrl": null, "themeColor": null, "newField": 1 });
+    value["newField"] = json!([]);
+    let found = drifts(from_str_strict::<LiteUserInfo>(&value.to_string()));
+    assert_eq!(found, [SchemaDrift::UnknownField { path: "/instance/newField".to_string() }, SchemaDrift::UnknownField { path: "/newField".to_string() }]);
+    assert_eq!(StrictError::Drift(found).to_string(), "schema drift detected:\n  unknown field `/instance/newField`\n  unknown field `/newField`");
+
+    value.as_object_mut().unwrap().remove("username");
+    assert!(matches!(from_str_strict::<LiteUserInfo>(&value.to_string()), Err(StrictError::SerdeError(_))));
+
+    // 省略されうるフィールドは存在しなくても差異として扱わず、それ以外は既定値であっても差異として扱う
+    let mut value = serde_json::from_str::<Value>(NOTE).unwrap();
+    value["visibility"] = json!("public");
+    value["reply"] = json!(null);
+    from_str_strict::<NoteInfo>(&value.to_string()).unwrap();
+    value.as_object_mut().unwrap().remove("reply");
+    from_str_strict::<NoteInfo>(&value.to_string()).unwrap();
+    value.as_object_mut().unwrap().remove("cw");
+    value.as_object_mut().unwrap().remove("reactionAcceptance");
+    value["user"].as_object_mut().unwrap().remove("avatarBlurhash");
+    assert_eq!(drifts(from_str_strict::<NoteInfo>(&value.to_string())), [
+        SchemaDrift::MissingField { path: "/cw".to_string() },
+        SchemaDrift::MissingField { path: "/user/avatarBlurhash".to_string() },
+        SchemaDrift::MissingField { path: "/reactionAcceptance".to_string() },
+    ]);
+
+    // 編集の履歴は履歴を公開するフォークでのみ含まれる
+    let mut value = serde_json::from_str::<Value>(NOTE).unwrap();
+    value["visibility"] = json!("public");
+    let note = from_str_strict::<NoteInfo>(&value.to_string()).unwrap();
+    assert!(note.updated_at().is_none() && note.note_edit_history().is_empty());
+    value["updatedAt"] = json!("2024-05-01T12:40:00.000Z");
+    value["updatedAtHistory"] = json!(["2024-05-01T12:40:00.000Z"]);
+    value["noteEditHistory"] = json!(["hello"]);
+    let note = from_str_strict::<NoteInfo>(&value.to_string()).unwrap();
+    assert_eq!(note.note_edit_history(), &["hello".to_string()]);
+    assert_eq!(note.updated_at_history().len(), 1);
+    assert_eq!(serde_json::to_value(&note).unwrap(), value);
+
+    // 検出中でなければ対応していない値があっても通常どおりデシリアライズできる
+    assert_eq!(serde_json::from_str::<LiteUserInfo>(USER_LITE).unwrap().online_status(), &OnlineStatus::Other("away".to_string()));
+}